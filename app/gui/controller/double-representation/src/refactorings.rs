@@ -6,5 +6,9 @@
 // ==============
 
 pub mod collapse;
+pub mod inline;
+pub mod rename;
 
 pub use collapse::collapse;
+pub use inline::inline;
+pub use rename::rename;
@@ -0,0 +1,522 @@
+//! Module with logic for inlining method calls.
+//!
+//! See the [`inline`] function for details.
+
+use crate::prelude::*;
+
+use crate::alias_analysis;
+use crate::definition::DefinitionInfo;
+use crate::definition::DefinitionName;
+use crate::graph::GraphInfo;
+use crate::identifier::generate_name;
+use crate::module;
+use crate::node;
+use crate::node::MainLine;
+use crate::node::NodeInfo;
+
+use ast::crumbs::Located;
+use ast::BlockLine;
+use parser::Parser;
+
+
+
+// ==================
+// === Inline API ===
+// ==================
+
+// === Entry point ===
+
+/// Run the "inline node" refactoring, being the inverse of [`collapse`](super::collapse).
+/// Generates output describing how to apply the refactoring.
+///
+/// "Inlining a node" means replacing a node calling a method defined in the same module with the
+/// lines of that method's body. The call arguments are substituted for the method parameters:
+/// identifiers and number literals are put directly in place of the parameter usages, while
+/// other expressions are bound to a new variable in a line preceding the inlined body.
+///
+/// The variables introduced by the inlined lines that clash with the names used in the refactored
+/// graph are renamed. The value returned by the method is assigned to the pattern of the inlined
+/// node, so the nodes using it remain connected.
+pub fn inline(
+    graph: &GraphInfo,
+    node: node::Id,
+    module: &module::Info,
+    module_name: &str,
+    parser: &Parser,
+) -> FallibleResult<Inlined> {
+    Inliner::new(graph.clone(), node, module, module_name, parser.clone_ref())?.inline()
+}
+
+/// Remove the definition of the inlined method from the module, unless it is still used somewhere
+/// in the module. Returns `true` if the definition has been removed.
+pub fn remove_if_unused(
+    module: &mut module::Info,
+    method: &DefinitionName,
+) -> FallibleResult<bool> {
+    let line_index = module::locate_line_with(&module.ast, method)?.line_index;
+    let name = method.name.item.as_str();
+    let is_used = module.ast.lines.iter().enumerate().any(|(index, line)| {
+        let uses_name =
+            |ast: &Ast| ast.iter_recursive().any(|ast| ast::identifier::name(ast) == Some(name));
+        index != line_index && line.elem.as_ref().contains_if(|ast| uses_name(ast))
+    });
+    if !is_used {
+        module.remove_line(line_index)?;
+        // The definitions are separated by blank lines. We remove the one following the removed
+        // definition, so no excessive blank lines are left.
+        let next_line_blank =
+            module.ast.lines.get(line_index).contains_if(|line| line.elem.is_none());
+        if next_line_blank {
+            module.remove_line(line_index)?;
+        }
+    }
+    Ok(!is_used)
+}
+
+
+// === Inlined ===
+
+/// Result of running node inline algorithm. Describes update to the refactored definition.
+#[derive(Clone, Debug)]
+pub struct Inlined {
+    /// New contents of the refactored definition.
+    pub updated_definition: DefinitionInfo,
+    /// Name of the method which body has been inlined.
+    pub inlined_method:     DefinitionName,
+    /// Identifiers of the nodes placed in the updated definition instead of the inlined node.
+    pub inlined_nodes:      Vec<node::Id>,
+}
+
+
+// === Errors ===
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Node `{}` is not a call to a method defined in the module.", _0)]
+pub struct NotAMethodCall(String);
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Cannot inline the call to method `{}` into its own body.", _0)]
+pub struct RecursiveInlining(String);
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(
+    display = "Currently inlining is supported only for methods with plain parameters without \
+default values. Method `{}` has parameter `{}`.",
+    _0, _1
+)]
+pub struct UnsupportedParameter(String, String);
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(
+    display = "The call to method `{}` does not provide exactly one argument per parameter.",
+    _0
+)]
+pub struct ArgumentsMismatch(String);
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(
+    display = "Cannot inline the call to method `{}`: its result is used, but the method's body \
+ends with an assignment.",
+    _0
+)]
+pub struct CannotReconnectOutput(String);
+
+
+
+// ===============
+// === Inliner ===
+// ===============
+
+/// Helper type that stores some common data used for inlining algorithm and implements its logic.
+#[derive(Clone, Debug)]
+pub struct Inliner {
+    /// The graph of definition where the node inlining takes place.
+    graph:     GraphInfo,
+    /// The inlined node.
+    node:      NodeInfo,
+    /// The definition of the called method.
+    method:    DefinitionInfo,
+    /// The call arguments, paired with the names of the parameters they are passed to, in the
+    /// order of the method's parameters.
+    arguments: Vec<(String, Ast)>,
+    parser:    Parser,
+}
+
+impl Inliner {
+    /// Does some early pre-processing: locates the inlined node and the called method, and matches
+    /// the call arguments with the method parameters.
+    pub fn new(
+        graph: GraphInfo,
+        node: node::Id,
+        module: &module::Info,
+        module_name: &str,
+        parser: Parser,
+    ) -> FallibleResult<Self> {
+        let id = node;
+        let node = graph.nodes().into_iter().find(|node| node.id() == id);
+        let node = node.ok_or(node::IdNotFound { id })?;
+        let expression = node.expression();
+        let not_a_call = || NotAMethodCall(expression.repr());
+        let chain = ast::prefix::Chain::from_ast_non_strict(&expression);
+        let method_name =
+            Self::called_method_name(&chain.func, module_name).ok_or_else(not_a_call)?;
+        if graph.source.name.item.name.item == method_name {
+            return Err(RecursiveInlining(method_name).into());
+        }
+        let method_crumb = DefinitionName::new_plain(&method_name);
+        let method = module::locate_child(&module.ast, &method_crumb).map_err(|_| not_a_call())?;
+        let method = method.item.clone();
+        let mut parameters = Vec::new();
+        for parameter in &method.args {
+            match ast::identifier::as_var(&parameter.item) {
+                Some(name) => parameters.push(name.to_owned()),
+                None => {
+                    let parameter = parameter.item.repr();
+                    return Err(UnsupportedParameter(method_name, parameter).into());
+                }
+            }
+        }
+        let call_args = chain.args.iter().map(|arg| &arg.sast.wrapped);
+        let arguments = Self::match_arguments(&parameters, call_args)
+            .ok_or_else(|| ArgumentsMismatch(method_name.clone()))?;
+        Ok(Inliner { graph, node, method, arguments, parser })
+    }
+
+    /// Get the name of the called method, if the given call target is either a plain method name
+    /// or a method name accessed on the module, like `Main.foo`.
+    fn called_method_name(func: &Ast, module_name: &str) -> Option<String> {
+        match ast::opr::to_access(func) {
+            Some(access) => {
+                let target_is_module = ast::identifier::name(&access.larg) == Some(module_name);
+                let name = ast::identifier::as_var(&access.rarg).filter(|_| target_is_module);
+                name.map(ToOwned::to_owned)
+            }
+            None => ast::identifier::as_var(func).map(ToOwned::to_owned),
+        }
+    }
+
+    /// Pair the call arguments with the method's parameters. Positional arguments are passed to
+    /// the parameters not passed by name, in order. Returns `None` if not every parameter gets
+    /// exactly one argument.
+    fn match_arguments<'a>(
+        parameters: &[String],
+        call_args: impl IntoIterator<Item = &'a Ast>,
+    ) -> Option<Vec<(String, Ast)>> {
+        let mut named = HashMap::new();
+        let mut positional = Vec::new();
+        for arg in call_args {
+            if let Some(named_arg) = ast::opr::match_named_argument(arg) {
+                let is_known = parameters.iter().any(|param| param == named_arg.name);
+                let previous = named.insert(named_arg.name.to_owned(), named_arg.rarg.clone_ref());
+                if !is_known || previous.is_some() {
+                    return None;
+                }
+            } else {
+                positional.push(arg.clone_ref());
+            }
+        }
+        let mut positional = positional.into_iter();
+        let arguments = parameters
+            .iter()
+            .map(|param| {
+                let arg = named.remove(param).or_else(|| positional.next())?;
+                Some((param.clone(), arg))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        positional.next().is_none().as_some(arguments)
+    }
+
+    /// Check if the argument can be put directly in place of the parameter usages.
+    fn is_simple_argument(arg: &Ast) -> bool {
+        ast::identifier::is_identifier(arg) || matches!(arg.shape(), ast::Shape::Number(_))
+    }
+
+    /// Generate the lines replacing the inlined node in the refactored definition.
+    fn inlined_lines(&self) -> FallibleResult<Vec<Ast>> {
+        let method_name = &self.method.name.item.name.item;
+        let graph_ast = self.graph.ast();
+        let mut taken: HashSet<String> = graph_ast
+            .iter_recursive()
+            .filter_map(|ast| ast::identifier::name(ast).map(ToOwned::to_owned))
+            .collect();
+        let mut take_name = |base: &str| -> FallibleResult<String> {
+            let name = if taken.contains(base) {
+                generate_name(base, taken.iter().cloned())?.to_string()
+            } else {
+                base.to_owned()
+            };
+            taken.insert(name.clone());
+            Ok(name)
+        };
+
+        // Substitutions of the parameters and of the renamed variables, by name.
+        let mut substitutions = HashMap::<String, Ast>::new();
+        let mut lines = Vec::new();
+        for (parameter, arg) in &self.arguments {
+            if Self::is_simple_argument(arg) {
+                substitutions.insert(parameter.clone(), arg.clone_ref());
+            } else {
+                let name = take_name(parameter)?;
+                lines.push(Ast::infix(
+                    Ast::var(&name),
+                    ast::opr::predefined::ASSIGNMENT,
+                    arg.clone_ref(),
+                ));
+                substitutions.insert(parameter.clone(), Ast::var(name));
+            }
+        }
+
+        let mut body: Vec<Ast> =
+            self.method.block_lines().into_iter().filter_map(|line| line.elem).collect();
+        let introduced = body.iter().flat_map(|line| alias_analysis::analyze_ast(line).introduced);
+        let introduced = introduced.map(|name| name.item).collect::<Vec<_>>();
+        // If the method returns one of its variables, we assign the result directly to that
+        // variable, instead of adding a separate line.
+        let returned_variable = body
+            .last()
+            .and_then(|line| ast::identifier::as_var(line))
+            .filter(|name| introduced.iter().any(|introduced| introduced == name))
+            .map(ToOwned::to_owned);
+        let pattern = self.node.pattern().cloned();
+        let pattern_var =
+            pattern.as_ref().and_then(|p| ast::identifier::as_var(p)).map(ToOwned::to_owned);
+        if let Some(returned) = &returned_variable {
+            if pattern.is_none() || pattern_var.is_some() {
+                body.pop();
+                if let Some(pattern_var) = &pattern_var {
+                    substitutions.insert(returned.clone(), Ast::var(pattern_var));
+                }
+            }
+        }
+        for name in introduced {
+            if !substitutions.contains_key(&name) {
+                let new_name = take_name(&name)?;
+                if new_name != name {
+                    substitutions.insert(name, Ast::var(new_name));
+                }
+            }
+        }
+
+        let body_len = body.len();
+        for (index, line) in body.into_iter().enumerate() {
+            let usage = alias_analysis::analyze_ast(&line);
+            let mut line = line;
+            for Located { crumbs, item } in usage.all_identifiers() {
+                if let Some(substitute) = substitutions.get(&item) {
+                    line = line.set_traversing(&crumbs, substitute.clone_ref())?;
+                }
+            }
+            let is_last = index + 1 == body_len;
+            let already_assigned = returned_variable.is_some() && pattern_var.is_some();
+            if let Some(pattern) = pattern.as_ref().filter(|_| is_last && !already_assigned) {
+                if ast::opr::is_assignment(&line) {
+                    return Err(CannotReconnectOutput(method_name.clone()).into());
+                }
+                let assignment = ast::opr::predefined::ASSIGNMENT;
+                line = Ast::infix(pattern.clone_ref(), assignment, line);
+            }
+            lines.push(line);
+        }
+        // The lines are reparsed, so all their nodes get fresh, unique IDs.
+        lines.iter().map(|line| self.parser.parse_line_ast(line.repr())).collect()
+    }
+
+    /// Run the inlining refactoring on this input.
+    pub fn inline(&self) -> FallibleResult<Inlined> {
+        let inlined_lines = self.inlined_lines()?;
+        let inlined_nodes = inlined_lines
+            .iter()
+            .filter_map(|line| MainLine::from_ast(line).map(|main_line| main_line.id()))
+            .collect();
+        let mut updated_definition = self.graph.source.clone();
+        let mut new_lines = Vec::new();
+        for line in updated_definition.block_lines() {
+            match line.elem.as_ref() {
+                // The documentation comment of the inlined node is removed with the node.
+                Some(ast) if self.node.contains_line(ast) => {
+                    if MainLine::from_ast(ast).contains_if(|n| n.id() == self.node.id()) {
+                        let inlined_lines = inlined_lines.iter().cloned();
+                        new_lines.extend(inlined_lines.map(|ast| BlockLine::new(Some(ast))));
+                    }
+                }
+                _ => new_lines.push(line),
+            }
+        }
+        updated_definition.set_block_lines(new_lines)?;
+        let inlined_method = self.method.name.item.clone();
+        Ok(Inlined { updated_definition, inlined_method, inlined_nodes })
+    }
+}
+
+
+
+// ============
+// === Test ===
+// ============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::graph;
+
+    use ast::crumbs::Crumb;
+
+    struct Case {
+        module_name:         &'static str,
+        refactored_name:     DefinitionName,
+        initial_module_code: &'static str,
+        inlined_line:        usize,
+        expected_refactored: &'static str,
+        expected_module:     &'static str,
+    }
+
+    impl Case {
+        fn run(&self, parser: &Parser) {
+            let ast = parser.parse_module(self.initial_module_code, default()).unwrap();
+            let main = module::locate_child(&ast, &self.refactored_name).unwrap();
+            let graph = graph::GraphInfo::from_definition(main.item.clone());
+            let node = graph.nodes()[self.inlined_line].id();
+            let mut module = module::Info { ast: ast.clone_ref() };
+            let inlined = inline(&graph, node, &module, self.module_name, parser).unwrap();
+            let new_main = &inlined.updated_definition.ast;
+            info!("Updated method:\n{new_main}");
+            let main_crumb = Crumb::from(main.crumb());
+            module.ast = module.ast.set(&main_crumb, new_main.ast().clone()).unwrap();
+            ast::test_utils::assert_unique_ids(module.ast.as_ref());
+            assert_eq!(new_main.repr(), self.expected_refactored);
+            let updated_graph = graph::GraphInfo::from_definition(inlined.updated_definition);
+            let updated_nodes = updated_graph.nodes().iter().map(|node| node.id()).collect_vec();
+            for inlined_node in &inlined.inlined_nodes {
+                assert!(updated_nodes.contains(inlined_node));
+            }
+            remove_if_unused(&mut module, &inlined.inlined_method).unwrap();
+            assert_eq!(module.ast.repr(), self.expected_module);
+        }
+    }
+
+    #[test]
+    fn test_inline() {
+        let parser = Parser::new();
+        let refactored_name = DefinitionName::new_plain("custom_old");
+
+        // Check that inlining is the inverse of collapsing: the returned variable is assigned
+        // directly, and the unused method is removed.
+        let initial_module_code = r"custom_new a =
+    b = 2
+    c = A + B
+    d = a + b
+    c
+
+custom_old =
+    a = 1
+    c = Main.custom_new a
+    c + 7";
+        let expected_refactored = r"custom_old =
+    a = 1
+    b = 2
+    c = A + B
+    d = a + b
+    c + 7";
+        let mut case = Case {
+            module_name: "Main",
+            refactored_name,
+            initial_module_code,
+            inlined_line: 1,
+            expected_refactored,
+            expected_module: expected_refactored,
+        };
+        case.run(&parser);
+
+        // Check that:
+        // 1) variables clashing with the refactored graph are renamed;
+        // 2) the returned expression is assigned to the node's pattern;
+        // 3) the method still used elsewhere is not removed.
+        case.initial_module_code = r"custom_new x =
+    b = x + 1
+    b * 2
+
+custom_old =
+    b = 10
+    c = Main.custom_new b
+    c + b
+
+other = Main.custom_new 1";
+        case.expected_refactored = r"custom_old =
+    b = 10
+    b1 = b + 1
+    c = b1 * 2
+    c + b";
+        case.expected_module = r"custom_new x =
+    b = x + 1
+    b * 2
+
+custom_old =
+    b = 10
+    b1 = b + 1
+    c = b1 * 2
+    c + b
+
+other = Main.custom_new 1";
+        case.run(&parser);
+
+        // Check that:
+        // 1) method with inline body can be inlined;
+        // 2) complex arguments are bound to variables, and named arguments are supported.
+        case.initial_module_code = r"custom_new a b = a + b
+
+custom_old =
+    x = 1
+    y = custom_new (x + 1) b=x
+    y";
+        case.expected_refactored = r"custom_old =
+    x = 1
+    a = (x + 1)
+    y = a + x
+    y";
+        case.expected_module = case.expected_refactored;
+        case.run(&parser);
+
+        // Check that the returned variable is dropped when the node has no pattern.
+        case.initial_module_code = r"custom_new =
+    c = 50 + d
+    c
+
+custom_old =
+    custom_new
+    10";
+        case.expected_refactored = r"custom_old =
+    c = 50 + d
+    10";
+        case.expected_module = case.expected_refactored;
+        case.inlined_line = 0;
+        case.run(&parser);
+    }
+
+    #[test]
+    fn inlining_unsupported_nodes() {
+        let parser = Parser::new();
+        let code = r"custom_new a = a + 1
+
+custom_old =
+    a = 1
+    b = custom_new a a
+    custom_old
+    Other.custom_new a";
+        let ast = parser.parse_module(code, default()).unwrap();
+        let module = module::Info { ast: ast.clone_ref() };
+        let main = module::locate_child(&ast, &DefinitionName::new_plain("custom_old")).unwrap();
+        let graph = graph::GraphInfo::from_definition(main.item.clone());
+        let nodes = graph.nodes();
+        let run = |index: usize| inline(&graph, nodes[index].id(), &module, "Main", &parser);
+        assert!(run(0).is_err()); // Not a call.
+        assert!(run(1).is_err()); // Too many arguments.
+        assert!(run(2).is_err()); // Recursive call.
+        assert!(run(3).is_err()); // Method of other module.
+    }
+}
@@ -0,0 +1,226 @@
+//! Module with logic for renaming variables.
+//!
+//! See the [`rename`] function for details.
+
+use crate::prelude::*;
+
+use crate::alias_analysis;
+use crate::definition::DefinitionInfo;
+use crate::identifier::Identifier;
+
+use ast::crumbs::Located;
+use ast::BlockLine;
+
+
+
+// ==================
+// === Rename API ===
+// ==================
+
+// === Entry point ===
+
+/// Run the "rename variable" refactoring. Returns the updated definition, where the variable
+/// introduced by one of the definition's nodes is renamed at its introduction site and at every
+/// use site.
+///
+/// The use sites are found with alias analysis, so the usages in nested lambdas and blocks are
+/// renamed as well, unless the variable is shadowed there by a local one with the same name.
+///
+/// Fails if the new name is not a valid variable name, or if it is already used anywhere in the
+/// definition, as renaming would then change the meaning of the code.
+pub fn rename(
+    definition: &DefinitionInfo,
+    old_name: &str,
+    new_name: &str,
+) -> FallibleResult<DefinitionInfo> {
+    let is_var_name = Identifier::from_text(new_name)
+        .ok()
+        .contains_if(|identifier| ast::identifier::as_var(identifier).is_some());
+    if !is_var_name {
+        return Err(NotAVariableName(new_name.to_owned()).into());
+    }
+    if old_name == new_name {
+        return Ok(definition.clone());
+    }
+    let definition_ast = Ast::from(definition.ast.clone_ref());
+    let is_used = |ast: &Ast| ast::identifier::name(ast) == Some(new_name);
+    if definition_ast.iter_recursive().any(is_used) {
+        return Err(NameAlreadyUsed(new_name.to_owned()).into());
+    }
+
+    let mut is_introduced = false;
+    let mut new_lines = Vec::new();
+    for line in definition.block_lines() {
+        let elem = match line.elem {
+            Some(ast) => {
+                let usage = alias_analysis::analyze_ast(&ast);
+                let introduces = |name: &Located<String>| name.item == old_name;
+                is_introduced |= usage.introduced.iter().any(introduces);
+                Some(rename_in_line(ast, &usage.all_identifiers(), old_name, new_name)?)
+            }
+            None => None,
+        };
+        new_lines.push(BlockLine { elem, off: line.off });
+    }
+    if !is_introduced {
+        return Err(NameNotIntroduced(old_name.to_owned()).into());
+    }
+    let mut updated_definition = definition.clone();
+    updated_definition.set_block_lines(new_lines)?;
+    Ok(updated_definition)
+}
+
+/// Rename the given identifiers of the line, if they have the old name. The renamed identifiers
+/// keep their IDs.
+fn rename_in_line(
+    line: Ast,
+    identifiers: &[Located<String>],
+    old_name: &str,
+    new_name: &str,
+) -> FallibleResult<Ast> {
+    let mut line = line;
+    for identifier in identifiers.iter().filter(|identifier| identifier.item == old_name) {
+        let old_ast = line.get_traversing(&identifier.crumbs)?;
+        let new_ast = Ast::new(ast::Var { name: new_name.to_owned() }, old_ast.id);
+        line = line.set_traversing(&identifier.crumbs, new_ast)?;
+    }
+    Ok(line)
+}
+
+
+// === Errors ===
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "`{}` is not a valid variable name.", _0)]
+pub struct NotAVariableName(String);
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Cannot rename to `{}`, as this name is already used in the definition.", _0)]
+pub struct NameAlreadyUsed(String);
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "The variable `{}` is not introduced by any node of the definition.", _0)]
+pub struct NameNotIntroduced(String);
+
+
+
+// ============
+// === Test ===
+// ============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::definition::DefinitionName;
+    use crate::module;
+
+    use parser::Parser;
+
+    struct Case {
+        initial_method_code: &'static str,
+        old_name:            &'static str,
+        new_name:            &'static str,
+        expected_renamed:    &'static str,
+    }
+
+    impl Case {
+        fn run(&self, parser: &Parser) -> FallibleResult<DefinitionInfo> {
+            let ast = parser.parse_module(self.initial_method_code, default()).unwrap();
+            let name = DefinitionName::new_plain("main");
+            let main = module::locate_child(&ast, &name).unwrap();
+            rename(&main.item, self.old_name, self.new_name)
+        }
+
+        fn check(&self, parser: &Parser) {
+            let renamed = self.run(parser).unwrap();
+            ast::test_utils::assert_unique_ids(renamed.ast.as_ref());
+            assert_eq!(renamed.ast.repr(), self.expected_renamed);
+        }
+    }
+
+    #[test]
+    fn test_rename() {
+        let parser = Parser::new();
+        let mut case = Case {
+            initial_method_code: r"main =
+    a = 10
+    b = a + 1
+    a * b",
+            old_name:            "a",
+            new_name:            "number",
+            expected_renamed:    r"main =
+    number = 10
+    b = number + 1
+    number * b",
+        };
+        case.check(&parser);
+
+        // Check that the usages in nested lambdas and blocks are renamed, unless shadowed.
+        case.initial_method_code = r"main =
+    a = 10
+    f = x -> x + a
+    g = a -> a + 1
+    h =
+        c = a + 2
+        c
+    f a";
+        case.expected_renamed = r"main =
+    number = 10
+    f = x -> x + number
+    g = a -> a + 1
+    h =
+        c = number + 2
+        c
+    f number";
+        case.check(&parser);
+
+        // Check that the IDs of the renamed nodes are preserved.
+        case.initial_method_code = r"main =
+    a = 10
+    a";
+        let ast = parser.parse_module(case.initial_method_code, default()).unwrap();
+        let main = module::locate_child(&ast, &DefinitionName::new_plain("main")).unwrap();
+        let ids = |definition: &DefinitionInfo| {
+            crate::graph::GraphInfo::from_definition(definition.clone())
+                .nodes()
+                .iter()
+                .map(|node| node.id())
+                .collect_vec()
+        };
+        let renamed = rename(&main.item, "a", "number").unwrap();
+        assert_eq!(ids(&main.item), ids(&renamed));
+    }
+
+    #[test]
+    fn rejected_renames() {
+        let parser = Parser::new();
+        let mut case = Case {
+            initial_method_code: r"main =
+    a = 10
+    b = 20
+    f = x -> x + a
+    a + b",
+            old_name:            "a",
+            new_name:            "b",
+            expected_renamed:    "",
+        };
+        // The name is used by another node.
+        assert!(case.run(&parser).is_err());
+        // The name is used by a lambda argument.
+        case.new_name = "x";
+        assert!(case.run(&parser).is_err());
+        // Not a variable name.
+        case.new_name = "Foo";
+        assert!(case.run(&parser).is_err());
+        case.new_name = "+";
+        assert!(case.run(&parser).is_err());
+        // The renamed variable is not introduced by any node.
+        case.old_name = "c";
+        case.new_name = "d";
+        assert!(case.run(&parser).is_err());
+    }
+}
@@ -0,0 +1,563 @@
+//! Capturing the traffic of the Language Server connections, and replaying it.
+//!
+//! The [`RecordingTransport`] decorates the transport of a connection, writing each frame sent
+//! and received to a [`Recorder`]. The recorder writes to any [`Write`] sink, like a file or an
+//! in-memory [`Buffer`] when there is no file system, as in the browser. The text and binary
+//! connections may share the recorder, so a single capture contains the whole communication with
+//! the engine.
+//!
+//! The [`ReplayTransport`] plays a capture back, acting as a deterministic fake server: the
+//! received frames are emitted after the preceding sent frames, regardless of their contents and
+//! timestamps. The frames of each connection are replayed independently, so a capture attached to
+//! a bug report reproduces the IDE-side state of `language_server::Connection` and
+//! `binary::Client` offline.
+
+use crate::prelude::*;
+
+use crate::binary::message::MessageFromServerOwned;
+use crate::binary::message::MessageToServerOwned;
+
+use chrono::DateTime;
+use chrono::Utc;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
+use json_rpc::messages::Id;
+use json_rpc::Transport;
+use json_rpc::TransportEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::io::Write;
+
+
+
+// =============
+// === Frame ===
+// =============
+
+/// The connection a frame was transferred through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Channel {
+    /// The text connection used to transfer JSON messages.
+    Json,
+    /// The binary connection used to transfer FlatBuffers messages.
+    Binary,
+}
+
+/// The direction in which a frame was transferred.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Direction {
+    /// Received from the server.
+    Inbound,
+    /// Sent to the server.
+    Outbound,
+}
+
+/// The contents of a frame.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub enum Payload {
+    Text(String),
+    /// Serialized as a hex string.
+    Binary(#[serde(with = "hex_bytes")] Vec<u8>),
+    /// The connection has been opened.
+    Opened,
+    /// The connection has been closed.
+    Closed,
+}
+
+impl Payload {
+    /// The payload of the transport event, if it is captured.
+    fn from_event(event: &TransportEvent) -> Option<Self> {
+        match event {
+            TransportEvent::TextMessage(text) => Some(Payload::Text(text.clone())),
+            TransportEvent::BinaryMessage(data) => Some(Payload::Binary(data.clone())),
+            TransportEvent::Opened => Some(Payload::Opened),
+            TransportEvent::Closed => Some(Payload::Closed),
+            TransportEvent::Reconnecting { .. } | TransportEvent::Reconnected { .. } => None,
+        }
+    }
+
+    fn into_event(self) -> TransportEvent {
+        match self {
+            Payload::Text(text) => TransportEvent::TextMessage(text),
+            Payload::Binary(data) => TransportEvent::BinaryMessage(data),
+            Payload::Opened => TransportEvent::Opened,
+            Payload::Closed => TransportEvent::Closed,
+        }
+    }
+}
+
+/// A single captured frame.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frame {
+    /// The time of sending or receiving the frame.
+    pub time:      DateTime<Utc>,
+    #[allow(missing_docs)]
+    pub channel:   Channel,
+    #[allow(missing_docs)]
+    pub direction: Direction,
+    #[allow(missing_docs)]
+    pub payload:   Payload,
+}
+
+impl Frame {
+    /// Create a frame transferred now.
+    pub fn new(channel: Channel, direction: Direction, payload: Payload) -> Self {
+        Frame { time: Utc::now(), channel, direction, payload }
+    }
+}
+
+mod hex_bytes {
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        hex::decode(text).map_err(serde::de::Error::custom)
+    }
+}
+
+
+
+// =================
+// === Recording ===
+// =================
+
+/// A capture of the connections' traffic. It is stored in the JSON Lines format: one JSON-encoded
+/// [`Frame`] per line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recording {
+    /// The captured frames, in the order of transferring.
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    /// Read the recording written by a [`Recorder`].
+    pub fn load(reader: impl BufRead) -> FallibleResult<Self> {
+        let lines = reader.lines().filter(|line| !matches!(line, Ok(line) if line.is_empty()));
+        let frames = lines.map(|line| Ok(serde_json::from_str(&line?)?));
+        Ok(Recording { frames: frames.collect::<FallibleResult<_>>()? })
+    }
+
+    /// The frames transferred through the given connection, in the order of transferring.
+    pub fn frames_of(&self, channel: Channel) -> impl Iterator<Item = &Frame> {
+        self.frames.iter().filter(move |frame| frame.channel == channel)
+    }
+}
+
+
+
+// ================
+// === Recorder ===
+// ================
+
+/// Writes the captured frames to a sink, like a file. Can be shared by many connections.
+#[derive(Clone, CloneRef)]
+pub struct Recorder {
+    sink: Rc<RefCell<Box<dyn Write>>>,
+}
+
+impl Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Recorder")
+    }
+}
+
+impl Recorder {
+    /// Constructor.
+    pub fn new(sink: impl Write + 'static) -> Self {
+        let sink: Box<dyn Write> = Box::new(sink);
+        Recorder { sink: Rc::new(RefCell::new(sink)) }
+    }
+
+    /// Write the frame. The failures are logged, as they should not break the connection.
+    pub fn record(&self, frame: &Frame) {
+        let line = serde_json::to_string(frame).expect("Frames are always serializable.");
+        let mut sink = self.sink.borrow_mut();
+        if let Err(error) = writeln!(sink, "{line}").and_then(|()| sink.flush()) {
+            error!("Failed to record a frame: {error}");
+        }
+    }
+}
+
+
+
+// ==============
+// === Buffer ===
+// ==============
+
+/// An in-memory sink for the [`Recorder`], shared by its clones. The capture can be read back to
+/// be saved by other means.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct Buffer {
+    data: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Buffer {
+    /// The data written so far.
+    pub fn contents(&self) -> Vec<u8> {
+        self.data.borrow().clone()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        self.data.borrow_mut().write(data)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+
+
+// ==========================
+// === RecordingTransport ===
+// ==========================
+
+/// A `Transport` decorator recording all the frames transferred through the wrapped transport.
+///
+/// The received frames are recorded and passed on by the `runner` future, which should be passed
+/// to the executor.
+#[derive(Debug)]
+pub struct RecordingTransport<T> {
+    transport:    T,
+    channel:      Channel,
+    recorder:     Recorder,
+    /// The events of the wrapped transport, until taken by the runner.
+    inner_events: Rc<RefCell<Option<UnboundedReceiver<TransportEvent>>>>,
+    /// The receiver of the passed on events.
+    outer_events: Rc<RefCell<Option<UnboundedSender<TransportEvent>>>>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// Constructor. The frames are recorded as transferred through the given channel.
+    pub fn new(mut transport: T, channel: Channel, recorder: &Recorder) -> Self {
+        let inner_events = Rc::new(RefCell::new(Some(transport.establish_event_stream())));
+        let outer_events = default();
+        let recorder = recorder.clone_ref();
+        RecordingTransport { transport, channel, recorder, inner_events, outer_events }
+    }
+
+    /// Returns a `Future` recording the events of the wrapped transport and passing them on. It
+    /// finishes when the wrapped transport is dropped.
+    pub fn runner(&self) -> impl Future<Output = ()> {
+        let events = self.inner_events.borrow_mut().take();
+        let events = events.expect("The runner of `RecordingTransport` can be created only once.");
+        let channel = self.channel;
+        let recorder = self.recorder.clone_ref();
+        let outer_events = self.outer_events.clone_ref();
+        events.for_each(move |event| {
+            if let Some(payload) = Payload::from_event(&event) {
+                recorder.record(&Frame::new(channel, Direction::Inbound, payload));
+            }
+            if let Some(transmitter) = outer_events.borrow().as_ref() {
+                channel::emit(transmitter, event);
+            }
+            futures::future::ready(())
+        })
+    }
+
+    fn record_outbound(&self, payload: Payload) {
+        self.recorder.record(&Frame::new(self.channel, Direction::Outbound, payload));
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn send_text(&mut self, message: &str) -> Result<(), failure::Error> {
+        self.record_outbound(Payload::Text(message.to_owned()));
+        self.transport.send_text(message)
+    }
+
+    fn send_binary(&mut self, message: &[u8]) -> Result<(), failure::Error> {
+        self.record_outbound(Payload::Binary(message.to_vec()));
+        self.transport.send_binary(message)
+    }
+
+    fn set_event_transmitter(&mut self, transmitter: UnboundedSender<TransportEvent>) {
+        *self.outer_events.borrow_mut() = Some(transmitter);
+    }
+
+    fn forget_request(&mut self, id: json_rpc::messages::Id) {
+        self.transport.forget_request(id)
+    }
+}
+
+
+
+// ===================
+// === ReplayError ===
+// ===================
+
+#[allow(missing_docs)]
+#[derive(Debug, Fail)]
+pub enum ReplayError {
+    #[fail(display = "The recording has no more frames sent by the client.")]
+    RecordingFinished,
+    #[fail(display = "Expected the client to send a {:?} frame.", _0)]
+    UnexpectedFrame(Payload),
+}
+
+
+
+// =======================
+// === ReplayTransport ===
+// =======================
+
+/// A `Transport` playing a recording back, as a fake server.
+///
+/// Each frame sent by the client is matched with the next recorded outbound frame, and then the
+/// recorded inbound frames up to the next outbound one are emitted. The contents of the sent
+/// frames are not compared with the recorded ones, with one exception: the ids of the sent
+/// messages may differ from the recorded ones, so the replies are rewritten to correlate with the
+/// messages actually sent. This applies both to the random ids of the binary messages, and the ids
+/// of the JSON-RPC requests.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    channel:           Channel,
+    frames:            VecDeque<Frame>,
+    event_transmitter: Option<UnboundedSender<TransportEvent>>,
+    /// The ids of the sent binary messages, by the ids of the recorded ones.
+    message_ids:       HashMap<Uuid, Uuid>,
+    /// The ids of the sent JSON-RPC requests, by the ids of the recorded ones.
+    request_ids:       HashMap<Id, Id>,
+}
+
+impl ReplayTransport {
+    /// Constructor. Replays the frames of the given channel.
+    pub fn new(recording: &Recording, channel: Channel) -> Self {
+        let frames = recording.frames_of(channel).cloned().collect();
+        let event_transmitter = None;
+        let message_ids = default();
+        let request_ids = default();
+        ReplayTransport { channel, frames, event_transmitter, message_ids, request_ids }
+    }
+
+    /// Check if all the recorded frames have been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Emits the recorded inbound frames up to the next outbound one.
+    fn replay_inbound(&mut self) {
+        let Some(transmitter) = self.event_transmitter.clone() else { return };
+        while self.frames.front().map_or(false, |frame| frame.direction == Direction::Inbound) {
+            if let Some(frame) = self.frames.pop_front() {
+                let payload = match frame.payload {
+                    Payload::Text(text) => Payload::Text(self.correlate_text(text)),
+                    Payload::Binary(data) => Payload::Binary(self.correlate_binary(data)),
+                    payload => payload,
+                };
+                channel::emit(&transmitter, payload.into_event());
+            }
+        }
+    }
+
+    /// Matches the sent frame with the next recorded outbound one, and replays the server's
+    /// response.
+    fn replay_outbound(&mut self, sent: Payload) -> FallibleResult {
+        let recorded = self.frames.pop_front().ok_or(ReplayError::RecordingFinished)?;
+        match (&recorded.payload, &sent) {
+            (Payload::Text(recorded), Payload::Text(sent)) => self.note_request_id(recorded, sent),
+            (Payload::Binary(recorded), Payload::Binary(sent)) =>
+                self.note_message_id(recorded, sent),
+            _ => {
+                warn!("Replaying {:?} connection diverged from the recording.", self.channel);
+                return Err(ReplayError::UnexpectedFrame(recorded.payload).into());
+            }
+        }
+        self.replay_inbound();
+        Ok(())
+    }
+
+    fn note_message_id(&mut self, recorded: &[u8], sent: &[u8]) {
+        let recorded = MessageToServerOwned::deserialize(recorded);
+        let sent = MessageToServerOwned::deserialize(sent);
+        if let (Ok(recorded), Ok(sent)) = (recorded, sent) {
+            self.message_ids.insert(recorded.message_id, sent.message_id);
+        }
+    }
+
+    fn note_request_id(&mut self, recorded: &str, sent: &str) {
+        let recorded = serde_json::from_str(recorded).ok().and_then(|msg| request_id(&msg));
+        let sent = serde_json::from_str(sent).ok().and_then(|msg| request_id(&msg));
+        if let (Some(recorded), Some(sent)) = (recorded, sent) {
+            self.request_ids.insert(recorded, sent);
+        }
+    }
+
+    /// Rewrites the id of the recorded JSON-RPC response to the id of the actually sent request.
+    fn correlate_text(&self, text: String) -> String {
+        let Ok(mut message) = serde_json::from_str::<serde_json::Value>(&text) else { return text };
+        let is_response = message.get("method").is_none();
+        let correlated = request_id(&message).and_then(|id| self.request_ids.get(&id));
+        match correlated {
+            Some(id) if is_response => {
+                message["id"] = serde_json::json!(id);
+                message.to_string()
+            }
+            _ => text,
+        }
+    }
+
+    /// Rewrites the correlation id of the recorded binary reply to the id of the actually sent
+    /// message.
+    fn correlate_binary(&self, data: Vec<u8>) -> Vec<u8> {
+        let Ok(mut message) = MessageFromServerOwned::deserialize(&data) else { return data };
+        let correlated = message.correlation_id.and_then(|id| self.message_ids.get(&id));
+        match correlated {
+            Some(id) => {
+                message.correlation_id = Some(*id);
+                message.with_serialized(|data| data.to_vec())
+            }
+            None => data,
+        }
+    }
+}
+
+/// The id of the JSON-RPC request or response. The notifications have no id.
+fn request_id(message: &serde_json::Value) -> Option<Id> {
+    serde_json::from_value(message.get("id")?.clone()).ok()
+}
+
+impl Transport for ReplayTransport {
+    fn send_text(&mut self, message: &str) -> Result<(), failure::Error> {
+        self.replay_outbound(Payload::Text(message.to_owned()))
+    }
+
+    fn send_binary(&mut self, message: &[u8]) -> Result<(), failure::Error> {
+        self.replay_outbound(Payload::Binary(message.to_vec()))
+    }
+
+    fn set_event_transmitter(&mut self, transmitter: UnboundedSender<TransportEvent>) {
+        self.event_transmitter = Some(transmitter);
+        self.replay_inbound();
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::binary;
+    use crate::binary::message::FromServerPayloadOwned;
+    use crate::binary::message::MessageFromServer;
+    use crate::binary::message::MessageToServer;
+    use crate::binary::message::ToServerPayloadOwned;
+    use crate::language_server;
+    use crate::language_server::API as _;
+
+    use futures::task::LocalSpawnExt;
+    use json_rpc::messages::Message;
+    use json_rpc::messages::RequestMessage;
+    use json_rpc::test_util::transport::mock::MockTransport;
+
+    #[test]
+    fn recording_and_replaying_json_connection() {
+        let buffer = Buffer::default();
+        let recorder = Recorder::new(buffer.clone_ref());
+        let mut server = MockTransport::new();
+        let transport = RecordingTransport::new(server.clone(), Channel::Json, &recorder);
+        let mut pool = futures::executor::LocalPool::new();
+        pool.spawner().spawn_local(transport.runner()).unwrap();
+        let client = language_server::Client::new(transport);
+        pool.spawner().spawn_local(client.runner()).unwrap();
+
+        let path = language_server::Path::new(default(), &["Main.enso"]);
+        let mut fut = client.close_text_file(&path);
+        let request = server.expect_json_message::<RequestMessage<serde_json::Value>>();
+        server.mock_peer_json_message(Message::new_success(request.id, ()));
+        pool.run_until_stalled();
+        fut.expect_ok();
+
+        let recording = Recording::load(buffer.contents().as_slice()).unwrap();
+        let directions = recording.frames.iter().map(|frame| frame.direction).collect_vec();
+        assert_eq!(directions, vec![Direction::Outbound, Direction::Inbound]);
+        assert!(recording.frames.iter().all(|frame| frame.channel == Channel::Json));
+
+        let replay = ReplayTransport::new(&recording, Channel::Json);
+        let client = language_server::Client::new(replay);
+        pool.spawner().spawn_local(client.runner()).unwrap();
+        let mut fut = client.close_text_file(&path);
+        pool.run_until_stalled();
+        fut.expect_ok();
+        // The recording has no more frames to replay.
+        let mut fut = client.close_text_file(&path);
+        pool.run_until_stalled();
+        fut.expect_err();
+    }
+
+    #[test]
+    fn replaying_binary_connection() {
+        let recorded_request =
+            MessageToServer::new(ToServerPayloadOwned::InitSession { client_id: Uuid::new_v4() });
+        let mut recorded_reply = MessageFromServer::new(FromServerPayloadOwned::Success {});
+        recorded_reply.correlation_id = Some(recorded_request.message_id);
+        let request = Payload::Binary(recorded_request.with_serialized(<[u8]>::to_vec));
+        let reply = Payload::Binary(recorded_reply.with_serialized(<[u8]>::to_vec));
+        let frames = vec![
+            Frame::new(Channel::Json, Direction::Inbound, Payload::Opened),
+            Frame::new(Channel::Binary, Direction::Outbound, request),
+            Frame::new(Channel::Binary, Direction::Inbound, reply),
+        ];
+        let recording = Recording { frames };
+
+        let replay = ReplayTransport::new(&recording, Channel::Binary);
+        let client = binary::Client::new(replay);
+        let mut pool = futures::executor::LocalPool::new();
+        pool.spawner().spawn_local(client.runner()).unwrap();
+        // The sent message has a different id than the recorded one.
+        let mut fut = client.init(Uuid::new_v4());
+        pool.run_until_stalled();
+        fut.expect_ok();
+    }
+
+    #[test]
+    fn replaying_json_connection_with_other_request_ids() {
+        let request = r#"{"jsonrpc":"2.0","id":7,"method":"text/closeFile","params":{}}"#;
+        let reply = r#"{"jsonrpc":"2.0","id":7,"result":null}"#;
+        let frames = vec![
+            Frame::new(Channel::Json, Direction::Outbound, Payload::Text(request.into())),
+            Frame::new(Channel::Json, Direction::Inbound, Payload::Text(reply.into())),
+        ];
+        let recording = Recording { frames };
+
+        let replay = ReplayTransport::new(&recording, Channel::Json);
+        let client = language_server::Client::new(replay);
+        let mut pool = futures::executor::LocalPool::new();
+        pool.spawner().spawn_local(client.runner()).unwrap();
+        // The sent request has the id 0, and the reply is rewritten to correlate with it.
+        let path = language_server::Path::new(default(), &["Main.enso"]);
+        let mut fut = client.close_text_file(&path);
+        pool.run_until_stalled();
+        fut.expect_ok();
+    }
+
+    #[test]
+    fn serializing_frames() {
+        let frame = Frame::new(Channel::Binary, Direction::Inbound, Payload::Binary(vec![1, 255]));
+        let json = serde_json::to_value(&frame).unwrap();
+        assert_eq!(json["payload"], serde_json::json!({ "kind": "binary", "data": "01ff" }));
+        let mut text = serde_json::to_string(&frame).unwrap();
+        text.push_str("\n\n");
+        let recording = Recording::load(text.as_bytes()).unwrap();
+        assert_eq!(recording.frames, vec![frame]);
+    }
+}
@@ -7,6 +7,12 @@
 pub enum Event<N> {
     /// The handler's transport has been closed.
     Closed,
+    /// The handler's transport is trying to reestablish the lost connection. The attempts are
+    /// numbered from 1.
+    #[allow(missing_docs)]
+    Reconnecting { attempt: usize },
+    /// The handler's transport has reestablished the lost connection.
+    Reconnected,
     /// An error has occurred.
     Error(failure::Error),
     /// A notification has been received.
@@ -138,6 +138,13 @@ where
                 }
                 TransportEvent::Opened => {}
                 TransportEvent::Closed => self.emit_event(Event::Closed),
+                TransportEvent::Reconnecting { attempt } => {
+                    // The `ReconnectingTransport` resends only the text requests, so the requests
+                    // of this handler will not get a reply.
+                    self.ongoing_calls.clear();
+                    self.emit_event(Event::Reconnecting { attempt })
+                }
+                TransportEvent::Reconnected { .. } => self.emit_event(Event::Reconnected),
             }
         });
     }
@@ -302,4 +309,27 @@ mod tests {
         assert!(matches!(event, Event::Closed), "Event was: {event:?}");
         events.expect_pending();
     }
+
+    #[test]
+    fn test_reconnecting_event_passing() {
+        let transport = MockTransport::new();
+        let processor = |msg| panic!("Must never be called in this test, but got {msg:?}!");
+        let handler = Handler::<i32, (), ()>::new(transport, processor);
+        let mut events = handler.event_stream().boxed_local();
+        let request = handler.state.borrow_mut().ongoing_calls.open_new_request(1, Ok);
+        let mut request = request.boxed_local();
+        request.expect_pending();
+
+        handler.state.borrow_mut().process_event(TransportEvent::Reconnecting { attempt: 1 });
+        let event = events.expect_next();
+        assert!(matches!(event, Event::Reconnecting { attempt: 1 }), "Event was: {event:?}");
+        // The request was sent through the lost connection, so it will not get a reply.
+        request.expect_err();
+
+        let reconnected = TransportEvent::Reconnected { dropped_requests: vec![] };
+        handler.state.borrow_mut().process_event(reconnected);
+        let event = events.expect_next();
+        assert!(matches!(event, Event::Reconnected), "Event was: {event:?}");
+        events.expect_pending();
+    }
 }
@@ -25,3 +25,19 @@ pub enum ErrorCodes {
     /// Note that timeout can also happen on the client side, as part of the Handler's logic.
     Timeout             = 11,
 }
+
+/// The methods which do not change the Language Server's state, so they may be safely sent again
+/// when the connection was lost before receiving their response.
+pub const READ_ONLY_METHODS: &[&str] = &[
+    "file/exists",
+    "file/list",
+    "file/read",
+    "file/info",
+    "file/checksum",
+    "search/getSuggestionsDatabase",
+    "search/getSuggestionsDatabaseVersion",
+    "search/completion",
+    "executionContext/getComponentGroups",
+    "vcs/list",
+    "vcs/status",
+];
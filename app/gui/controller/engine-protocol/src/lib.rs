@@ -28,6 +28,7 @@
 // ==============
 
 pub mod binary;
+pub mod capture;
 pub mod common;
 pub mod generated;
 pub mod handler;
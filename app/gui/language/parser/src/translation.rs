@@ -30,7 +30,7 @@ pub fn tree_to_ast(mut tree: &Tree, ids: BTreeMap<(usize, usize), uuid::Uuid>) -
         match &*tree.variant {
             tree::Variant::BodyBlock(block) => break context.translate_module(block),
             tree::Variant::Invalid(tree::Invalid { ast, error }) => {
-                warn!("Parser reports invalid module: {}", error.message);
+                warn!("Parser reports invalid module: {}", error);
                 tree = ast
             }
             _ => unreachable!("enso_parser always returns a tree with a BodyBlock as root."),
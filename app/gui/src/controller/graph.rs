@@ -17,7 +17,9 @@ use double_representation::definition;
 use double_representation::definition::DefinitionProvider;
 use double_representation::graph::GraphInfo;
 use double_representation::identifier::generate_name;
+use double_representation::import::ImportedNames;
 use double_representation::module;
+use double_representation::name::QualifiedName;
 use double_representation::node;
 use double_representation::node::MainLine;
 use double_representation::node::NodeAst;
@@ -36,7 +38,9 @@ use span_tree::SpanTree;
 // === Export ===
 // ==============
 
+pub mod clipboard;
 pub mod executed;
+pub mod layout;
 pub mod widget;
 
 pub use double_representation::graph::Id;
@@ -951,6 +955,48 @@ impl Handle {
         )
     }
 
+    /// Arrange all nodes of the graph with the automatic layout, in a single transaction. The
+    /// sizes of the nodes are provided by the view. See [`layout::Layout::tidy`].
+    #[profile(Task)]
+    pub fn tidy_layout(&self, node_sizes: &HashMap<node::Id, Vector2>) -> FallibleResult {
+        let _transaction_guard = self.get_or_open_transaction("Tidy graph layout");
+        let graph = self.graph_info()?;
+        let current_positions = self.node_positions(&graph);
+        let positions = layout::Layout::new(&graph, node_sizes).tidy(&current_positions);
+        for (id, position) in positions {
+            self.set_node_position(id, position)?;
+        }
+        Ok(())
+    }
+
+    /// Place the node with the automatic layout, without moving any other node, in a single
+    /// transaction. The sizes of the nodes are provided by the view. Returns the node's new
+    /// position. See [`layout::Layout::place_new_node`].
+    #[profile(Task)]
+    pub fn place_node(
+        &self,
+        id: node::Id,
+        node_sizes: &HashMap<node::Id, Vector2>,
+    ) -> FallibleResult<model::module::Position> {
+        let _transaction_guard = self.get_or_open_transaction("Place node");
+        let graph = self.graph_info()?;
+        let current_positions = self.node_positions(&graph);
+        let layout = layout::Layout::new(&graph, node_sizes);
+        let position = layout.place_new_node(id, &current_positions).ok_or(NodeNotFound(id))?;
+        self.set_node_position(id, position)?;
+        Ok(position)
+    }
+
+    /// The positions of the graph nodes which have them set.
+    fn node_positions(&self, graph: &GraphInfo) -> HashMap<node::Id, model::module::Position> {
+        let nodes = graph.nodes();
+        let positions = nodes.iter().filter_map(|node| {
+            let position = self.module.node_metadata(node.id()).ok()?.position?;
+            Some((node.id(), position))
+        });
+        positions.collect()
+    }
+
     /// Mark the node as skipped by prepending "SKIP" macro call to its AST.
     pub fn set_node_action_skip(&self, node_id: ast::Id, skip: bool) -> FallibleResult {
         self.update_node(node_id, |mut node| {
@@ -1024,6 +1070,225 @@ impl Handle {
         Ok(collapsed_node)
     }
 
+    /// Inline the method called by the given node.
+    ///
+    /// The node is replaced with the lines of the called method's body. If
+    /// `remove_unused_method` is set, the method's definition is removed from the module when
+    /// nothing else uses it. Returns the IDs of the nodes placed in the graph in place of the
+    /// inlined one. These nodes have no position set, so they are placed by the view.
+    #[profile(Task)]
+    pub fn inline(
+        &self,
+        node: node::Id,
+        remove_unused_method: bool,
+    ) -> FallibleResult<Vec<node::Id>> {
+        let _transaction_guard = self.get_or_open_transaction("Inline node");
+        analytics::remote_log_event("graph::inline");
+        use double_representation::refactorings::inline::inline;
+        use double_representation::refactorings::inline::remove_if_unused;
+        info!("Inlining node {node}.");
+        let mut module = module::Info { ast: self.module.ast() };
+        let graph = self.graph_info()?;
+        let module_name = self.module.name().to_owned();
+        let inlined = inline(&graph, node, &module, &module_name, &self.parser)?;
+        module.update_definition(&self.id, |_| Ok(inlined.updated_definition))?;
+        if remove_unused_method {
+            remove_if_unused(&mut module, &inlined.inlined_method)?;
+        }
+        self.module.update_ast(module.ast)?;
+        let _ = self.module.remove_node_metadata(node);
+        Ok(inlined.inlined_nodes)
+    }
+
+    /// Rename the variable introduced by the node's pattern, together with all its usages in the
+    /// graph. Fails if the node's pattern is not a single variable, or if the new name is invalid
+    /// or already used in the graph.
+    #[profile(Task)]
+    pub fn rename_node(&self, id: node::Id, new_name: &str) -> FallibleResult {
+        let _transaction_guard = self.get_or_open_transaction("Rename node");
+        use double_representation::refactorings::rename::rename;
+        let node = self.node_info(id)?;
+        let old_name = node.pattern().and_then(ast::identifier::as_var);
+        let old_name = old_name.ok_or(NoPatternOnNode { node: id })?.to_owned();
+        info!("Renaming `{old_name}` to `{new_name}`.");
+        self.update_definition_ast(|definition| rename(&definition, &old_name, new_name))
+    }
+
+    /// Copy the selected nodes into a clipboard payload.
+    ///
+    /// The nodes are stored in the order they are defined in the graph, together with their
+    /// metadata. The transient parts of the metadata, like the edit status, are not copied. The
+    /// payload also contains the imported names and modules the nodes use, so they can be imported
+    /// into the module the nodes are pasted into.
+    pub fn copy_nodes(
+        &self,
+        nodes: impl IntoIterator<Item = node::Id>,
+    ) -> FallibleResult<clipboard::ClipboardContent> {
+        use double_representation::alias_analysis;
+        let selected = nodes.into_iter().collect::<HashSet<_>>();
+        let nodes = self.nodes()?.into_iter().filter(|node| selected.contains(&node.id()));
+        let nodes = nodes.collect_vec();
+        let is_missing = |id: &&node::Id| !nodes.iter().any(|node| node.id() == **id);
+        if let Some(missing) = selected.iter().find(is_missing) {
+            return Err(NodeNotFound(*missing).into());
+        }
+        info!("Copying {nodes:?}.");
+
+        let mut introduced_names = HashSet::new();
+        let mut external_names = Vec::new();
+        let mut copied_nodes = Vec::new();
+        for node in nodes {
+            let usage = alias_analysis::analyze_ast(node.main_line.ast());
+            let used = usage.used.into_iter().map(|name| name.item);
+            external_names.extend(used.filter(|name| !introduced_names.contains(name)));
+            introduced_names.extend(usage.introduced.into_iter().map(|name| name.item));
+            let metadata = node.metadata.clone().map(|mut metadata| {
+                metadata.selected = false;
+                metadata.edit_status = None;
+                metadata
+            });
+            copied_nodes.push(clipboard::CopiedNode {
+                code: node.main_line.ast().repr(),
+                documentation: node.documentation_text().map(|text| text.to_string()),
+                metadata,
+            });
+        }
+        let imports = self.imported_names(external_names.iter().map(|name| name.as_str()));
+        let module_imports = self.imported_modules(&external_names);
+        Ok(clipboard::ClipboardContent { nodes: copied_nodes, imports, module_imports })
+    }
+
+    /// Fully qualified names of the given names, for the names imported into the module with
+    /// unqualified imports. Names from `from <module> import all` imports are resolved using the
+    /// suggestion database.
+    fn imported_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<QualifiedName> {
+        let module = module::Info { ast: self.module.ast() };
+        let imports = module.iter_imports().collect_vec();
+        let mut imported = Vec::new();
+        for name in names {
+            for import in &imports {
+                if let Ok(module_name) = import.qualified_module_name() {
+                    let qualified_name = module_name.new_child(name);
+                    let is_known =
+                        || self.suggestion_db.lookup_by_qualified_name(&qualified_name).is_some();
+                    let is_imported = match &import.imported {
+                        ImportedNames::Module { .. } => false,
+                        ImportedNames::List { names } => names.contains(name),
+                        ImportedNames::All => is_known(),
+                        ImportedNames::AllExcept { not_imported } =>
+                            !not_imported.contains(name) && is_known(),
+                    };
+                    if is_imported && !imported.contains(&qualified_name) {
+                        imported.push(qualified_name);
+                    }
+                }
+            }
+        }
+        imported
+    }
+
+    /// The whole module imports of the module, which import any of the given names.
+    fn imported_modules(&self, names: &[String]) -> Vec<clipboard::ModuleImport> {
+        let module = module::Info { ast: self.module.ast() };
+        let imports = module.iter_imports().filter_map(|import| match &import.imported {
+            ImportedNames::Module { alias } =>
+                Some((import.qualified_module_name().ok()?, alias.clone())),
+            _ => None,
+        });
+        let is_used = |(module, alias): &(QualifiedName, Option<String>)| {
+            let name = alias.as_deref().unwrap_or_else(|| module.alias_name().as_str());
+            names.iter().any(|used| used == name)
+        };
+        let used = imports.filter(is_used);
+        used.map(|(module, alias)| clipboard::ModuleImport { module, alias }).collect()
+    }
+
+    /// Add the whole module import to the module, if it is not there yet.
+    fn add_module_import_if_missing(&self, import: &clipboard::ModuleImport) -> FallibleResult {
+        let import = import.info();
+        let mut module = module::Info { ast: self.module.ast() };
+        if !module.iter_imports().any(|existing| existing == import) {
+            module.add_import(&self.parser, import);
+            self.module.update_ast(module.ast)?;
+        }
+        Ok(())
+    }
+
+    /// Paste the nodes from the clipboard payload at the end of the graph.
+    ///
+    /// The pattern variables of the pasted nodes get new unique names, and the references between
+    /// the pasted nodes are updated accordingly, so the connections between them are preserved.
+    /// The imports used by the nodes are added to the module if missing. If the `position` is
+    /// given, the nodes are moved so their mean position is placed there, keeping the relative
+    /// positions. Returns the IDs of the pasted nodes.
+    #[profile(Task)]
+    pub fn paste_nodes(
+        &self,
+        content: &clipboard::ClipboardContent,
+        position: Option<model::module::Position>,
+    ) -> FallibleResult<Vec<node::Id>> {
+        use double_representation::alias_analysis;
+        let _transaction_guard = self.get_or_open_transaction("Paste nodes");
+        info!("Pasting {} nodes.", content.nodes.len());
+        for import in &content.imports {
+            self.add_import_if_missing(import.clone())?;
+        }
+        for import in &content.module_imports {
+            self.add_module_import_if_missing(import)?;
+        }
+        let offset = position.map(|position| position.vector - content.mean_position().vector);
+        let mut renamed = HashMap::<String, Ast>::new();
+        let mut pasted_nodes = Vec::new();
+        for copied in &content.nodes {
+            let mut ast = self.parser.parse_line_ast(&copied.code)?;
+            let usage = alias_analysis::analyze_ast(&ast);
+            for used in &usage.used {
+                if let Some(new_name) = renamed.get(&used.item) {
+                    ast = ast.set_traversing(&used.crumbs, new_name.with_new_id())?;
+                }
+            }
+            let mut node = NodeInfo::from_main_line_ast(&ast).ok_or(FailedToCreateNode)?;
+            let pattern_var = node.pattern().and_then(|pat| ast::known::Var::try_from(pat).ok());
+            if let Some(var) = pattern_var {
+                let new_var = self.variable_name_for(&node)?;
+                node.set_pattern(new_var.clone().into());
+                renamed.insert(var.name.clone(), new_var.into());
+            } else if !usage.introduced.is_empty() {
+                // Destructuring patterns introduce several names. Each of them gets a new name
+                // based on the original one.
+                let used_names = self.used_names()?.into_iter().map(|name| name.item);
+                let mut used_names = used_names.collect_vec();
+                for introduced in &usage.introduced {
+                    let base = introduced.item.trim_end_matches(|c: char| c.is_ascii_digit());
+                    let new_name = generate_name(base, used_names.clone())?;
+                    used_names.push(new_name.name().to_owned());
+                    let new_name = Ast::from(new_name);
+                    ast = ast.set_traversing(&introduced.crumbs, new_name.clone())?;
+                    renamed.insert(introduced.item.clone(), new_name);
+                }
+                node = NodeInfo::from_main_line_ast(&ast).ok_or(FailedToCreateNode)?;
+            }
+            node.documentation = copied
+                .documentation
+                .as_ref()
+                .and_then(|pretty_text| self.documentation_comment_from_pretty_text(pretty_text));
+
+            self.update_definition_ast(|definition| {
+                let mut graph = GraphInfo::from_definition(definition);
+                graph.add_node(&node, LocationHint::End)?;
+                Ok(graph.source)
+            })?;
+            if let Some(mut metadata) = copied.metadata.clone() {
+                if let Some((position, offset)) = metadata.position.as_mut().zip(offset) {
+                    position.vector += offset;
+                }
+                self.module.set_node_metadata(node.id(), metadata)?;
+            }
+            pasted_nodes.push(node.id());
+        }
+        Ok(pasted_nodes)
+    }
+
     /// Updates the given node in the definition.
     ///
     /// The function `F` is called with the information with the state of the node so far and
@@ -1385,6 +1650,196 @@ main =
         })
     }
 
+    #[test]
+    fn placing_node_with_layout() {
+        let mut test = Fixture::set_up();
+        test.data.code = r"
+main =
+    a = 10
+    b = a + 1"
+            .to_owned();
+        test.run(move |graph| async move {
+            let nodes = graph.nodes().unwrap();
+            graph.set_node_position(nodes[0].info.id(), Position::new(100.0, 200.0)).unwrap();
+            let position = graph.place_node(nodes[1].info.id(), &default()).unwrap();
+            let node = graph.node(nodes[1].info.id()).unwrap();
+            assert_eq!(node.metadata.and_then(|metadata| metadata.position), Some(position));
+            assert_eq!(position.vector.x, 100.0);
+            assert!(position.vector.y < 200.0);
+        })
+    }
+
+    #[test]
+    fn inlining_node() {
+        let mut test = Fixture::set_up();
+        let code = r"
+func1 a =
+    b = 20
+    c = a + b
+    c
+
+main =
+    a = 10
+    d = Mock_Module.func1 a
+    d + 1";
+
+        let expected_code = "
+main =
+    a = 10
+    b = 20
+    d = a + b
+    d + 1";
+
+        test.data.code = code.to_owned();
+        test.run(move |graph| async move {
+            let nodes = graph.nodes().unwrap();
+            assert_eq!(nodes.len(), 3);
+            let inlined_nodes = graph.inline(nodes[1].info.id(), true).unwrap();
+            model::module::test::expect_code(&*graph.module, expected_code);
+
+            let nodes_after = graph.nodes().unwrap();
+            assert_eq!(nodes_after.len(), 4);
+            let inlined_after = nodes_after[1..3].iter().map(|node| node.info.id()).collect_vec();
+            assert_eq!(inlined_nodes, inlined_after);
+        })
+    }
+
+    #[test]
+    fn renaming_node() {
+        let mut test = Fixture::set_up();
+        let code = r"
+main =
+    a = 10
+    b = x -> x + a
+    c
+    b a";
+
+        let expected_code = "
+main =
+    number = 10
+    b = x -> x + number
+    c
+    b number";
+
+        test.data.code = code.to_owned();
+        test.run(move |graph| async move {
+            let nodes = graph.nodes().unwrap();
+            graph.rename_node(nodes[0].info.id(), "number").unwrap();
+            model::module::test::expect_code(&*graph.module, expected_code);
+            // Names clashing with other variables are rejected.
+            assert!(graph.rename_node(nodes[0].info.id(), "b").is_err());
+            // Nodes without a variable pattern cannot be renamed.
+            assert!(graph.rename_node(nodes[2].info.id(), "d").is_err());
+            model::module::test::expect_code(&*graph.module, expected_code);
+        })
+    }
+
+    #[test]
+    fn copying_and_pasting_nodes() {
+        let mut test = Fixture::set_up();
+        let code = r"
+main =
+    c = 5
+    a = 10
+    b = a + c";
+
+        let expected_code = "
+main =
+    c = 5
+    a = 10
+    b = a + c
+    number1 = 10
+    sum1 = number1 + c";
+
+        test.data.code = code.to_owned();
+        test.run(move |graph| async move {
+            let nodes = graph.nodes().unwrap();
+            let positions = [Position::new(100.0, 200.0), Position::new(150.0, 300.0)];
+            for (node, position) in nodes[1..3].iter().zip(positions) {
+                let metadata = NodeMetadata { position: Some(position), ..default() };
+                graph.module.set_node_metadata(node.id(), metadata).unwrap();
+            }
+
+            let selected_nodes = nodes[1..3].iter().map(|node| node.id());
+            let content = graph.copy_nodes(selected_nodes).unwrap();
+            let content = clipboard::ClipboardContent::from_json(&content.to_json().unwrap());
+            let pasted = graph.paste_nodes(&content.unwrap(), Some(default())).unwrap();
+            model::module::test::expect_code(&*graph.module, expected_code);
+
+            let pasted_positions = pasted.iter().map(|id| graph.node(*id).unwrap().position());
+            let expected_positions = [Position::new(-25.0, -50.0), Position::new(25.0, 50.0)];
+            assert_eq!(pasted_positions.collect_vec(), expected_positions.map(Some));
+            let connections = connections(&graph).unwrap().connections;
+            let is_pasted_connection = |connection: &Connection| {
+                connection.source.node == pasted[0] && connection.destination.node == pasted[1]
+            };
+            assert!(connections.iter().any(is_pasted_connection));
+        })
+    }
+
+    #[test]
+    fn pasting_nodes_adds_missing_imports() {
+        let mut test = Fixture::set_up();
+        let code = r"
+from Standard.Base.Data.Vector import Vector
+
+main =
+    vec = Vector.new 1
+    vec.length";
+
+        test.data.code = code.to_owned();
+        test.run(move |graph| async move {
+            let target = MockData { code: "main =\n    x = 1".into(), ..MockData::new() }.graph();
+            let nodes = graph.nodes().unwrap();
+            let content = graph.copy_nodes(nodes.iter().map(|node| node.id())).unwrap();
+            let imports = content.imports.iter().map(|name| name.to_string()).collect_vec();
+            assert_eq!(imports, vec!["Standard.Base.Data.Vector.Vector"]);
+            target.paste_nodes(&content, None).unwrap();
+
+            let module = module::Info { ast: target.module.ast() };
+            let (import,) = module.iter_imports().collect_vec().expect_tuple();
+            let module_name = import.qualified_module_name().unwrap();
+            assert_eq!(module_name.to_string(), "Standard.Base.Data.Vector");
+            let expected_names = ImportedNames::List { names: ["Vector".to_owned()].into() };
+            assert_eq!(import.imported, expected_names);
+
+            let nodes = target.nodes().unwrap();
+            let (_, vector, length) = nodes.expect_tuple();
+            let vector_name = vector.pattern().unwrap().repr();
+            assert_ne!(vector_name, "vec");
+            assert_eq!(length.expression().repr(), format!("{vector_name}.length"));
+        })
+    }
+
+    #[test]
+    fn pasting_nodes_adds_missing_module_imports() {
+        let mut test = Fixture::set_up();
+        let code = r"
+import Standard.Base.Data.Vector as Vec
+import Standard.Base.Data.Text
+
+main =
+    vec = Vec.new 1
+    vec.length";
+
+        test.data.code = code.to_owned();
+        test.run(move |graph| async move {
+            let target = MockData { code: "main =\n    x = 1".into(), ..MockData::new() }.graph();
+            let nodes = graph.nodes().unwrap();
+            let content = graph.copy_nodes(nodes.iter().map(|node| node.id())).unwrap();
+            assert!(content.imports.is_empty());
+            let (module_import,) = content.module_imports.iter().expect_tuple();
+            assert_eq!(module_import.module.to_string(), "Standard.Base.Data.Vector");
+            assert_eq!(module_import.alias.as_deref(), Some("Vec"));
+            target.paste_nodes(&content, None).unwrap();
+            target.paste_nodes(&content, None).unwrap();
+
+            let module = module::Info { ast: target.module.ast() };
+            let (import,) = module.iter_imports().collect_vec().expect_tuple();
+            assert_eq!(import.to_string(), "import Standard.Base.Data.Vector as Vec");
+        })
+    }
+
     #[test]
     fn graph_controller_doubly_nested_definition() {
         // Tests editing nested definition that requires transforming inline expression into
@@ -0,0 +1,133 @@
+//! Clipboard payload used to copy nodes between graphs.
+//!
+//! The payload contains the code of the copied nodes, in the order they are defined in the graph.
+//! The connections between the copied nodes are kept in their code, as the expressions refer to
+//! the patterns of other copied nodes. See [`controller::Graph::copy_nodes`] and
+//! [`controller::Graph::paste_nodes`].
+
+use crate::prelude::*;
+
+use crate::model::module::NodeMetadata;
+use crate::model::module::Position;
+
+use double_representation::import;
+use double_representation::import::ImportedNames;
+use double_representation::name::QualifiedName;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// ==================
+// === CopiedNode ===
+// ==================
+
+/// A single node stored in the clipboard.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CopiedNode {
+    /// The node's main line code, including the pattern, if the node has one.
+    pub code:          String,
+    /// The pretty text of the node's documentation comment.
+    pub documentation: Option<String>,
+    /// The node's metadata, including its position.
+    pub metadata:      Option<NodeMetadata>,
+}
+
+impl CopiedNode {
+    /// The node's position, if it was set.
+    pub fn position(&self) -> Option<Position> {
+        self.metadata.as_ref().and_then(|metadata| metadata.position)
+    }
+}
+
+
+
+// ====================
+// === ModuleImport ===
+// ====================
+
+/// A whole module import (`import <module> [as <alias>]`) used by the copied nodes.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ModuleImport {
+    /// The imported module.
+    pub module: QualifiedName,
+    /// The name the module is imported under, if it differs from the module's name.
+    pub alias:  Option<String>,
+}
+
+impl ModuleImport {
+    /// The import declaration.
+    pub fn info(&self) -> import::Info {
+        let imported = ImportedNames::Module { alias: self.alias.clone() };
+        import::Info { module: (&self.module).into(), imported }
+    }
+}
+
+
+
+// ========================
+// === ClipboardContent ===
+// ========================
+
+/// The content of the clipboard after copying nodes.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ClipboardContent {
+    /// The copied nodes, ordered as in the source graph, so every node is placed after the nodes
+    /// it refers to.
+    pub nodes:          Vec<CopiedNode>,
+    /// The names used by the copied nodes, which were imported into the source module. They need
+    /// to be imported into the module the nodes are pasted into.
+    pub imports:        Vec<QualifiedName>,
+    /// The modules used by the copied nodes, which were imported as a whole into the source
+    /// module.
+    #[serde(default)]
+    pub module_imports: Vec<ModuleImport>,
+}
+
+impl ClipboardContent {
+    /// Serialize the content, so it can be put into the system clipboard.
+    pub fn to_json(&self) -> FallibleResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize the content serialized with [`Self::to_json`].
+    pub fn from_json(json: &str) -> FallibleResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The mean position of the copied nodes with set positions.
+    pub fn mean_position(&self) -> Position {
+        Position::mean(self.nodes.iter().filter_map(|node| node.position()))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization_round_trip() {
+        let metadata = NodeMetadata { position: Some(Position::new(10.0, 20.0)), ..default() };
+        let nodes = vec![
+            CopiedNode {
+                code:          "a = 10".into(),
+                documentation: Some("The answer.".into()),
+                metadata:      Some(metadata),
+            },
+            CopiedNode { code: "a + 1".into(), documentation: None, metadata: None },
+        ];
+        let imports = vec![QualifiedName::from_text("Standard.Base.Data.Vector").unwrap()];
+        let module = QualifiedName::from_text("Standard.Base.Data.Text").unwrap();
+        let module_imports = vec![ModuleImport { module, alias: Some("T".into()) }];
+        let content = ClipboardContent { nodes, imports, module_imports };
+        let json = content.to_json().unwrap();
+        assert_eq!(ClipboardContent::from_json(&json).unwrap(), content);
+        assert_eq!(content.mean_position(), Position::new(10.0, 20.0));
+    }
+}
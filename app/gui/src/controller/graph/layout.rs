@@ -0,0 +1,571 @@
+//! Automatic layout of the graph nodes.
+//!
+//! The nodes are arranged in layers, following the Sugiyama method: every node is placed in a layer
+//! below all the nodes it takes inputs from, then the nodes in each layer are reordered to minimize
+//! the edge crossings, and finally the coordinates are assigned, respecting the node sizes.
+//!
+//! The node positions follow the graph editor convention: the `x` coordinate is the left edge of
+//! the node, the `y` coordinate is its vertical center, and the `y` axis points up.
+
+use crate::prelude::*;
+
+use crate::model::module::Position;
+
+use double_representation::graph::GraphInfo;
+use double_representation::node;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width assumed for nodes with unknown size.
+pub const DEFAULT_NODE_WIDTH: f32 = 200.0;
+/// The height assumed for nodes with unknown size.
+pub const DEFAULT_NODE_HEIGHT: f32 = 28.0;
+/// The vertical gap between subsequent layers.
+pub const LAYER_GAP: f32 = 40.0;
+/// The horizontal gap between nodes in the same layer.
+pub const NODE_GAP: f32 = 40.0;
+/// The number of sweeps over the layers reordering the nodes to minimize edge crossings.
+const ORDERING_SWEEPS: usize = 8;
+/// The number of sweeps over the layers aligning the nodes with their neighbours.
+const PLACEMENT_SWEEPS: usize = 4;
+/// The number of horizontal steps at each side of the desired position tried by
+/// [`find_free_place`] before moving to the next row.
+const FREE_PLACE_STEPS: usize = 8;
+
+
+
+// ============
+// === Rect ===
+// ============
+
+/// The area occupied by a node.
+#[derive(Clone, Copy, Debug)]
+struct Rect {
+    left:   f32,
+    right:  f32,
+    bottom: f32,
+    top:    f32,
+}
+
+impl Rect {
+    /// The area of a node of the given size placed at the given position.
+    fn new(position: Vector2, size: Vector2) -> Self {
+        let left = position.x;
+        let right = position.x + size.x;
+        let bottom = position.y - size.y / 2.0;
+        let top = position.y + size.y / 2.0;
+        Self { left, right, bottom, top }
+    }
+
+    fn center_x(&self) -> f32 {
+        (self.left + self.right) / 2.0
+    }
+
+    /// Check if the areas overlap or are closer than the gaps.
+    fn collides(&self, other: &Rect) -> bool {
+        let separated_horizontally =
+            self.right + NODE_GAP <= other.left || other.right + NODE_GAP <= self.left;
+        let separated_vertically =
+            self.top + LAYER_GAP <= other.bottom || other.top + LAYER_GAP <= self.bottom;
+        !separated_horizontally && !separated_vertically
+    }
+}
+
+
+
+// ==============
+// === Layout ===
+// ==============
+
+/// Layout engine of a single graph.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    /// The graph nodes, in the order they are defined in.
+    ids:   Vec<node::Id>,
+    /// The sizes of the nodes, indexed like `ids`.
+    sizes: Vec<Vector2>,
+    /// The connections between the nodes, as pairs of source and target node indices.
+    edges: Vec<(usize, usize)>,
+}
+
+impl Layout {
+    /// Constructor. The nodes and the connections between them are read from the graph. Nodes not
+    /// present in `node_sizes` are assumed to have the default size.
+    pub fn new(graph: &GraphInfo, node_sizes: &HashMap<node::Id, Vector2>) -> Self {
+        let ids = graph.nodes().iter().map(|node| node.id()).collect_vec();
+        let default_size = Vector2::new(DEFAULT_NODE_WIDTH, DEFAULT_NODE_HEIGHT);
+        let sizes = ids.iter().map(|id| node_sizes.get(id).copied().unwrap_or(default_size));
+        let sizes = sizes.collect();
+        let index_of = |id: node::Id| ids.iter().position(|node| *node == id);
+        let edges = graph.connections().into_iter().filter_map(|connection| {
+            let source = index_of(connection.source.node)?;
+            let target = index_of(connection.destination.node)?;
+            (source != target).as_some((source, target))
+        });
+        let edges = edges.sorted().dedup().collect();
+        Self { ids, sizes, edges }
+    }
+
+    /// Compute the positions of all nodes, arranging them in layers from top to bottom. The nodes
+    /// are ordered in the layers to minimize the edge crossings, and centered over the nodes they
+    /// are connected with.
+    ///
+    /// The top left corner of the arranged nodes is placed at the top left corner of the nodes
+    /// which have `current_positions`, so the tidied graph stays in the same place.
+    pub fn tidy(
+        &self,
+        current_positions: &HashMap<node::Id, Position>,
+    ) -> HashMap<node::Id, Position> {
+        let mut graph = LayeredGraph::new(self);
+        graph.minimize_crossings();
+        let positions = graph.coordinates();
+        let anchor = self.top_left_corner(current_positions).unwrap_or_else(Vector2::zeros);
+        let positions = positions.into_iter().map(|position| Position::from(position + anchor));
+        self.ids.iter().copied().zip(positions).collect()
+    }
+
+    /// Compute the position of the given node, so it is placed below the nodes it takes inputs
+    /// from, or above the nodes using its output, without overlapping any other node. The nodes
+    /// which already have `positions` are not moved. Returns [`None`] if the node is not in the
+    /// graph.
+    pub fn place_new_node(
+        &self,
+        node: node::Id,
+        positions: &HashMap<node::Id, Position>,
+    ) -> Option<Position> {
+        let index = self.ids.iter().position(|id| *id == node)?;
+        let size = self.sizes[index];
+        let placed_rect = |other: usize| {
+            let position = positions.get(&self.ids[other]).filter(|_| other != index)?;
+            Some(Rect::new(position.vector, self.sizes[other]))
+        };
+        let sources = self.edges.iter().filter(|(_, target)| *target == index);
+        let sources = sources.filter_map(|(source, _)| placed_rect(*source)).collect_vec();
+        let targets = self.edges.iter().filter(|(source, _)| *source == index);
+        let targets = targets.filter_map(|(_, target)| placed_rect(*target)).collect_vec();
+        let obstacles = (0..self.ids.len()).filter_map(placed_rect).collect_vec();
+        let mean_center_x =
+            |rects: &[Rect]| rects.iter().map(Rect::center_x).sum::<f32>() / rects.len() as f32;
+        let lowest_bottom =
+            |rects: &[Rect]| rects.iter().map(|r| r.bottom).fold(f32::MAX, f32::min);
+        let highest_top = |rects: &[Rect]| rects.iter().map(|r| r.top).fold(f32::MIN, f32::max);
+        let desired = if !sources.is_empty() {
+            let y = lowest_bottom(&sources) - LAYER_GAP - size.y / 2.0;
+            Vector2::new(mean_center_x(&sources) - size.x / 2.0, y)
+        } else if !targets.is_empty() {
+            let y = highest_top(&targets) + LAYER_GAP + size.y / 2.0;
+            Vector2::new(mean_center_x(&targets) - size.x / 2.0, y)
+        } else if !obstacles.is_empty() {
+            let left = obstacles.iter().map(|r| r.left).fold(f32::MAX, f32::min);
+            Vector2::new(left, lowest_bottom(&obstacles) - LAYER_GAP - size.y / 2.0)
+        } else {
+            Vector2::zeros()
+        };
+        Some(Position::from(find_free_place(desired, size, &obstacles)))
+    }
+
+    /// The top left corner of the area occupied by the nodes with known positions.
+    fn top_left_corner(&self, positions: &HashMap<node::Id, Position>) -> Option<Vector2> {
+        let rects = self.ids.iter().zip(&self.sizes).filter_map(|(id, size)| {
+            positions.get(id).map(|position| Rect::new(position.vector, *size))
+        });
+        rects.fold(None, |corner: Option<Vector2>, rect| {
+            let corner = corner.unwrap_or_else(|| Vector2::new(rect.left, rect.top));
+            Some(Vector2::new(corner.x.min(rect.left), corner.y.max(rect.top)))
+        })
+    }
+}
+
+/// Find the position closest to `desired` where a node of the given size does not collide with
+/// any of the obstacles. The positions in the same row are tried first, alternating the sides,
+/// then the search moves one row down.
+fn find_free_place(desired: Vector2, size: Vector2, obstacles: &[Rect]) -> Vector2 {
+    let is_free = |position: Vector2| {
+        let rect = Rect::new(position, size);
+        obstacles.iter().all(|obstacle| !rect.collides(obstacle))
+    };
+    let horizontal_step = (size.x + NODE_GAP) / 2.0;
+    let vertical_step = size.y + LAYER_GAP;
+    let lowest_bottom = obstacles.iter().map(|r| r.bottom).fold(f32::MAX, f32::min);
+    let mut y = desired.y;
+    loop {
+        for step in 0..=FREE_PLACE_STEPS {
+            let offset = step as f32 * horizontal_step;
+            for x in [desired.x + offset, desired.x - offset] {
+                let position = Vector2::new(x, y);
+                if is_free(position) {
+                    return position;
+                }
+            }
+        }
+        // The rows below all obstacles are free, so the search stops there at the latest.
+        let below_all_obstacles = y + size.y / 2.0 + LAYER_GAP <= lowest_bottom;
+        if below_all_obstacles || y.is_nan() {
+            return Vector2::new(desired.x, y);
+        }
+        y -= vertical_step;
+    }
+}
+
+/// Place boxes of the given widths in a row, in the given order, so they are separated by at
+/// least `gap`, while keeping their left edges as close to the `desired` ones as possible, in the
+/// least squares sense.
+///
+/// After subtracting the offsets of the boxes packed tightly, the separation constraint becomes
+/// the monotonicity of the positions. Therefore, the problem is an isotonic regression, solved
+/// with the Pool Adjacent Violators algorithm.
+fn place_in_row(desired: &[f32], widths: &[f32], gap: f32) -> Vec<f32> {
+    let offsets = widths
+        .iter()
+        .scan(0.0, |offset, width| {
+            let current = *offset;
+            *offset += width + gap;
+            Some(current)
+        })
+        .collect_vec();
+    // Pools of subsequent boxes placed tightly, described by the sum of targets and box count.
+    let mut pools: Vec<(f32, usize)> = Vec::new();
+    for (desired, offset) in desired.iter().zip(&offsets) {
+        pools.push((desired - offset, 1));
+        while let [.., (sum1, count1), (sum2, count2)] = pools[..] {
+            if sum1 / count1 as f32 <= sum2 / count2 as f32 {
+                break;
+            }
+            pools.pop();
+            pools.pop();
+            pools.push((sum1 + sum2, count1 + count2));
+        }
+    }
+    let bases =
+        pools.iter().flat_map(|(sum, count)| std::iter::repeat(sum / *count as f32).take(*count));
+    bases.zip(offsets).map(|(base, offset)| base + offset).collect()
+}
+
+
+
+// ====================
+// === LayeredGraph ===
+// ====================
+
+/// The graph with nodes assigned to layers. The connections spanning more than one layer are split
+/// into chains of zero-sized dummy vertices, one in each crossed layer, so every edge connects
+/// vertices in subsequent layers.
+#[derive(Clone, Debug)]
+struct LayeredGraph {
+    /// The number of vertices being graph nodes. These are the first vertices, indexed like the
+    /// nodes in the [`Layout`]. The rest are the dummy vertices.
+    node_count: usize,
+    sizes:      Vec<Vector2>,
+    layer_of:   Vec<usize>,
+    /// The vertices in each layer, from top to bottom, in their left to right order.
+    layers:     Vec<Vec<usize>>,
+    /// The neighbours of each vertex in the layer above.
+    upper:      Vec<Vec<usize>>,
+    /// The neighbours of each vertex in the layer below.
+    lower:      Vec<Vec<usize>>,
+}
+
+impl LayeredGraph {
+    /// Assign the nodes to layers, so that every node is in the first layer below all the nodes it
+    /// takes inputs from.
+    fn new(layout: &Layout) -> Self {
+        let node_count = layout.ids.len();
+        // The connections going against the order of the definitions are reversed. This way the
+        // graph is acyclic, and the nodes order is a topological one.
+        let mut edges = layout.edges.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect_vec();
+        edges.sort_by_key(|&(source, target)| (target, source));
+        edges.dedup();
+        let mut layer_of = vec![0; node_count];
+        for &(source, target) in &edges {
+            layer_of[target] = layer_of[target].max(layer_of[source] + 1);
+        }
+        let mut graph = Self {
+            node_count,
+            sizes: layout.sizes.clone(),
+            layer_of,
+            layers: default(),
+            upper: vec![default(); node_count],
+            lower: vec![default(); node_count],
+        };
+        for (source, target) in edges {
+            let mut previous = source;
+            for layer in graph.layer_of[source] + 1..graph.layer_of[target] {
+                let dummy = graph.add_dummy(layer);
+                graph.link(previous, dummy);
+                previous = dummy;
+            }
+            graph.link(previous, target);
+        }
+        let layer_count = graph.layer_of.iter().max().map_or(0, |last| last + 1);
+        graph.layers = vec![default(); layer_count];
+        for (vertex, layer) in graph.layer_of.iter().enumerate() {
+            graph.layers[*layer].push(vertex);
+        }
+        graph
+    }
+
+    fn add_dummy(&mut self, layer: usize) -> usize {
+        self.sizes.push(Vector2::zeros());
+        self.layer_of.push(layer);
+        self.upper.push(default());
+        self.lower.push(default());
+        self.sizes.len() - 1
+    }
+
+    fn link(&mut self, upper: usize, lower: usize) {
+        self.lower[upper].push(lower);
+        self.upper[lower].push(upper);
+    }
+
+    /// The index of each vertex in its layer.
+    fn indices_in_layers(&self) -> Vec<usize> {
+        let mut indices = vec![0; self.sizes.len()];
+        for layer in &self.layers {
+            for (index, vertex) in layer.iter().enumerate() {
+                indices[*vertex] = index;
+            }
+        }
+        indices
+    }
+
+    /// The number of edge crossings in the current vertex order.
+    fn crossings(&self) -> usize {
+        let indices = &self.indices_in_layers();
+        let layer_crossings = self.layers.iter().map(|layer| {
+            let edges = layer.iter().flat_map(|&upper| {
+                self.lower[upper].iter().map(move |&lower| (indices[upper], indices[lower]))
+            });
+            let edges = edges.collect_vec();
+            let crossing = |((u1, l1), (u2, l2)): (&(usize, usize), &(usize, usize))| {
+                (u1 < u2 && l1 > l2) || (u1 > u2 && l1 < l2)
+            };
+            edges.iter().tuple_combinations().filter(|pair| crossing(*pair)).count()
+        });
+        layer_crossings.sum()
+    }
+
+    /// Reorder the vertices in the layers with the barycenter heuristic, sweeping the layers
+    /// alternately downwards and upwards. The order with the fewest crossings is kept.
+    fn minimize_crossings(&mut self) {
+        let layer_count = self.layers.len();
+        let mut best_layers = self.layers.clone();
+        let mut best_crossings = self.crossings();
+        for sweep in 0..ORDERING_SWEEPS {
+            let downwards = sweep % 2 == 0;
+            if downwards {
+                for layer in 1..layer_count {
+                    self.order_by_barycenter(layer, downwards);
+                }
+            } else {
+                for layer in (0..layer_count.saturating_sub(1)).rev() {
+                    self.order_by_barycenter(layer, downwards);
+                }
+            }
+            let crossings = self.crossings();
+            if crossings < best_crossings {
+                best_layers = self.layers.clone();
+                best_crossings = crossings;
+            }
+        }
+        self.layers = best_layers;
+    }
+
+    /// Order the vertices of the layer by the mean index of their neighbours in the layer above
+    /// (if `downwards` is set) or below. Vertices without such neighbours keep their index.
+    fn order_by_barycenter(&mut self, layer: usize, downwards: bool) {
+        let indices = self.indices_in_layers();
+        let neighbours = if downwards { &self.upper } else { &self.lower };
+        let barycenter = |(index, vertex): (usize, &usize)| {
+            let neighbours = &neighbours[*vertex];
+            let barycenter = if neighbours.is_empty() {
+                index as f32
+            } else {
+                let sum = neighbours.iter().map(|n| indices[*n] as f32).sum::<f32>();
+                sum / neighbours.len() as f32
+            };
+            (barycenter, *vertex)
+        };
+        let mut keyed = self.layers[layer].iter().enumerate().map(barycenter).collect_vec();
+        keyed.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        self.layers[layer] = keyed.into_iter().map(|(_, vertex)| vertex).collect();
+    }
+
+    /// Assign the coordinates to the graph nodes. The layers are stacked from top to bottom,
+    /// starting at `y = 0`, and the vertices are moved horizontally towards their neighbours,
+    /// keeping the order and the gaps. The leftmost node is placed at `x = 0`.
+    fn coordinates(&self) -> Vec<Vector2> {
+        let mut layer_y = Vec::with_capacity(self.layers.len());
+        let mut top = 0.0;
+        for layer in &self.layers {
+            let height = layer.iter().map(|vertex| self.sizes[*vertex].y).fold(0.0, f32::max);
+            layer_y.push(top - height / 2.0);
+            top -= height + LAYER_GAP;
+        }
+
+        let mut left = vec![0.0; self.sizes.len()];
+        for layer in &self.layers {
+            let mut x = 0.0;
+            for vertex in layer {
+                left[*vertex] = x;
+                x += self.sizes[*vertex].x + NODE_GAP;
+            }
+        }
+        let layer_count = self.layers.len();
+        for sweep in 0..PLACEMENT_SWEEPS {
+            let downwards = sweep % 2 == 0;
+            let layers = if downwards {
+                (1..layer_count).collect_vec()
+            } else {
+                (0..layer_count.saturating_sub(1)).rev().collect_vec()
+            };
+            let neighbours = if downwards { &self.upper } else { &self.lower };
+            for layer in layers {
+                let vertices = &self.layers[layer];
+                let desired = vertices.iter().map(|&vertex| {
+                    let neighbours = &neighbours[vertex];
+                    if neighbours.is_empty() {
+                        left[vertex]
+                    } else {
+                        let center = |n: &usize| left[*n] + self.sizes[*n].x / 2.0;
+                        let sum = neighbours.iter().map(center).sum::<f32>();
+                        sum / neighbours.len() as f32 - self.sizes[vertex].x / 2.0
+                    }
+                });
+                let desired = desired.collect_vec();
+                let widths = vertices.iter().map(|vertex| self.sizes[*vertex].x).collect_vec();
+                for (vertex, x) in vertices.iter().zip(place_in_row(&desired, &widths, NODE_GAP)) {
+                    left[*vertex] = x;
+                }
+            }
+        }
+
+        let nodes = 0..self.node_count;
+        let min_left = nodes.clone().map(|node| left[node]).fold(f32::MAX, f32::min);
+        nodes
+            .map(|node| Vector2::new(left[node] - min_left, layer_y[self.layer_of[node]]))
+            .collect()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use double_representation::definition::DefinitionName;
+    use double_representation::module;
+    use parser::Parser;
+
+    fn graph(code: &str) -> GraphInfo {
+        let parser = Parser::new();
+        let ast = parser.parse_module(code, default()).unwrap();
+        let main = module::locate_child(&ast, &DefinitionName::new_plain("main")).unwrap();
+        GraphInfo::from_definition(main.item.clone())
+    }
+
+    #[test]
+    fn placing_boxes_in_row() {
+        assert_eq!(place_in_row(&[0.0, 100.0], &[10.0, 10.0], 5.0), vec![0.0, 100.0]);
+        assert_eq!(place_in_row(&[0.0, 0.0], &[10.0, 10.0], 10.0), vec![-10.0, 10.0]);
+        assert_eq!(place_in_row(&[5.0, 0.0, 30.0], &[10.0, 10.0, 10.0], 0.0), vec![
+            -2.5, 7.5, 30.0
+        ]);
+    }
+
+    #[test]
+    fn tidying_graph() {
+        let graph = graph(
+            r"
+main =
+    a = 10
+    b = 20
+    c = a + b
+    d = a + 1
+    e = c + d",
+        );
+        let ids = graph.nodes().iter().map(|node| node.id()).collect_vec();
+        let layout = Layout::new(&graph, &default());
+        let positions = layout.tidy(&default());
+        let [a, b, c, d, e] = [0, 1, 2, 3, 4].map(|index| positions[&ids[index]].vector);
+        // Nodes are placed in layers from top to bottom.
+        assert_eq!(a.y, b.y);
+        assert_eq!(c.y, d.y);
+        assert_eq!(a.y, -DEFAULT_NODE_HEIGHT / 2.0);
+        assert_eq!(c.y, a.y - DEFAULT_NODE_HEIGHT - LAYER_GAP);
+        assert!(e.y < c.y);
+        // Nodes in the same layer do not overlap.
+        assert!((a.x - b.x).abs() >= DEFAULT_NODE_WIDTH + NODE_GAP);
+        assert!((c.x - d.x).abs() >= DEFAULT_NODE_WIDTH + NODE_GAP);
+        // The order without crossings is chosen: `a` is connected to both `c` and `d`, while `b`
+        // only to `c`.
+        assert!(a.x < b.x);
+        assert!(d.x < c.x);
+        assert_eq!(positions.values().map(|p| p.vector.x).fold(f32::MAX, f32::min), 0.0);
+
+        // The tidied graph keeps the top left corner of the positioned nodes.
+        let current = [(ids[0], Position::new(100.0, 50.0))].into_iter().collect();
+        let moved = layout.tidy(&current);
+        let corner = Vector2::new(100.0, 50.0 + DEFAULT_NODE_HEIGHT / 2.0);
+        for id in &ids {
+            assert_eq!(moved[id].vector, positions[id].vector + corner);
+        }
+    }
+
+    #[test]
+    fn placing_new_node() {
+        let graph = graph(
+            r"
+main =
+    a = 10
+    b = 20
+    c = a + b
+    d = 30",
+        );
+        let ids = graph.nodes().iter().map(|node| node.id()).collect_vec();
+        let wide = Vector2::new(300.0, DEFAULT_NODE_HEIGHT);
+        let sizes = [(ids[1], wide)].into_iter().collect();
+        let layout = Layout::new(&graph, &sizes);
+        let mut positions: HashMap<_, _> =
+            [(ids[0], Position::new(0.0, 0.0)), (ids[1], Position::new(300.0, 0.0))]
+                .into_iter()
+                .collect();
+        // The node is placed below its inputs, centered under them.
+        let c = layout.place_new_node(ids[2], &positions).unwrap().vector;
+        let expected_x = (DEFAULT_NODE_WIDTH / 2.0 + 450.0) / 2.0 - DEFAULT_NODE_WIDTH / 2.0;
+        assert_eq!(c, Vector2::new(expected_x, -DEFAULT_NODE_HEIGHT - LAYER_GAP));
+        positions.insert(ids[2], c.into());
+        // The unconnected node is placed below the existing nodes, without overlapping them.
+        let d = layout.place_new_node(ids[3], &positions).unwrap().vector;
+        let d_rect = Rect::new(d, Vector2::new(DEFAULT_NODE_WIDTH, DEFAULT_NODE_HEIGHT));
+        for (index, id) in ids[..3].iter().enumerate() {
+            let rect = Rect::new(positions[id].vector, layout.sizes[index]);
+            assert!(!d_rect.collides(&rect));
+        }
+        assert_eq!(d.x, 0.0);
+        // Placing the node again does not move it onto other nodes.
+        let c_again = layout.place_new_node(ids[2], &positions).unwrap().vector;
+        assert_eq!(c_again, c);
+    }
+
+    #[test]
+    fn free_place_is_searched_aside_and_below() {
+        let size = Vector2::new(100.0, 20.0);
+        let obstacle = Rect::new(Vector2::new(0.0, 0.0), size);
+        let free = find_free_place(Vector2::new(0.0, 0.0), size, &[obstacle]);
+        assert!(!Rect::new(free, size).collides(&obstacle));
+        assert_eq!(free.y, 0.0);
+        let wall = (-10..=10).map(|i| Rect::new(Vector2::new(i as f32 * 140.0, 0.0), size));
+        let wall = wall.collect_vec();
+        let free = find_free_place(Vector2::new(0.0, 0.0), size, &wall);
+        assert_eq!(free, Vector2::new(0.0, -size.y - LAYER_GAP));
+    }
+}
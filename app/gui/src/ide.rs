@@ -28,6 +28,16 @@ pub use initializer::Initializer;
 /// Text that shows up in the statusbar when any of the backend connections is lost.
 pub const BACKEND_DISCONNECTED_MESSAGE: &str =
     "Connection to the backend has been lost. Please try restarting IDE.";
+/// Text that shows up in the statusbar when a lost backend connection is being reestablished.
+pub const BACKEND_RECONNECTING_MESSAGE: &str =
+    "Connection to the backend has been lost. Reconnecting...";
+/// Text that shows up in the statusbar when a lost backend connection has been reestablished.
+pub const BACKEND_RECONNECTED_MESSAGE: &str = "Connection to the backend has been reestablished.";
+/// Text that shows up in the statusbar when a backend connection has been reestablished, but the
+/// project state could not be restored in the new session.
+pub const BACKEND_SESSION_NOT_RESTORED_MESSAGE: &str =
+    "Connection to the backend has been reestablished, but the project could not be restored. \
+    Please reload IDE.";
 /// Text that shows up in the statusbar when backend reports a failed execution.
 pub const EXECUTION_FAILED_MESSAGE: &str =
     "Execution failed. Please try restarting project or IDE and report this problem at support@enso.org.";
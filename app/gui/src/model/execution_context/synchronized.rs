@@ -95,6 +95,29 @@ impl ExecutionContext {
         result.map(|res| res.map_err(|err| err.into()))
     }
 
+    /// Create the execution context again in a new Language Server session, after the connection
+    /// was reestablished. The stack frames, the execution environment and the attached
+    /// visualizations are restored from the model.
+    pub async fn restore(&self) -> FallibleResult {
+        info!("Restoring the execution context {}.", self.id);
+        self.language_server.client.create_execution_context(&self.id).await?;
+        self.push_root_frame().await?;
+        let stack_items = self.model.stack_items().collect_vec();
+        for stack_item in stack_items {
+            let call = language_server::LocalCall { expression_id: stack_item.call };
+            let frame = language_server::StackItem::LocalCall(call);
+            self.language_server.push_to_execution_context(&self.id, &frame).await?;
+        }
+        let environment = self.model.execution_environment.get();
+        self.language_server.client.set_execution_environment(&self.id, &environment).await?;
+        for vis in self.model.all_visualizations_info() {
+            let config = vis.config(self.id);
+            let ls = &self.language_server;
+            ls.attach_visualisation(&vis.id, &vis.expression_id, &config).await?;
+        }
+        Ok(())
+    }
+
     /// Load the component groups defined in libraries imported into the execution context.
     async fn load_component_groups(&self) {
         let log_group_parsing_error = |err: &failure::Error| {
@@ -448,13 +471,30 @@ pub mod test {
         pub fn mock_default_calls(data: &MockData, ls: &mut language_server::MockClient) {
             Self::mock_create_destroy_calls(data, ls);
             let id = data.context_id;
+            let stack_item = Self::root_frame(data);
+            expect_call!(ls.push_to_execution_context(id,stack_item) => Ok(()));
+        }
+
+        /// Sets up mock client expectations for the calls issued by [`ExecutionContext::restore`]
+        /// for a context with no stack items and visualizations pushed by the user.
+        pub fn mock_restore_calls(data: &MockData, ls: &mut language_server::MockClient) {
+            let id = data.context_id;
+            let result = Self::expected_creation_response(data);
+            let stack_item = Self::root_frame(data);
+            let environment = ExecutionEnvironment::default();
+            expect_call!(ls.create_execution_context(id) => Ok(result));
+            expect_call!(ls.push_to_execution_context(id,stack_item) => Ok(()));
+            expect_call!(ls.set_execution_environment(id,environment) => Ok(()));
+        }
+
+        /// The stack frame of the context's entry point.
+        fn root_frame(data: &MockData) -> language_server::StackItem {
             let root_frame = language_server::ExplicitCall {
                 method_pointer:                   data.main_method_pointer(),
                 this_argument_expression:         None,
                 positional_arguments_expressions: vec![],
             };
-            let stack_item = language_server::StackItem::ExplicitCall(root_frame);
-            expect_call!(ls.push_to_execution_context(id,stack_item) => Ok(()));
+            language_server::StackItem::ExplicitCall(root_frame)
         }
 
         /// Generates a mock update for a random expression ID.
@@ -510,6 +550,43 @@ pub mod test {
         });
     }
 
+    #[test]
+    fn restoring_context() {
+        let expression_id = model::execution_context::ExpressionId::new_v4();
+        let method_pointer = QualifiedMethodPointer::module_method(
+            MockData::new().module_qualified_name(),
+            Identifier::from_text("foo").unwrap(),
+        );
+        let vis = Visualization {
+            id: model::execution_context::VisualizationId::new_v4(),
+            expression_id,
+            method_pointer,
+            arguments: vec![],
+        };
+        let Fixture { data, mut test, context } = Fixture::new_customized(|ls, data| {
+            let id = data.context_id;
+            let local_call = language_server::LocalCall { expression_id };
+            let local_frame = language_server::StackItem::LocalCall(local_call);
+            let vis_id = vis.id;
+            let config = vis.config(id);
+            expect_call!(ls.push_to_execution_context(id,local_frame.clone()) => Ok(()));
+            expect_call!(ls.attach_visualisation(vis_id,expression_id,config.clone()) => Ok(()));
+
+            // The calls made when restoring the context in a new session. The root frame is pushed
+            // again before the user's frame.
+            Fixture::mock_restore_calls(data, ls);
+            expect_call!(ls.push_to_execution_context(id,local_frame) => Ok(()));
+            expect_call!(ls.attach_visualisation(vis_id,expression_id,config) => Ok(()));
+        });
+        test.run_task(async move {
+            let item =
+                LocalCall { call: expression_id, definition: data.main_method_pointer() };
+            context.push(item).await.unwrap();
+            let _ = context.attach_visualization(vis.clone()).await.unwrap();
+            context.restore().await.unwrap();
+        });
+    }
+
     #[test]
     fn attaching_visualizations_and_notifying() {
         let method_pointer = QualifiedMethodPointer::module_method(
@@ -18,6 +18,7 @@ use double_representation::definition::DefinitionInfo;
 use double_representation::graph::Id;
 use double_representation::import;
 use engine_protocol::language_server;
+use engine_protocol::language_server::CapabilityRegistration;
 use engine_protocol::language_server::FileEdit;
 use engine_protocol::language_server::TextEdit;
 use engine_protocol::types::Sha3_224;
@@ -130,6 +131,16 @@ enum LanguageServerContent {
 // === Synchronized Module ===
 // ===========================
 
+// === Errors ===
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "The content of file {} is not synchronized with the Language Server.", _0)]
+pub struct FileNotSynchronized(language_server::Path);
+
+
+// === Definition ===
+
 /// A Module which state is synchronized with Language Server using its textual API.
 ///
 /// This struct owns  `model::Module`, load the state during creation and updates LS about all
@@ -220,6 +231,31 @@ impl Module {
         Ok(())
     }
 
+    /// Open the file again in a new Language Server session, after the connection was
+    /// reestablished.
+    ///
+    /// The write capability is acquired if it was not granted on opening, and the LS state is
+    /// updated with the model's current content. Fails if the content could not be synchronized.
+    pub async fn restore_in_language_server(&self) -> FallibleResult {
+        let file_path = self.path().file_path();
+        info!("Restoring file {file_path} in the new Language Server session.");
+        let opened = self.language_server.client.open_text_file(file_path).await?;
+        if opened.write_capability.is_none() {
+            let capability = CapabilityRegistration::create_can_edit_text_file(file_path.clone());
+            let method = &capability.method;
+            let options = &capability.register_options;
+            self.language_server.client.acquire_capability(method, options).await?;
+        }
+        let content = opened.content.into();
+        let summary = ContentSummary::new(&content);
+        let new_file = self.model.serialized_content()?;
+        self.full_invalidation(summary, new_file).await;
+        match *self.ls_content.borrow() {
+            LanguageServerContent::Synchronized(_) => Ok(()),
+            LanguageServerContent::Unknown => Err(FileNotSynchronized(file_path.clone()).into()),
+        }
+    }
+
     /// Apply text changes received from the language server.
     pub async fn apply_text_change_from_ls(&self, edits: Vec<TextEdit>) -> FallibleResult {
         let mut content: text::Rope = self.serialized_content()?.content.into();
@@ -178,6 +178,13 @@ pub type Synchronized = synchronized::Project;
 pub enum Notification {
     /// One of the backend connections has been lost.
     ConnectionLost(BackendConnection),
+    /// One of the backend connections has been lost, and is being reestablished.
+    Reconnecting(BackendConnection),
+    /// One of the backend connections has been reestablished.
+    Reconnected(BackendConnection),
+    /// One of the backend connections has been reestablished, but the session state (opened
+    /// modules, execution contexts) could not be restored in the new session.
+    SessionNotRestored(BackendConnection),
     /// Indicates that the project VCS status has changed.
     VcsStatusChanged(VcsStatus),
     /// Indicates that the project has finished execution.
@@ -7,12 +7,16 @@ use crate::model::execution_context;
 use crate::model::execution_context::synchronized::Notification as ExecutionUpdate;
 use crate::model::execution_context::VisualizationUpdateData;
 use crate::model::module;
+use crate::model::suggestion_database;
 use crate::model::SuggestionDatabase;
+use crate::transport;
 use crate::transport::web::WebSocket;
 
 use double_representation::name::project;
 use engine_protocol::binary;
 use engine_protocol::binary::message::VisualisationContext;
+use engine_protocol::capture;
+use engine_protocol::capture::RecordingTransport;
 use engine_protocol::common::error::code;
 use engine_protocol::language_server;
 use engine_protocol::language_server::response;
@@ -26,6 +30,9 @@ use engine_protocol::project_manager::MissingComponentAction;
 use engine_protocol::project_manager::ProjectName;
 use flo_stream::Subscriber;
 use json_rpc::error::RpcError;
+use json_rpc::transport::reconnecting::resend_methods;
+use json_rpc::transport::reconnecting::ConnectionFuture;
+use json_rpc::transport::ReconnectingTransport;
 use parser::Parser;
 
 
@@ -103,6 +110,11 @@ impl ExecutionContextsRegistry {
         self.0.borrow_mut().insert(context.id(), context);
     }
 
+    /// Get all the registered execution contexts which are still alive.
+    pub fn all(&self) -> Vec<Rc<execution_context::Synchronized>> {
+        self.0.borrow().iter().map(|(_, execution_context)| execution_context).collect()
+    }
+
     /// Adjust execution contexts after renaming the project.
     pub fn rename_project(&self, old_name: impl Str, new_name: impl Str) {
         self.0.borrow().iter().for_each(|(_, execution_context)| {
@@ -212,6 +224,109 @@ async fn update_modules_on_file_change(
 
 
 
+// ====================
+// === Reconnecting ===
+// ====================
+
+/// Wrap the opened socket into the transport reestablishing the lost connection. The new
+/// connections are made by the `connector`, and the transport's runner is spawned.
+fn reconnecting_transport(
+    mut socket: WebSocket,
+    connector: impl Fn() -> ConnectionFuture + 'static,
+) -> ReconnectingTransport {
+    socket.disable_auto_reconnect();
+    let transport = ReconnectingTransport::new(socket, connector);
+    executor::global::spawn(transport.runner());
+    transport
+}
+
+/// Restore the session state in the new Language Server session, after the JSON-RPC connection was
+/// reestablished. The capabilities, opened modules and execution contexts are not kept by the
+/// server between sessions.
+async fn restore_session(
+    language_server: Rc<language_server::Connection>,
+    module_registry: Rc<model::registry::Registry<module::Path, module::Synchronized>>,
+    execution_contexts: Rc<ExecutionContextsRegistry>,
+) -> FallibleResult {
+    let capability = CapabilityRegistration::create_receives_suggestions_database_updates();
+    let method = &capability.method;
+    let options = &capability.register_options;
+    language_server.acquire_capability(method, options).await?;
+    for module in module_registry.loaded() {
+        module.restore_in_language_server().await?;
+    }
+    for execution_context in execution_contexts.all() {
+        execution_context.restore().await?;
+    }
+    Ok(())
+}
+
+/// Open a new socket replacing the lost one. The socket reconnects only through the wrapping
+/// `ReconnectingTransport`.
+async fn reopen_socket(url: &str) -> FallibleResult<WebSocket> {
+    let mut socket = WebSocket::new_opened(url).await?;
+    socket.disable_auto_reconnect();
+    Ok(socket)
+}
+
+/// Reestablish the lost JSON-RPC connection to the Language Server. The protocol is initialized
+/// with the same client id as before, so the server can correlate the new connection with the
+/// binary one.
+async fn reconnect_json_rpc(
+    url: String,
+    client_id: Uuid,
+) -> FallibleResult<Box<dyn json_rpc::Transport>> {
+    let socket = reopen_socket(&url).await?;
+    let client = language_server::Client::new(socket.clone_ref());
+    executor::global::spawn(client.runner());
+    language_server::API::init_protocol_connection(&client, &client_id).await?;
+    Ok(Box::new(socket))
+}
+
+/// Reestablish the lost binary connection to the Language Server. The session is initialized with
+/// the same client id as before.
+async fn reconnect_binary(
+    url: String,
+    client_id: Uuid,
+) -> FallibleResult<Box<dyn json_rpc::Transport>> {
+    let socket = reopen_socket(&url).await?;
+    let client = binary::Client::new(socket.clone_ref());
+    executor::global::spawn(client.runner());
+    binary::API::init(&client, client_id).await?;
+    Ok(Box::new(socket))
+}
+
+
+
+// =======================
+// === Traffic Capture ===
+// =======================
+
+/// Create the clients of the Language Server connections. If the `traffic_capture` buffer is
+/// given, the traffic of both connections is recorded to it.
+fn language_server_clients(
+    json_transport: ReconnectingTransport,
+    binary_transport: ReconnectingTransport,
+    traffic_capture: Option<&capture::Buffer>,
+) -> (language_server::Client, binary::Client) {
+    match traffic_capture {
+        Some(buffer) => {
+            info!("Capturing the traffic of the Language Server connections.");
+            let recorder = capture::Recorder::new(buffer.clone_ref());
+            let json = RecordingTransport::new(json_transport, capture::Channel::Json, &recorder);
+            let binary =
+                RecordingTransport::new(binary_transport, capture::Channel::Binary, &recorder);
+            executor::global::spawn(json.runner());
+            executor::global::spawn(binary.runner());
+            (language_server::Client::new(json), binary::Client::new(binary))
+        }
+        None =>
+            (language_server::Client::new(json_transport), binary::Client::new(binary_transport)),
+    }
+}
+
+
+
 // =============
 // === Model ===
 // =============
@@ -306,11 +421,14 @@ pub struct Project {
     pub execution_contexts:  Rc<ExecutionContextsRegistry>,
     pub visualization:       controller::Visualization,
     pub suggestion_db:       Rc<SuggestionDatabase>,
+    pub suggestion_db_cache: Rc<suggestion_database::Cache>,
     pub content_roots:       Rc<ContentRoots>,
     pub parser:              Parser,
     pub notifications:       notification::Publisher<model::project::Notification>,
     pub urm:                 Rc<model::undo_redo::Manager>,
     pub read_only:           Rc<Cell<bool>>,
+    /// The recorded traffic of the Language Server connections, if it is captured.
+    pub traffic_capture:     Option<capture::Buffer>,
 }
 
 impl Project {
@@ -334,7 +452,12 @@ impl Project {
         let visualization =
             controller::Visualization::new(language_server, embedded_visualizations);
         let language_server = &*language_server_rpc;
-        let suggestion_db = SuggestionDatabase::create_synchronized(language_server);
+        let engine_version = properties.engine_version.to_string();
+        let suggestion_db_cache = Rc::new(suggestion_database::Cache::new(engine_version));
+        let suggestion_db = SuggestionDatabase::create_synchronized_with_cache(
+            language_server,
+            &suggestion_db_cache,
+        );
         let suggestion_db = Rc::new(suggestion_db.await.map_err(&wrap)?);
         let content_roots = ContentRoots::new_from_connection(language_server);
         let content_roots = Rc::new(content_roots);
@@ -351,11 +474,13 @@ impl Project {
             execution_contexts,
             visualization,
             suggestion_db,
+            suggestion_db_cache,
             content_roots,
             parser,
             notifications,
             urm,
             read_only: default(),
+            traffic_capture: None,
         };
 
         let binary_handler = ret.binary_event_handler();
@@ -381,8 +506,18 @@ impl Project {
         let client_id = Uuid::new_v4();
         let json_ws = WebSocket::new_opened(&language_server_rpc).await?;
         let binary_ws = WebSocket::new_opened(&language_server_bin).await?;
-        let client_json = language_server::Client::new(json_ws);
-        let client_binary = binary::Client::new(binary_ws);
+        let json_connector =
+            move || reconnect_json_rpc(language_server_rpc.clone(), client_id).boxed_local();
+        let binary_connector =
+            move || reconnect_binary(language_server_bin.clone(), client_id).boxed_local();
+        let json_transport = reconnecting_transport(json_ws, json_connector);
+        let read_only_methods = language_server::constants::READ_ONLY_METHODS.iter().copied();
+        json_transport.set_resend_policy(resend_methods(read_only_methods));
+        let binary_transport = reconnecting_transport(binary_ws, binary_connector);
+        let capture_traffic = enso_config::ARGS.groups.engine.options.capture_traffic.value;
+        let traffic_capture = capture_traffic.then(capture::Buffer::default);
+        let (client_json, client_binary) =
+            language_server_clients(json_transport, binary_transport, traffic_capture.as_ref());
         crate::executor::global::spawn(client_json.runner());
         crate::executor::global::spawn(client_binary.runner());
         let connection_json =
@@ -391,9 +526,10 @@ impl Project {
             binary::Connection::new(client_binary, client_id).await.map_err(&wrap)?;
         let language_server_rpc = Rc::new(connection_json);
         let language_server_bin = Rc::new(connection_binary);
-        let model =
+        let mut model =
             Self::new(project_manager, language_server_rpc, language_server_bin, properties)
                 .await?;
+        model.traffic_capture = traffic_capture;
         Ok(Rc::new(model))
     }
 
@@ -452,9 +588,18 @@ impl Project {
                     let which = model::project::BackendConnection::LanguageServerBinary;
                     let notification = model::project::Notification::ConnectionLost(which);
                     publisher.notify(notification);
-                    // TODO [wmu]
-                    //   The connection should be reestablished, see
-                    //   https://github.com/enso-org/ide/issues/145
+                }
+                Event::Reconnecting { attempt } => {
+                    warn!("Reconnecting the binary connection, attempt {attempt}.");
+                    let which = model::project::BackendConnection::LanguageServerBinary;
+                    let notification = model::project::Notification::Reconnecting(which);
+                    publisher.notify(notification);
+                }
+                Event::Reconnected => {
+                    info!("Reconnected the binary connection.");
+                    let which = model::project::BackendConnection::LanguageServerBinary;
+                    let notification = model::project::Notification::Reconnected(which);
+                    publisher.notify(notification);
                 }
                 Event::Error(error) => {
                     error!("Error emitted by the binary data connection: {error}.");
@@ -502,8 +647,10 @@ impl Project {
         let project_root_id = self.project_content_root_id();
         let language_server = self.json_rpc().clone_ref();
         let weak_suggestion_db = Rc::downgrade(&self.suggestion_db);
+        let suggestion_db_cache = self.suggestion_db_cache.clone_ref();
         let weak_content_roots = Rc::downgrade(&self.content_roots);
         let weak_module_registry = Rc::downgrade(&self.module_registry);
+        let weak_execution_contexts = Rc::downgrade(&self.execution_contexts);
         let execution_update_handler = self.execution_update_handler();
         move |event| {
             debug!("Received an event from the json-rpc protocol: {event:?}");
@@ -565,6 +712,15 @@ impl Project {
                 }
                 Event::Notification(Notification::SuggestionDatabaseUpdates(update)) =>
                     if let Some(suggestion_db) = weak_suggestion_db.upgrade() {
+                        let cache = suggestion_db_cache.clone_ref();
+                        let language_server = language_server.clone_ref();
+                        let cached_update = update.clone();
+                        executor::global::spawn(async move {
+                            let stored = cache.store_update(&language_server, cached_update);
+                            if let Err(err) = stored.await {
+                                warn!("Failed to store the suggestion database update: {err}");
+                            }
+                        });
                         suggestion_db.apply_update_event(update);
                     },
                 Event::Notification(Notification::ContentRootAdded { root }) => {
@@ -592,9 +748,33 @@ impl Project {
                     let which = model::project::BackendConnection::LanguageServerJson;
                     let notification = model::project::Notification::ConnectionLost(which);
                     publisher.notify(notification);
-                    // TODO [wmu]
-                    //  The connection should be reestablished,
-                    //  see https://github.com/enso-org/ide/issues/145
+                }
+                Event::Reconnecting { attempt } => {
+                    warn!("Reconnecting to the Language Server, attempt {attempt}.");
+                    let which = model::project::BackendConnection::LanguageServerJson;
+                    let notification = model::project::Notification::Reconnecting(which);
+                    publisher.notify(notification);
+                }
+                Event::Reconnected => {
+                    info!("Reconnected to the Language Server.");
+                    let modules = weak_module_registry.upgrade();
+                    let contexts = weak_execution_contexts.upgrade();
+                    if let (Some(modules), Some(contexts)) = (modules, contexts) {
+                        let restored =
+                            restore_session(language_server.clone_ref(), modules, contexts);
+                        let publisher = publisher.clone_ref();
+                        executor::global::spawn(async move {
+                            let which = model::project::BackendConnection::LanguageServerJson;
+                            let notification = match restored.await {
+                                Ok(()) => model::project::Notification::Reconnected(which),
+                                Err(err) => {
+                                    error!("Failed to restore the Language Server session: {err}");
+                                    model::project::Notification::SessionNotRestored(which)
+                                }
+                            };
+                            publisher.notify(notification);
+                        });
+                    }
                 }
                 Event::Error(error) => {
                     error!("Error emitted by the JSON-RPC data connection: {error}.");
@@ -767,6 +947,17 @@ impl model::project::API for Project {
     }
 }
 
+impl Drop for Project {
+    fn drop(&mut self) {
+        if let Some(traffic_capture) = &self.traffic_capture {
+            let file_name = format!("{}-traffic.jsonl", self.properties.borrow().name.project);
+            if let Err(err) = transport::capture::download(traffic_capture, &file_name) {
+                error!("Failed to save the capture of the Language Server traffic: {err}");
+            }
+        }
+    }
+}
+
 
 
 // =============
@@ -809,9 +1000,17 @@ mod test {
             binary_client.expect_event_stream().return_once(|| binary_events.boxed_local());
             let json_events_sender = json_client.setup_events();
 
+            let cache_path = cache_file_path(&suggestion_database::cache::SNAPSHOT_FILE_PATH);
+            let cache_error = RpcError::new_remote_error(code::FILE_NOT_FOUND, "File not found");
+            expect_call!(json_client.read_file(cache_path) => Err(cache_error));
+            let db_version = response::GetSuggestionDatabaseVersion { current_version: 0 };
+            expect_call!(json_client.get_suggestions_database_version() => Ok(db_version));
             let initial_suggestions_db =
                 response::GetSuggestionDatabase { entries: vec![], current_version: 0 };
             expect_call!(json_client.get_suggestions_database() => Ok(initial_suggestions_db));
+            // The snapshot and the empty update log of the suggestion database cache.
+            json_client.expect.write_file(|_, _| Ok(()));
+            json_client.expect.write_file(|_, _| Ok(()));
             let capability_reg =
                 CapabilityRegistration::create_receives_suggestions_database_updates();
             let method = capability_reg.method;
@@ -839,13 +1038,21 @@ mod test {
         }
     }
 
+    fn cache_file_path(segments: &[&str]) -> language_server::Path {
+        language_server::Path::new(Uuid::default(), segments)
+    }
+
     #[wasm_bindgen_test]
     fn notify_backend_connection_lost() {
         use crate::model::project::BackendConnection::*;
         use crate::model::project::Notification;
 
-        fn run(expected_event: Notification, close_socket: impl FnOnce(&mut Fixture)) {
-            let mut f = Fixture::new(|_| {}, |_| {});
+        fn run_with_mock(
+            expected_event: Notification,
+            setup_mock_json: impl FnOnce(&mut language_server::MockClient),
+            close_socket: impl FnOnce(&mut Fixture),
+        ) {
+            let mut f = Fixture::new(setup_mock_json, |_| {});
             let mut events = f.project.subscribe().boxed_local();
             events.expect_pending();
             close_socket(&mut f);
@@ -855,13 +1062,108 @@ mod test {
             assert_eq!(event, expected_event);
         }
 
+        fn run(expected_event: Notification, close_socket: impl FnOnce(&mut Fixture)) {
+            run_with_mock(expected_event, |_| {}, close_socket)
+        }
+
         run(Notification::ConnectionLost(LanguageServerBinary), |f| {
             f.binary_events_sender.send(binary::Event::Closed).boxed_local().expect_ok();
         });
 
+        run(Notification::Reconnecting(LanguageServerBinary), |f| {
+            let event = binary::Event::Reconnecting { attempt: 1 };
+            f.binary_events_sender.send(event).boxed_local().expect_ok();
+        });
+
+        run(Notification::Reconnected(LanguageServerBinary), |f| {
+            f.binary_events_sender.send(binary::Event::Reconnected).boxed_local().expect_ok();
+        });
+
         run(Notification::ConnectionLost(LanguageServerJson), |f| {
             f.json_events_sender.send(json_rpc::Event::Closed).boxed_local().expect_ok();
         });
+
+        run(Notification::Reconnecting(LanguageServerJson), |f| {
+            let event = json_rpc::Event::Reconnecting { attempt: 1 };
+            f.json_events_sender.send(event).boxed_local().expect_ok();
+        });
+
+        // The capabilities are acquired again in the new session.
+        let reacquire_capability = |ls_json: &mut language_server::MockClient| {
+            let capability_reg =
+                CapabilityRegistration::create_receives_suggestions_database_updates();
+            let method = capability_reg.method;
+            let options = capability_reg.register_options;
+            expect_call!(ls_json.acquire_capability(method,options) => Ok(()));
+        };
+        run_with_mock(Notification::Reconnected(LanguageServerJson), reacquire_capability, |f| {
+            f.json_events_sender.send(json_rpc::Event::Reconnected).boxed_local().expect_ok();
+        });
+
+        // The user is asked to reload when the session state cannot be restored.
+        let fail_to_acquire_capability = |ls_json: &mut language_server::MockClient| {
+            let capability_reg =
+                CapabilityRegistration::create_receives_suggestions_database_updates();
+            let method = capability_reg.method;
+            let options = capability_reg.register_options;
+            let error = RpcError::LostConnection;
+            expect_call!(ls_json.acquire_capability(method,options) => Err(error));
+        };
+        let expected_event = Notification::SessionNotRestored(LanguageServerJson);
+        run_with_mock(expected_event, fail_to_acquire_capability, |f| {
+            f.json_events_sender.send(json_rpc::Event::Reconnected).boxed_local().expect_ok();
+        });
+    }
+
+    #[wasm_bindgen_test]
+    fn restore_session_after_reconnecting() {
+        use crate::model::project::BackendConnection::LanguageServerJson;
+        use crate::model::project::Notification;
+        use execution_context::synchronized::test::Fixture as ExecutionFixture;
+
+        let path = module::Path::from_mock_module_name("TestModule");
+        let file_path = path.file_path().clone();
+        let context_data = execution_context::plain::test::MockData::new();
+        let Fixture { mut test, project, json_events_sender, .. } = Fixture::new(
+            |ls_json| {
+                mock_calls_for_opening_text_file(ls_json, file_path.clone(), "2+2");
+                ExecutionFixture::mock_default_calls(&context_data, ls_json);
+
+                // The calls made in the new session.
+                let capability_reg =
+                    CapabilityRegistration::create_receives_suggestions_database_updates();
+                let method = capability_reg.method;
+                let options = capability_reg.register_options;
+                expect_call!(ls_json.acquire_capability(method,options) => Ok(()));
+                let content = "2+2".to_string();
+                let current_version = Sha3_224::new(content.as_bytes());
+                let write_capability = None;
+                let open_response =
+                    response::OpenTextFile { content, current_version, write_capability };
+                expect_call!(ls_json.open_text_file(path=file_path.clone()) => Ok(open_response));
+                let capability_reg =
+                    CapabilityRegistration::create_can_edit_text_file(file_path.clone());
+                let method = capability_reg.method;
+                let options = capability_reg.register_options;
+                expect_call!(ls_json.acquire_capability(method,options) => Ok(()));
+                ls_json.expect.apply_text_file_edit(|_, _| Ok(()));
+                ExecutionFixture::mock_restore_calls(&context_data, ls_json);
+                ls_json.require_all_calls();
+            },
+            |_| {},
+        );
+        let mut events = project.subscribe().boxed_local();
+
+        let module = project.module(path);
+        let _module = test.expect_completion(module).unwrap();
+        let execution = project
+            .create_execution_context(context_data.main_method_pointer(), context_data.context_id);
+        let _execution = test.expect_completion(execution).unwrap();
+        events.expect_pending();
+
+        json_events_sender.unbounded_send(json_rpc::Event::Reconnected).unwrap();
+        test.run_until_stalled();
+        assert_eq!(events.expect_next(), Notification::Reconnected(LanguageServerJson));
     }
 
     #[wasm_bindgen_test]
@@ -961,6 +1263,37 @@ mod test {
     }
 
 
+    // === Suggestion database cache ===
+
+    #[wasm_bindgen_test]
+    fn caching_suggestion_db_updates() {
+        use engine_protocol::language_server::Notification::SuggestionDatabaseUpdates;
+        use engine_protocol::language_server::SuggestionDatabaseUpdatesEvent;
+        use suggestion_database::cache::UpdateLog;
+        use suggestion_database::cache::UPDATE_LOG_FILE_PATH;
+
+        let update = SuggestionDatabaseUpdatesEvent { updates: vec![], current_version: 1 };
+        let update_log = UpdateLog {
+            engine_version:   "0.2.1".to_owned(),
+            snapshot_version: 0,
+            events:           vec![update.clone()],
+        };
+        let mut f = Fixture::new(
+            |client| {
+                let path = cache_file_path(&UPDATE_LOG_FILE_PATH);
+                let contents = serde_json::to_string(&update_log).unwrap();
+                expect_call!(client.write_file(path, contents) => Ok(()));
+                client.require_all_calls();
+            },
+            |_| {},
+        );
+        let notification = SuggestionDatabaseUpdates(update);
+        let event = json_rpc::Event::Notification(notification);
+        f.json_events_sender.send(event).boxed_local().expect_ok();
+        f.test.run_until_stalled();
+    }
+
+
     // === VCS status check ===
 
     #[wasm_bindgen_test]
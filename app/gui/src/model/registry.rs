@@ -133,6 +133,16 @@ where K: Clone + Eq + Hash
         }
     }
 
+    /// Get all items which are currently loaded. The items being loaded are skipped.
+    pub fn loaded(&self) -> Vec<Rc<V>> {
+        let registry = self.registry.borrow();
+        let loaded = registry.iter().filter_map(|(_, entry)| match entry {
+            Entry::Loaded(item) => Some(item),
+            Entry::Loading(_) => None,
+        });
+        loaded.collect()
+    }
+
     async fn load<F, E>(&self, key: K, loader: F) -> Result<Rc<V>, E>
     where F: Future<Output = Result<Rc<V>, E>> {
         let publisher = Publisher::default();
@@ -192,6 +202,29 @@ mod test {
         });
     }
 
+    #[test]
+    fn listing_loaded_modules() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            let line = ast::Ast::infix_var("a", "+", "b");
+            let ast = ast::Ast::one_line_module(line).try_into().unwrap();
+            let path = ModulePath::from_mock_module_name("Test");
+            let state = Rc::new(Plain::new(path.clone(), ast, default(), default(), default()));
+            let registry = Registry::default();
+            assert!(registry.loaded().is_empty());
+
+            let loader = async { Ok(state.clone_ref()) };
+            registry.get_or_load(path, loader).await.unwrap();
+            let loaded = registry.loaded();
+            assert_eq!(loaded.len(), 1);
+            assert!(Rc::ptr_eq(&loaded[0], &state));
+
+            drop(loaded);
+            drop(state);
+            assert!(registry.loaded().is_empty());
+        });
+    }
+
     #[test]
     fn getting_module_during_load() {
         let line = ast::Ast::infix_var("a", "+", "b");
@@ -605,6 +605,23 @@ main =
         });
     }
 
+    // Positions of all nodes are set.
+    #[test]
+    fn tidy_layout_atomic() {
+        let code = r#"
+main =
+    foo = 2
+    bar = foo + 6
+    baz = 2 + foo + bar
+"#;
+        check_atomic_graph_action(code, |graph| {
+            graph.tidy_layout(&default()).unwrap();
+            for node in graph.nodes().unwrap() {
+                assert!(node.metadata.and_then(|metadata| metadata.position).is_some());
+            }
+        });
+    }
+
     // A complex operation: involves introducing variable name, reordering lines and
     // replacing an argument.
     #[test]
@@ -431,6 +431,21 @@ impl Project {
                     let message = view::status_bar::event::Label::from(message);
                     model.status_bar.add_event(message);
                 }
+                Notification::Reconnecting(_) => {
+                    let message = crate::BACKEND_RECONNECTING_MESSAGE;
+                    let message = view::status_bar::event::Label::from(message);
+                    model.status_bar.add_event(message);
+                }
+                Notification::Reconnected(_) => {
+                    let message = crate::BACKEND_RECONNECTED_MESSAGE;
+                    let message = view::status_bar::event::Label::from(message);
+                    model.status_bar.add_event(message);
+                }
+                Notification::SessionNotRestored(_) => {
+                    let message = crate::BACKEND_SESSION_NOT_RESTORED_MESSAGE;
+                    let message = view::status_bar::event::Label::from(message);
+                    model.status_bar.add_event(message);
+                }
                 Notification::VcsStatusChanged(VcsStatus::Dirty) => {
                     model.set_project_changed(true);
                 }
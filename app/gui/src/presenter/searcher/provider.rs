@@ -96,23 +96,7 @@ impl list_view::entry::ModelProvider<GlyphHighlightedLabel> for Action {
         let action = self.actions.get_cloned(id)?;
         if let MatchInfo::Matches { subsequence, .. } = action.match_info {
             let label = action.action.to_string();
-            let mut char_iter = label.char_indices().enumerate();
-            let highlighted = subsequence
-                .indices
-                .iter()
-                .filter_map(|idx| loop {
-                    if let Some(char) = char_iter.next() {
-                        let (char_idx, (byte_id, char)) = char;
-                        if char_idx == *idx {
-                            let start = enso_text::index::Byte(byte_id);
-                            let end = enso_text::index::Byte(byte_id + char.len_utf8());
-                            break Some(enso_text::Range::new(start, end));
-                        }
-                    } else {
-                        break None;
-                    }
-                })
-                .collect();
+            let highlighted = bytes_of_subsequence(&subsequence, &label);
             Some(list_view::entry::GlyphHighlightedLabelModel { label, highlighted })
         } else {
             None
@@ -339,28 +323,20 @@ fn component_to_entry_model(component: &component::Component) -> component_grid:
 
 fn bytes_of_matched_letters(match_info: &MatchInfo, label: &str) -> Vec<text::Range<text::Byte>> {
     if let MatchInfo::Matches { subsequence, .. } = match_info {
-        let mut char_iter = label.char_indices().enumerate();
-        subsequence
-            .indices
-            .iter()
-            .filter_map(|idx| loop {
-                if let Some(char) = char_iter.next() {
-                    let (char_idx, (byte_id, char)) = char;
-                    if char_idx == *idx {
-                        let start = enso_text::index::Byte(byte_id);
-                        let end = enso_text::index::Byte(byte_id + char.len_utf8());
-                        break Some(enso_text::Range::new(start, end));
-                    }
-                } else {
-                    break None;
-                }
-            })
-            .collect()
+        bytes_of_subsequence(subsequence, label)
     } else {
         default()
     }
 }
 
+fn bytes_of_subsequence(
+    subsequence: &fuzzly::Subsequence,
+    label: &str,
+) -> Vec<text::Range<text::Byte>> {
+    let ranges = subsequence.byte_ranges(label).into_iter();
+    ranges.map(|range| text::Range::new(text::Byte(range.start), text::Byte(range.end))).collect()
+}
+
 
 
 // ===========================
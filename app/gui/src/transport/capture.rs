@@ -0,0 +1,50 @@
+//! Saving the captures of the backend connections' traffic, recorded by the
+//! [`RecordingTransport`](engine_protocol::capture::RecordingTransport).
+
+use crate::prelude::*;
+use enso_web::traits::*;
+
+use engine_protocol::capture::Buffer;
+use wasm_bindgen::JsCast;
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(display = "Failed to download the capture: {}.", _0)]
+pub struct DownloadError(String);
+
+impl DownloadError {
+    /// Create the error from a JS value describing it.
+    fn from_js(js_val: impl AsRef<enso_web::JsValue>) -> Self {
+        DownloadError(js_val.as_ref().print_to_string())
+    }
+}
+
+
+
+// ================
+// === Download ===
+// ================
+
+/// Save the capture as a file downloaded by the browser.
+pub fn download(capture: &Buffer, file_name: &str) -> FallibleResult {
+    let no_document = || DownloadError("No document.".into());
+    let document = web_sys::window().and_then(|window| window.document());
+    let document = document.ok_or_else(no_document)?;
+    let data = js_sys::Uint8Array::from(capture.contents().as_slice());
+    let parts = js_sys::Array::of1(&data);
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(DownloadError::from_js)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(DownloadError::from_js)?;
+    let anchor = document.create_element("a").map_err(DownloadError::from_js)?;
+    let anchor = anchor.dyn_into::<web_sys::HtmlAnchorElement>().map_err(DownloadError::from_js)?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url).map_err(DownloadError::from_js)?;
+    Ok(())
+}
@@ -290,8 +290,11 @@ impl WebSocket {
         let model = Rc::downgrade(&self.model);
         move |_| {
             if let Some(model) = model.upgrade() {
-                if let Err(e) = model.borrow_mut().reconnect() {
-                    error!("Failed to reconnect: {}", e.print_to_string());
+                let mut model = model.borrow_mut();
+                if model.auto_reconnect {
+                    if let Err(e) = model.reconnect() {
+                        error!("Failed to reconnect: {}", e.print_to_string());
+                    }
                 }
             }
         }
@@ -326,6 +329,12 @@ impl WebSocket {
         }
     }
 
+    /// Disables reconnecting automatically when the connection is lost. Used when the connection
+    /// is reestablished by the wrapping `ReconnectingTransport` instead.
+    pub fn disable_auto_reconnect(&mut self) {
+        self.model.borrow_mut().auto_reconnect = false;
+    }
+
     /// Checks the current state of the connection.
     pub fn state(&self) -> State {
         State::query_ws(&self.model.borrow().socket)
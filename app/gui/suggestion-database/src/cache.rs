@@ -0,0 +1,522 @@
+//! The on-disk cache of the suggestion database.
+//!
+//! The cache keeps the Language Server's response to `search/getSuggestionsDatabase` (the
+//! snapshot) together with the [`SuggestionDatabaseUpdatesEvent`]s received after it. The
+//! snapshot and the update log are stored in separate files in the project directory, so only
+//! the small update log is rewritten when a new update arrives. The files are written one at a
+//! time, in the order of the changes, so an older update log never overwrites a newer one. See
+//! [`SuggestionDatabase::create_synchronized_with_cache`].
+
+use crate::prelude::*;
+
+use engine_protocol::language_server;
+use engine_protocol::language_server::response::GetSuggestionDatabase;
+use engine_protocol::language_server::SuggestionDatabaseUpdatesEvent;
+use engine_protocol::language_server::SuggestionsDatabaseVersion;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+#[cfg(doc)]
+use crate::SuggestionDatabase;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The path of the snapshot file, relative to the project root.
+pub const SNAPSHOT_FILE_PATH: [&str; 3] = [".enso", "cache", "suggestion-database.json"];
+
+/// The path of the update log file, relative to the project root.
+pub const UPDATE_LOG_FILE_PATH: [&str; 3] = [".enso", "cache", "suggestion-database-updates.json"];
+
+/// The maximum number of update events kept in the update log. When the log is full, the current
+/// database is fetched from the Language Server and stored as a new snapshot, starting an empty
+/// log.
+pub const MAX_CACHED_UPDATES: usize = 256;
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Fail)]
+#[fail(
+    display = "The suggestion database cache was created by the engine version {}, while {} is \
+    running.",
+    cached, current
+)]
+pub struct EngineVersionMismatch {
+    cached:  String,
+    current: String,
+}
+
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Fail)]
+#[fail(display = "The update log of the suggestion database cache does not match the snapshot.")]
+pub struct UpdateLogMismatch;
+
+
+
+// ================
+// === Snapshot ===
+// ================
+
+/// The content of the snapshot file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// The version of the engine which provided the database.
+    pub engine_version: String,
+    /// The whole database, as received from the Language Server.
+    pub database:       GetSuggestionDatabase,
+}
+
+
+
+// =================
+// === UpdateLog ===
+// =================
+
+/// The content of the update log file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLog {
+    /// The version of the engine which provided the updates.
+    pub engine_version:   String,
+    /// The version of the snapshot the updates should be applied to.
+    pub snapshot_version: SuggestionsDatabaseVersion,
+    /// The updates received after the snapshot, in the order of receiving.
+    pub events:           Vec<SuggestionDatabaseUpdatesEvent>,
+}
+
+impl UpdateLog {
+    /// Create an empty log of updates to the given snapshot.
+    pub fn new(snapshot: &Snapshot) -> Self {
+        let engine_version = snapshot.engine_version.clone();
+        let snapshot_version = snapshot.database.current_version;
+        Self { engine_version, snapshot_version, events: default() }
+    }
+}
+
+
+
+// ======================
+// === CachedDatabase ===
+// ======================
+
+/// The suggestion database loaded from the cache.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CachedDatabase {
+    /// The snapshot of the whole database.
+    pub snapshot: Snapshot,
+    /// The updates to be applied to the snapshot.
+    pub updates:  UpdateLog,
+}
+
+impl CachedDatabase {
+    /// The version of the database after applying all the cached updates.
+    pub fn version(&self) -> SuggestionsDatabaseVersion {
+        let last_update = self.updates.events.last();
+        last_update.map_or(self.snapshot.database.current_version, |event| event.current_version)
+    }
+}
+
+
+
+// =============
+// === Write ===
+// =============
+
+/// A pending write of one of the cache files.
+#[derive(Clone, Debug)]
+struct Write {
+    path:     language_server::Path,
+    contents: String,
+}
+
+
+
+// =============
+// === Cache ===
+// =============
+
+/// The handle to the suggestion database cache of the project.
+///
+/// The cache is valid only for the engine version it was created by, as the suggestions of
+/// different engine versions may differ, even if the database version is the same.
+#[derive(Debug)]
+pub struct Cache {
+    engine_version: String,
+    update_log:     RefCell<Option<UpdateLog>>,
+    /// Set while a new snapshot is being fetched because the update log is full.
+    fetching:       Cell<bool>,
+    write_queue:    RefCell<VecDeque<Write>>,
+    writing:        Cell<bool>,
+}
+
+impl Cache {
+    /// Constructor.
+    pub fn new(engine_version: impl Into<String>) -> Self {
+        Self {
+            engine_version: engine_version.into(),
+            update_log:     default(),
+            fetching:       default(),
+            write_queue:    default(),
+            writing:        default(),
+        }
+    }
+
+    /// Read the cached database from the project directory. Fails if the cache files are missing
+    /// or were created by a different engine version.
+    pub async fn load(
+        &self,
+        language_server: &language_server::Connection,
+    ) -> FallibleResult<CachedDatabase> {
+        let snapshot_path = Self::path(language_server, &SNAPSHOT_FILE_PATH);
+        let snapshot = language_server.client.read_file(&snapshot_path).await?;
+        let snapshot: Snapshot = serde_json::from_str(&snapshot.contents)?;
+        if snapshot.engine_version != self.engine_version {
+            let cached = snapshot.engine_version;
+            let current = self.engine_version.clone();
+            return Err(EngineVersionMismatch { cached, current }.into());
+        }
+        let update_log_path = Self::path(language_server, &UPDATE_LOG_FILE_PATH);
+        let updates = language_server.client.read_file(&update_log_path).await?;
+        let updates: UpdateLog = serde_json::from_str(&updates.contents)?;
+        let engine_version_matches = updates.engine_version == snapshot.engine_version;
+        let snapshot_version_matches =
+            updates.snapshot_version == snapshot.database.current_version;
+        if !engine_version_matches || !snapshot_version_matches {
+            return Err(UpdateLogMismatch.into());
+        }
+        self.update_log.replace(Some(updates.clone()));
+        Ok(CachedDatabase { snapshot, updates })
+    }
+
+    /// Write a new snapshot of the database to the project directory, together with an empty
+    /// update log.
+    pub async fn store_snapshot(
+        &self,
+        language_server: &language_server::Connection,
+        database: GetSuggestionDatabase,
+    ) -> FallibleResult {
+        let snapshot = Snapshot { engine_version: self.engine_version.clone(), database };
+        let update_log = UpdateLog::new(&snapshot);
+        let snapshot_json = serde_json::to_string(&snapshot)?;
+        let update_log_json = serde_json::to_string(&update_log)?;
+        self.update_log.replace(Some(update_log));
+        let snapshot_path = Self::path(language_server, &SNAPSHOT_FILE_PATH);
+        let update_log_path = Self::path(language_server, &UPDATE_LOG_FILE_PATH);
+        self.enqueue_write(snapshot_path, snapshot_json);
+        self.enqueue_write(update_log_path, update_log_json);
+        self.write_queued(language_server).await
+    }
+
+    /// Record the update event in the update log and write the log to the project directory.
+    ///
+    /// Does nothing if there is no snapshot the update could be applied to. If the log is full,
+    /// the current database is fetched from the Language Server and stored as a new snapshot
+    /// instead.
+    pub async fn store_update(
+        &self,
+        language_server: &language_server::Connection,
+        event: SuggestionDatabaseUpdatesEvent,
+    ) -> FallibleResult {
+        let update_log_json = {
+            let mut update_log = self.update_log.borrow_mut();
+            match &mut *update_log {
+                Some(log) => {
+                    log.events.push(event);
+                    let is_full = log.events.len() >= MAX_CACHED_UPDATES;
+                    // The updates received while fetching the new snapshot are still written to
+                    // the current log.
+                    if is_full && !self.fetching.get() {
+                        None
+                    } else {
+                        Some(serde_json::to_string(log)?)
+                    }
+                }
+                None => return Ok(()),
+            }
+        };
+        match update_log_json {
+            Some(json) => {
+                self.enqueue_write(Self::path(language_server, &UPDATE_LOG_FILE_PATH), json);
+                self.write_queued(language_server).await
+            }
+            None => self.store_current_snapshot(language_server).await,
+        }
+    }
+
+    /// Fetch the current database from the Language Server and store it as a new snapshot. The
+    /// updates received while fetching which are not included in the fetched database are kept in
+    /// the new update log.
+    async fn store_current_snapshot(
+        &self,
+        language_server: &language_server::Connection,
+    ) -> FallibleResult {
+        self.fetching.set(true);
+        let database = language_server.client.get_suggestions_database().await;
+        self.fetching.set(false);
+        let database = database?;
+        let snapshot = Snapshot { engine_version: self.engine_version.clone(), database };
+        let mut update_log = UpdateLog::new(&snapshot);
+        if let Some(log) = &*self.update_log.borrow() {
+            let version = update_log.snapshot_version;
+            let is_newer =
+                |event: &&SuggestionDatabaseUpdatesEvent| event.current_version > version;
+            update_log.events = log.events.iter().filter(is_newer).cloned().collect();
+        }
+        let snapshot_json = serde_json::to_string(&snapshot)?;
+        let update_log_json = serde_json::to_string(&update_log)?;
+        self.update_log.replace(Some(update_log));
+        self.enqueue_write(Self::path(language_server, &SNAPSHOT_FILE_PATH), snapshot_json);
+        self.enqueue_write(Self::path(language_server, &UPDATE_LOG_FILE_PATH), update_log_json);
+        self.write_queued(language_server).await
+    }
+
+    fn enqueue_write(&self, path: language_server::Path, contents: String) {
+        self.write_queue.borrow_mut().push_back(Write { path, contents });
+    }
+
+    /// Write the queued files one by one, in the order of enqueuing. If another task is already
+    /// writing, the queued files are left to it, so the files are never written concurrently.
+    async fn write_queued(&self, language_server: &language_server::Connection) -> FallibleResult {
+        if self.writing.replace(true) {
+            return Ok(());
+        }
+        let mut result = Ok(());
+        loop {
+            let next = self.write_queue.borrow_mut().pop_front();
+            let Some(write) = next else { break };
+            let written = language_server.client.write_file(&write.path, &write.contents).await;
+            if let Err(err) = written {
+                result = Err(err.into());
+            }
+        }
+        self.writing.set(false);
+        result
+    }
+
+    fn path(
+        language_server: &language_server::Connection,
+        segments: &[&str],
+    ) -> language_server::Path {
+        language_server::Path::new(language_server.project_root().id(), segments)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::entry;
+    use crate::SuggestionDatabase;
+
+    use engine_protocol::common::error::code;
+    use engine_protocol::language_server::response;
+    use engine_protocol::language_server::SuggestionEntry;
+    use engine_protocol::language_server::SuggestionsDatabaseEntry;
+    use enso_executor::test_utils::TestWithLocalPoolExecutor;
+    use json_rpc::error::RpcError;
+    use json_rpc::expect_call;
+
+    const ENGINE_VERSION: &str = "0.2.1";
+
+    fn constructor(name: &str) -> SuggestionEntry {
+        SuggestionEntry::Constructor {
+            name:          name.to_owned(),
+            module:        "test.TestProject.TestModule".to_owned(),
+            arguments:     vec![],
+            return_type:   "test.TestProject.TestModule.TestAtom".to_owned(),
+            documentation: None,
+            external_id:   None,
+            reexport:      None,
+        }
+    }
+
+    fn database(current_version: SuggestionsDatabaseVersion) -> GetSuggestionDatabase {
+        let entry = SuggestionsDatabaseEntry { id: 1, suggestion: constructor("First") };
+        GetSuggestionDatabase { entries: vec![entry], current_version }
+    }
+
+    fn add_event(
+        id: entry::Id,
+        current_version: SuggestionsDatabaseVersion,
+    ) -> SuggestionDatabaseUpdatesEvent {
+        let suggestion = Box::new(constructor("Second"));
+        let updates = vec![entry::Update::Add { id, suggestion }];
+        SuggestionDatabaseUpdatesEvent { updates, current_version }
+    }
+
+    fn snapshot_path() -> language_server::Path {
+        language_server::Path::new(default(), &SNAPSHOT_FILE_PATH)
+    }
+
+    fn update_log_path() -> language_server::Path {
+        language_server::Path::new(default(), &UPDATE_LOG_FILE_PATH)
+    }
+
+    fn expect_read<T: Serialize>(
+        client: &mut language_server::MockClient,
+        path: language_server::Path,
+        content: &T,
+    ) {
+        let contents = serde_json::to_string(content).unwrap();
+        expect_call!(client.read_file(path=path) => Ok(response::Read { contents }));
+    }
+
+    fn expect_write<T: Serialize>(
+        client: &mut language_server::MockClient,
+        path: language_server::Path,
+        content: &T,
+    ) {
+        let contents = serde_json::to_string(content).unwrap();
+        expect_call!(client.write_file(path=path, contents=contents) => Ok(()));
+    }
+
+    fn expect_full_fetch(
+        client: &mut language_server::MockClient,
+        database: GetSuggestionDatabase,
+    ) {
+        let snapshot = Snapshot { engine_version: ENGINE_VERSION.to_owned(), database };
+        expect_call!(client.get_suggestions_database() => Ok(snapshot.database.clone()));
+        expect_write(client, snapshot_path(), &snapshot);
+        expect_write(client, update_log_path(), &UpdateLog::new(&snapshot));
+    }
+
+    fn create_db(client: language_server::MockClient, cache: &Cache) -> SuggestionDatabase {
+        let mut fixture = TestWithLocalPoolExecutor::set_up();
+        client.require_all_calls();
+        let connection = language_server::Connection::new_mock(client);
+        let db = SuggestionDatabase::create_synchronized_with_cache(&connection, cache);
+        fixture.expect_completion(db).unwrap()
+    }
+
+    #[test]
+    fn creating_database_from_up_to_date_cache() {
+        let mut client = language_server::MockClient::default();
+        let snapshot =
+            Snapshot { engine_version: ENGINE_VERSION.to_owned(), database: database(3) };
+        let updates = UpdateLog { events: vec![add_event(2, 4)], ..UpdateLog::new(&snapshot) };
+        expect_read(&mut client, snapshot_path(), &snapshot);
+        expect_read(&mut client, update_log_path(), &updates);
+        let version = response::GetSuggestionDatabaseVersion { current_version: 4 };
+        expect_call!(client.get_suggestions_database_version() => Ok(version));
+
+        let db = create_db(client, &Cache::new(ENGINE_VERSION));
+        assert_eq!(db.lookup(1).unwrap().name, "First");
+        assert_eq!(db.lookup(2).unwrap().name, "Second");
+        assert_eq!(db.version.get(), 4);
+    }
+
+    #[test]
+    fn replacing_outdated_cache() {
+        let mut client = language_server::MockClient::default();
+        let snapshot =
+            Snapshot { engine_version: ENGINE_VERSION.to_owned(), database: database(3) };
+        let updates = UpdateLog { events: vec![add_event(2, 4)], ..UpdateLog::new(&snapshot) };
+        expect_read(&mut client, snapshot_path(), &snapshot);
+        expect_read(&mut client, update_log_path(), &updates);
+        let version = response::GetSuggestionDatabaseVersion { current_version: 5 };
+        expect_call!(client.get_suggestions_database_version() => Ok(version));
+        expect_full_fetch(&mut client, database(5));
+
+        let db = create_db(client, &Cache::new(ENGINE_VERSION));
+        assert_eq!(db.lookup(1).unwrap().name, "First");
+        assert!(db.lookup(2).is_err());
+        assert_eq!(db.version.get(), 5);
+    }
+
+    #[test]
+    fn ignoring_cache_of_other_engine_version() {
+        let mut client = language_server::MockClient::default();
+        let snapshot = Snapshot { engine_version: "0.1.0".to_owned(), database: database(3) };
+        expect_read(&mut client, snapshot_path(), &snapshot);
+        let version = response::GetSuggestionDatabaseVersion { current_version: 3 };
+        expect_call!(client.get_suggestions_database_version() => Ok(version));
+        expect_full_fetch(&mut client, database(3));
+
+        let db = create_db(client, &Cache::new(ENGINE_VERSION));
+        assert_eq!(db.version.get(), 3);
+    }
+
+    #[test]
+    fn creating_database_without_cache() {
+        let mut client = language_server::MockClient::default();
+        let path = snapshot_path();
+        let error = RpcError::new_remote_error(code::FILE_NOT_FOUND, "File not found");
+        expect_call!(client.read_file(path=path) => Err(error));
+        let version = response::GetSuggestionDatabaseVersion { current_version: 3 };
+        expect_call!(client.get_suggestions_database_version() => Ok(version));
+        expect_full_fetch(&mut client, database(3));
+
+        let db = create_db(client, &Cache::new(ENGINE_VERSION));
+        assert_eq!(db.lookup(1).unwrap().name, "First");
+        assert_eq!(db.version.get(), 3);
+    }
+
+    #[test]
+    fn recording_updates() {
+        let mut fixture = TestWithLocalPoolExecutor::set_up();
+        let mut client = language_server::MockClient::default();
+        let cache = Cache::new(ENGINE_VERSION);
+        let snapshot =
+            Snapshot { engine_version: ENGINE_VERSION.to_owned(), database: database(3) };
+        let event = add_event(2, 4);
+        let updates = UpdateLog { events: vec![event.clone()], ..UpdateLog::new(&snapshot) };
+        expect_write(&mut client, snapshot_path(), &snapshot);
+        expect_write(&mut client, update_log_path(), &UpdateLog::new(&snapshot));
+        expect_write(&mut client, update_log_path(), &updates);
+        client.require_all_calls();
+        let connection = language_server::Connection::new_mock(client);
+
+        // Without a snapshot, the updates are not recorded.
+        let store = cache.store_update(&connection, event.clone());
+        fixture.expect_completion(store).unwrap();
+        let store = cache.store_snapshot(&connection, snapshot.database.clone());
+        fixture.expect_completion(store).unwrap();
+        let store = cache.store_update(&connection, event);
+        fixture.expect_completion(store).unwrap();
+    }
+
+    #[test]
+    fn storing_new_snapshot_when_update_log_is_full() {
+        let mut fixture = TestWithLocalPoolExecutor::set_up();
+        let mut client = language_server::MockClient::default();
+        let cache = Cache::new(ENGINE_VERSION);
+        let snapshot =
+            Snapshot { engine_version: ENGINE_VERSION.to_owned(), database: database(3) };
+        let versions = 4..4 + MAX_CACHED_UPDATES - 1;
+        let events = versions.map(|version| add_event(2, version)).collect();
+        cache.update_log.replace(Some(UpdateLog { events, ..UpdateLog::new(&snapshot) }));
+        // The fetched database does not contain the last update yet.
+        let last_version = 3 + MAX_CACHED_UPDATES;
+        let event = add_event(2, last_version);
+        let new_snapshot = Snapshot {
+            engine_version: ENGINE_VERSION.to_owned(),
+            database:       database(last_version - 1),
+        };
+        let updates = UpdateLog { events: vec![event.clone()], ..UpdateLog::new(&new_snapshot) };
+        expect_call!(client.get_suggestions_database() => Ok(new_snapshot.database.clone()));
+        expect_write(&mut client, snapshot_path(), &new_snapshot);
+        expect_write(&mut client, update_log_path(), &updates);
+        client.require_all_calls();
+        let connection = language_server::Connection::new_mock(client);
+
+        let store = cache.store_update(&connection, event);
+        fixture.expect_completion(store).unwrap();
+        assert_eq!(*cache.update_log.borrow(), Some(updates));
+    }
+}
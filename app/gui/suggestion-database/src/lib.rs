@@ -55,11 +55,13 @@ use language_server::types::SuggestionsDatabaseVersion;
 // === Export ===
 // ==============
 
+pub mod cache;
 pub mod documentation_ir;
 pub mod entry;
 pub mod example;
 pub mod mock;
 
+pub use cache::Cache;
 pub use engine_protocol;
 pub use entry::Entry;
 pub use example::Example;
@@ -355,6 +357,45 @@ impl SuggestionDatabase {
         Ok(Self::from_ls_response(response))
     }
 
+    /// Create a new database which will take its initial content from the cache, if the cache is
+    /// up to date with the Language Server's database. Otherwise, the content is taken from the
+    /// Language Server and stored in the cache.
+    ///
+    /// The Language Server does not provide the updates it sent before, so an outdated cache
+    /// cannot be updated, and the whole database is fetched instead.
+    pub async fn create_synchronized_with_cache(
+        language_server: &language_server::Connection,
+        cache: &Cache,
+    ) -> FallibleResult<Self> {
+        let cached = cache.load(language_server).await;
+        let version = language_server.client.get_suggestions_database_version().await?;
+        match cached {
+            Ok(cached) if cached.version() == version.current_version =>
+                return Ok(Self::from_cache(cached)),
+            Ok(cached) => info!(
+                "The suggestion database cache is outdated (version {}, while the current one is \
+                {}).",
+                cached.version(),
+                version.current_version
+            ),
+            Err(err) => info!("Cannot use the suggestion database cache: {err}"),
+        }
+        let response = language_server.client.get_suggestions_database().await?;
+        if let Err(err) = cache.store_snapshot(language_server, response.clone()).await {
+            warn!("Failed to store the suggestion database cache: {err}");
+        }
+        Ok(Self::from_ls_response(response))
+    }
+
+    /// Create a new database model from the cached snapshot and updates.
+    fn from_cache(cached: cache::CachedDatabase) -> Self {
+        let db = Self::from_ls_response(cached.snapshot.database);
+        for event in cached.updates.events {
+            db.apply_update_event(event);
+        }
+        db
+    }
+
     /// Create a new database model from response received from the Language Server.
     fn from_ls_response(response: language_server::response::GetSuggestionDatabase) -> Self {
         let mut entries = HashMap::new();
@@ -24,6 +24,7 @@ pub mod index;
 pub mod movement;
 pub mod rope;
 pub mod selection;
+pub mod wrap;
 
 
 
@@ -34,6 +35,7 @@ pub mod traits {
 
 pub use formatting::*;
 pub use movement::*;
+pub use wrap::WrapMode;
 
 pub use enso_text::index::*;
 pub use enso_text::unit::*;
@@ -298,6 +300,9 @@ pub struct BufferModelData {
     /// The line that corresponds to `ViewLine(0)`.
     first_view_line:   Cell<Line>,
     view_line_count:   Cell<Option<usize>>,
+    /// Soft wrap points of lines. They are computed by the view displaying the buffer, as they
+    /// depend on the shaped glyphs.
+    pub soft_wraps:    RefCell<wrap::SoftWraps>,
 }
 
 impl BufferModel {
@@ -628,6 +633,8 @@ impl BufferModel {
         let selected_line_count = redraw_end_line - redraw_start_line + Line(1);
         let inserted_line_count = local_byte_selection.end.line - redraw_start_line + Line(1);
         let line_diff = inserted_line_count - selected_line_count;
+        let replaced_lines = redraw_start_line..=redraw_end_line;
+        self.soft_wraps.borrow_mut().on_change(replaced_lines, line_diff);
 
         let loc_selection =
             Selection::<Location>::from_in_context_snapped(self, new_byte_selection);
@@ -724,6 +731,21 @@ impl BufferModel {
 }
 
 
+// === Soft Wrapping ===
+
+impl BufferModel {
+    /// Set the soft wrap points of the line. See [`wrap::wrap_points`] to learn more.
+    pub fn set_soft_wraps(&self, line: Line, points: Vec<Column>) {
+        self.soft_wraps.borrow_mut().set(line, points);
+    }
+
+    /// Number of rows (visual lines) the line is displayed in.
+    pub fn row_count(&self, line: Line) -> usize {
+        self.soft_wraps.borrow().row_count(line)
+    }
+}
+
+
 // === Undo / Redo ===
 
 impl BufferModel {
@@ -739,6 +761,7 @@ impl BufferModel {
         item.map(|(text, style, selection)| {
             self.rope.set_text(text);
             self.rope.set_style(style);
+            self.soft_wraps.borrow_mut().clear();
             selection
         })
     }
@@ -33,13 +33,13 @@ pub enum Transform {
     Word,
     /// Select the line at every cursor.
     Line,
-    /// Move to left end of visible line.
+    /// Move to left end of visible line. In soft-wrapped lines, this is the start of the row.
     LeftOfLine,
-    /// Move to right end of visible line.
+    /// Move to right end of visible line. In soft-wrapped lines, this is the end of the row.
     RightOfLine,
-    /// Move up one visible line.
+    /// Move up one visible line. In soft-wrapped lines, this moves to the previous row.
     Up,
-    /// Move down one visible line.
+    /// Move down one visible line. In soft-wrapped lines, this moves to the next row.
     Down,
     /// Move to the start of the document.
     StartOfDocument,
@@ -71,7 +71,9 @@ impl BufferModel {
         }
     }
 
-    /// Compute movement based on vertical motion by the given number of lines.
+    /// Compute movement based on vertical motion by the given number of visible lines. Soft-wrapped
+    /// lines are displayed in several rows, and every row is considered a separate visible line.
+    /// The cursor keeps its offset from the start of the row when moving between rows.
     fn vertical_motion(
         &self,
         selection: Selection,
@@ -80,16 +82,44 @@ impl BufferModel {
     ) -> selection::Shape {
         let move_up = line_diff < LineDiff(0);
         let location = self.vertical_motion_selection_to_location(selection, move_up, modify);
-        let first_line = Line(0);
+        let soft_wraps = self.soft_wraps.borrow();
         let last_line = self.last_line_index();
-        let desired_line = location.line.to_diff() + line_diff;
-        let tgt_location = if desired_line < first_line.to_diff() {
-            Location { line: first_line, offset: Column(0) }
-        } else if desired_line > last_line.to_diff() {
-            Location { line: last_line, offset: self.last_line_last_column() }
-        } else {
-            location.with_line(desired_line.to_line())
-        };
+        let mut line = location.line;
+        let mut row = soft_wraps.row_of_column(line, location.offset);
+        let offset_in_row = location.offset - soft_wraps.row_start(line, row);
+        let mut out_of_bounds_location = None;
+        for _ in 0..line_diff.value.unsigned_abs() {
+            if move_up {
+                if row > 0 {
+                    row -= 1;
+                } else if line > Line(0) {
+                    line = Line(line.value - 1);
+                    row = soft_wraps.row_count(line) - 1;
+                } else {
+                    out_of_bounds_location = Some(Location { line, offset: Column(0) });
+                    break;
+                }
+            } else if row + 1 < soft_wraps.row_count(line) {
+                row += 1;
+            } else if line < last_line {
+                line = line.inc();
+                row = 0;
+            } else {
+                let offset = self.last_line_last_column();
+                out_of_bounds_location = Some(Location { line: last_line, offset });
+                break;
+            }
+        }
+        let tgt_location = out_of_bounds_location.unwrap_or_else(|| {
+            let offset = soft_wraps.row_start(line, row) + offset_in_row;
+            // The column of a wrap point is displayed at the start of the next row, so the cursor
+            // has to stay before it.
+            let offset = match soft_wraps.row_end(line, row) {
+                Some(row_end) => std::cmp::min(offset, row_end - Column(1)),
+                None => offset,
+            };
+            Location { line, offset }
+        });
         selection::Shape(selection.start, tgt_location)
     }
 
@@ -155,23 +185,33 @@ impl BufferModel {
             Transform::RightSelectionBorder => shape(selection.start, selection.max()),
 
             Transform::LeftOfLine => {
-                let end = Location(selection.end.line, Column(0));
+                let line = selection.end.line;
+                let soft_wraps = self.soft_wraps.borrow();
+                let row = soft_wraps.row_of_column(line, selection.end.offset);
+                let end = Location(line, soft_wraps.row_start(line, row));
                 shape(selection.start, end)
             }
 
             Transform::RightOfLine => {
                 let line = selection.end.line;
-                let text_byte_size = text.last_byte_index();
-                let is_last_line = line == self.last_line_index();
-                let next_line_offset_opt = self.line_offset(line + Line(1));
-                let next_line_offset =
-                    next_line_offset_opt.unwrap_or_else(|_| text.last_byte_index());
-                let offset = if is_last_line {
-                    text_byte_size
-                } else {
-                    text.prev_grapheme_offset(next_line_offset).unwrap_or(text_byte_size)
+                let soft_wraps = self.soft_wraps.borrow();
+                let row = soft_wraps.row_of_column(line, selection.end.offset);
+                let end = match soft_wraps.row_end(line, row) {
+                    Some(row_end) => Location(line, row_end - Column(1)),
+                    None => {
+                        let text_byte_size = text.last_byte_index();
+                        let is_last_line = line == self.last_line_index();
+                        let next_line_offset_opt = self.line_offset(line + Line(1));
+                        let next_line_offset =
+                            next_line_offset_opt.unwrap_or_else(|_| text.last_byte_index());
+                        let offset = if is_last_line {
+                            text_byte_size
+                        } else {
+                            text.prev_grapheme_offset(next_line_offset).unwrap_or(text_byte_size)
+                        };
+                        Location::from_in_context_snapped(self, offset)
+                    }
                 };
-                let end = Location::from_in_context_snapped(self, offset);
                 shape(selection.start, end)
             }
 
@@ -213,3 +253,80 @@ impl BufferModel {
         Selection(start, end, selection.id)
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A buffer with the second line displayed in three rows: `second `, `long `, and `line`.
+    fn buffer_with_wrapped_line() -> BufferModel {
+        let buffer = BufferModel::new();
+        buffer.rope.replace(.., "first line\nsecond long line\nend");
+        buffer.set_soft_wraps(Line(1), vec![Column(7), Column(12)]);
+        buffer
+    }
+
+    fn moved_cursor(buffer: &BufferModel, transform: Transform, location: Location) -> Location {
+        let cursor = Selection::new_cursor(location, selection::Id { value: 0 });
+        buffer.moved_selection_region(transform, cursor, false).end
+    }
+
+    #[test]
+    fn vertical_motion_over_rows() {
+        let buffer = buffer_with_wrapped_line();
+        let down = |line, column| moved_cursor(&buffer, Transform::Down, Location(line, column));
+        let up = |line, column| moved_cursor(&buffer, Transform::Up, Location(line, column));
+        assert_eq!(down(Line(0), Column(3)), Location(Line(1), Column(3)));
+        assert_eq!(down(Line(1), Column(3)), Location(Line(1), Column(10)));
+        assert_eq!(down(Line(1), Column(10)), Location(Line(1), Column(15)));
+        assert_eq!(down(Line(1), Column(15)), Location(Line(2), Column(3)));
+        assert_eq!(down(Line(2), Column(1)), Location(Line(2), Column(3)));
+        assert_eq!(up(Line(2), Column(1)), Location(Line(1), Column(13)));
+        assert_eq!(up(Line(1), Column(13)), Location(Line(1), Column(8)));
+        assert_eq!(up(Line(1), Column(8)), Location(Line(1), Column(1)));
+        assert_eq!(up(Line(0), Column(2)), Location(Line(0), Column(0)));
+        // The cursor can't be placed at the wrap point, as it would be displayed in the next row.
+        assert_eq!(down(Line(0), Column(9)), Location(Line(1), Column(6)));
+    }
+
+    #[test]
+    fn vertical_motion_without_wrapping() {
+        let buffer = buffer_with_wrapped_line();
+        buffer.set_soft_wraps(Line(1), vec![]);
+        let down = |line, column| moved_cursor(&buffer, Transform::Down, Location(line, column));
+        assert_eq!(down(Line(0), Column(9)), Location(Line(1), Column(9)));
+        assert_eq!(down(Line(1), Column(9)), Location(Line(2), Column(9)));
+    }
+
+    #[test]
+    fn line_ends_of_rows() {
+        let buffer = buffer_with_wrapped_line();
+        let left = |column| moved_cursor(&buffer, Transform::LeftOfLine, Location(Line(1), column));
+        let right =
+            |column| moved_cursor(&buffer, Transform::RightOfLine, Location(Line(1), column));
+        assert_eq!(left(Column(3)), Location(Line(1), Column(0)));
+        assert_eq!(left(Column(9)), Location(Line(1), Column(7)));
+        assert_eq!(left(Column(13)), Location(Line(1), Column(12)));
+        assert_eq!(right(Column(3)), Location(Line(1), Column(6)));
+        assert_eq!(right(Column(9)), Location(Line(1), Column(11)));
+        assert_eq!(right(Column(13)), Location(Line(1), Column(16)));
+    }
+
+    #[test]
+    fn soft_wraps_follow_edits() {
+        let buffer = buffer_with_wrapped_line();
+        let cursor = Selection::new_cursor(default(), selection::Id { value: 0 });
+        buffer.set_selection(&cursor.into());
+        buffer.insert("new line\n");
+        assert_eq!(buffer.row_count(Line(1)), 1);
+        assert_eq!(buffer.row_count(Line(2)), 3);
+        buffer.undo();
+        assert_eq!(buffer.row_count(Line(2)), 1);
+    }
+}
@@ -0,0 +1,230 @@
+//! Soft line wrapping. Lines longer than the wrap width are displayed as several visual lines
+//! (called rows), without modifying the text. This module computes the wrap points and stores them
+//! per buffer line, so both the renderer and the cursor movement can use them.
+
+use crate::prelude::*;
+use enso_text::index::*;
+use enso_text::unit::*;
+
+
+
+// ================
+// === WrapMode ===
+// ================
+
+/// Soft wrapping mode of the text area.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WrapMode {
+    /// Lines are never wrapped.
+    #[default]
+    None,
+    /// Lines are wrapped at the view width. If the view width is not set, lines are not wrapped.
+    ViewWidth,
+    /// Lines are wrapped at the provided width.
+    Width(f32),
+}
+
+impl WrapMode {
+    /// The width lines should be wrapped at, if any.
+    pub fn width(self, view_width: Option<f32>) -> Option<f32> {
+        match self {
+            Self::None => None,
+            Self::ViewWidth => view_width,
+            Self::Width(width) => Some(width),
+        }
+    }
+}
+
+
+
+// ================
+// === WrapUnit ===
+// ================
+
+/// Description of a single shaped grapheme cluster (a single column) used to compute the wrap
+/// points.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WrapUnit {
+    pub x_advance:     f32,
+    /// Word boundaries are placed after whitespaces. Whitespaces at the end of a row are allowed
+    /// to exceed the wrap width.
+    pub is_whitespace: bool,
+}
+
+impl WrapUnit {
+    /// Constructor.
+    pub fn new(x_advance: f32, is_whitespace: bool) -> Self {
+        Self { x_advance, is_whitespace }
+    }
+}
+
+/// Compute the columns at which a line consisting of the provided units should be wrapped to fit
+/// the width. The result contains the first column of every row except the first one, so it is
+/// empty if the line fits the width.
+///
+/// Lines are wrapped at word boundaries. If a word does not fit the width on its own, it is
+/// wrapped at a grapheme cluster boundary instead. Every row contains at least one grapheme
+/// cluster, even if it is wider than the wrap width.
+pub fn wrap_points(units: &[WrapUnit], width: f32) -> Vec<Column> {
+    let mut points = vec![];
+    let mut row_start = 0;
+    let mut row_width = 0.0;
+    let mut last_word_start = None;
+    for (index, unit) in units.iter().enumerate() {
+        let overflows = row_width + unit.x_advance > width;
+        if overflows && !unit.is_whitespace && index > row_start {
+            let wrap_at = last_word_start.filter(|&start| start > row_start).unwrap_or(index);
+            points.push(Column(wrap_at));
+            row_start = wrap_at;
+            row_width = units[wrap_at..index].iter().map(|unit| unit.x_advance).sum();
+            last_word_start = None;
+        }
+        row_width += unit.x_advance;
+        if unit.is_whitespace {
+            last_word_start = Some(index + 1);
+        }
+    }
+    points
+}
+
+
+
+// =================
+// === SoftWraps ===
+// =================
+
+/// Wrap points of all wrapped lines of a buffer. Lines not registered here are not wrapped and
+/// consist of a single row.
+#[derive(Clone, Debug, Default)]
+pub struct SoftWraps {
+    lines: BTreeMap<Line, Vec<Column>>,
+}
+
+impl SoftWraps {
+    /// Set the wrap points of the line. See [`wrap_points`] to learn more.
+    pub fn set(&mut self, line: Line, points: Vec<Column>) {
+        if points.is_empty() {
+            self.lines.remove(&line);
+        } else {
+            self.lines.insert(line, points);
+        }
+    }
+
+    /// The wrap points of the line.
+    pub fn get(&self, line: Line) -> &[Column] {
+        self.lines.get(&line).map(|points| points.as_slice()).unwrap_or_default()
+    }
+
+    /// Remove all wrap points, for example after the whole text was replaced.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Number of rows the line is displayed in.
+    pub fn row_count(&self, line: Line) -> usize {
+        self.get(line).len() + 1
+    }
+
+    /// Index of the row containing the column. A column equal to a wrap point belongs to the row
+    /// starting with it.
+    pub fn row_of_column(&self, line: Line, column: Column) -> usize {
+        self.get(line).partition_point(|&point| point <= column)
+    }
+
+    /// The first column of the row.
+    pub fn row_start(&self, line: Line, row: usize) -> Column {
+        if row == 0 {
+            Column(0)
+        } else {
+            self.get(line).get(row - 1).copied().unwrap_or_default()
+        }
+    }
+
+    /// The first column of the next row, if the row is not the last one in the line.
+    pub fn row_end(&self, line: Line, row: usize) -> Option<Column> {
+        self.get(line).get(row).copied()
+    }
+
+    /// Update the wrap points after a change replacing the provided lines. The wrap points of the
+    /// replaced lines are removed, and the following lines are shifted by the line difference.
+    pub fn on_change(&mut self, replaced_lines: RangeInclusive<Line>, line_diff: LineDiff) {
+        let replaced_end = *replaced_lines.end();
+        let following = self.lines.split_off(&(replaced_end + Line(1)));
+        self.lines.retain(|line, _| line < replaced_lines.start());
+        self.lines.extend(following.into_iter().map(|(line, points)| (line + line_diff, points)));
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units(text: &str) -> Vec<WrapUnit> {
+        text.chars().map(|c| WrapUnit::new(1.0, c.is_whitespace())).collect()
+    }
+
+    #[test]
+    fn wrapping_at_word_boundaries() {
+        assert_eq!(wrap_points(&units("short"), 10.0), vec![]);
+        assert_eq!(wrap_points(&units("ab cd ef"), 4.0), vec![Column(3), Column(6)]);
+        assert_eq!(wrap_points(&units("ab cd ef"), 5.0), vec![Column(6)]);
+        // Whitespaces at the end of a row may exceed the width.
+        assert_eq!(wrap_points(&units("abcde    fg"), 5.0), vec![Column(9)]);
+    }
+
+    #[test]
+    fn wrapping_long_words_at_grapheme_boundaries() {
+        assert_eq!(wrap_points(&units("abcdefgh"), 3.0), vec![Column(3), Column(6)]);
+        assert_eq!(wrap_points(&units("a bcdefgh"), 5.0), vec![Column(2), Column(7)]);
+        // Every row contains at least one grapheme cluster.
+        let wide = vec![WrapUnit::new(10.0, false); 2];
+        assert_eq!(wrap_points(&wide, 5.0), vec![Column(1)]);
+    }
+
+    #[test]
+    fn wrap_width_of_modes() {
+        assert_eq!(WrapMode::None.width(Some(10.0)), None);
+        assert_eq!(WrapMode::ViewWidth.width(Some(10.0)), Some(10.0));
+        assert_eq!(WrapMode::ViewWidth.width(None), None);
+        assert_eq!(WrapMode::Width(5.0).width(Some(10.0)), Some(5.0));
+    }
+
+    #[test]
+    fn rows_of_soft_wrapped_line() {
+        let mut wraps = SoftWraps::default();
+        wraps.set(Line(1), vec![Column(3), Column(6)]);
+        assert_eq!(wraps.row_count(Line(0)), 1);
+        assert_eq!(wraps.row_count(Line(1)), 3);
+        assert_eq!(wraps.row_of_column(Line(1), Column(2)), 0);
+        assert_eq!(wraps.row_of_column(Line(1), Column(3)), 1);
+        assert_eq!(wraps.row_of_column(Line(1), Column(8)), 2);
+        assert_eq!(wraps.row_start(Line(1), 2), Column(6));
+        assert_eq!(wraps.row_end(Line(1), 1), Some(Column(6)));
+        assert_eq!(wraps.row_end(Line(1), 2), None);
+        wraps.set(Line(1), vec![]);
+        assert_eq!(wraps.row_count(Line(1)), 1);
+    }
+
+    #[test]
+    fn soft_wraps_follow_line_changes() {
+        let mut wraps = SoftWraps::default();
+        wraps.set(Line(0), vec![Column(1)]);
+        wraps.set(Line(2), vec![Column(2)]);
+        wraps.set(Line(4), vec![Column(4)]);
+        wraps.on_change(Line(2)..=Line(2), LineDiff(2));
+        assert_eq!(wraps.get(Line(0)), &[Column(1)]);
+        assert_eq!(wraps.get(Line(2)), &[]);
+        assert_eq!(wraps.get(Line(4)), &[]);
+        assert_eq!(wraps.get(Line(6)), &[Column(4)]);
+        wraps.on_change(Line(0)..=Line(1), LineDiff(-1));
+        assert_eq!(wraps.get(Line(0)), &[]);
+        assert_eq!(wraps.get(Line(5)), &[Column(4)]);
+    }
+}
@@ -5,6 +5,7 @@
 // === Export ===
 // ==============
 
+pub mod gutter;
 pub mod line;
 pub mod text;
 
@@ -0,0 +1,106 @@
+//! Line number gutter displayed on the left side of the text area.
+
+use crate::prelude::*;
+use enso_text::index::*;
+
+use crate::component::Text;
+
+use enso_frp as frp;
+use ensogl_core::application::Application;
+use ensogl_core::display;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The space between the gutter and the text.
+pub const GUTTER_PADDING: f32 = 8.0;
+
+
+
+// ===============
+// === Content ===
+// ===============
+
+/// The gutter content displaying numbers of the subsequent lines, starting with the `first_line`.
+/// The `row_counts` contain the number of rows each line is displayed in. Rows of soft-wrapped
+/// lines other than the first one are left empty, so the numbers are aligned with the text lines.
+/// The numbers are right-aligned with spaces, which works well with monospace fonts.
+pub fn content(first_line: Line, row_counts: &[usize]) -> String {
+    let last_number = first_line.value + row_counts.len();
+    let width = last_number.to_string().len();
+    let mut out = String::new();
+    for (index, row_count) in row_counts.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        let number = first_line.value + index + 1;
+        out.push_str(&format!("{number:>width$}"));
+        for _ in 1..*row_count {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+
+
+// ==============
+// === Gutter ===
+// ==============
+
+/// Line number gutter. It is a text area displaying the line numbers, placed on the left side of
+/// the text area it belongs to. It assumes that all lines of the text area have the same height.
+#[derive(Clone, CloneRef, Debug, Deref)]
+pub struct Gutter {
+    #[deref]
+    text:    Text,
+    network: frp::Network,
+}
+
+impl Gutter {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let text = Text::new(app);
+        let network = frp::Network::new("Gutter");
+        let display_object = text.display_object().clone_ref();
+        frp::extend! { network
+            eval text.width ((width) display_object.set_x(-width - GUTTER_PADDING));
+        }
+        Self { text, network }
+    }
+
+    /// Display numbers of the subsequent lines. See [`content`] to learn more.
+    pub fn set_line_numbers(&self, first_line: Line, row_counts: &[usize]) {
+        let content = content(first_line, row_counts);
+        if self.text.content.value().to_string() != content {
+            self.text.set_content(content);
+        }
+    }
+}
+
+impl display::Object for Gutter {
+    fn display_object(&self) -> &display::object::Instance {
+        self.text.display_object()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gutter_content() {
+        assert_eq!(content(Line(0), &[1, 1, 1]), "1\n2\n3");
+        assert_eq!(content(Line(0), &[1, 3, 1]), "1\n2\n\n\n3");
+        assert_eq!(content(Line(8), &[1, 2]), " 9\n10\n");
+    }
+}
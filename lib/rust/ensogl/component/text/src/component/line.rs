@@ -248,6 +248,14 @@ pub struct View {
     /// Centers between division points. Used for glyph selection with mouse cursor.
     pub centers:        Vec<f32>,
     pub truncation:     Truncation,
+    /// The first columns of the rows of a soft-wrapped line, except the first row. Empty if the
+    /// line is not wrapped. See [`crate::buffer::wrap`] to learn more.
+    pub soft_wraps:     Vec<Column>,
+    /// The division point of the first column of every row. The `divs` are computed as if the line
+    /// was not wrapped, so these values are subtracted from them to get the in-row positions.
+    pub row_offsets_x:  NonEmptyVec<f32>,
+    /// The distance between baselines of subsequent rows.
+    pub row_height:     f32,
     baseline_anim:      Animation<f32>,
 }
 
@@ -262,6 +270,9 @@ impl View {
         let divs = default();
         let centers = default();
         let truncation: Truncation = default();
+        let soft_wraps = default();
+        let row_offsets_x = NonEmptyVec::singleton(0.0);
+        let row_height = default();
         let frame_time = frame_time.clone_ref();
         baseline_anim.simulator.update_spring(|s| s * crate::DEBUG_ANIMATION_SPRING_FACTOR);
 
@@ -287,7 +298,18 @@ impl View {
             eval start_time ((t) truncation.set_animation_start_time(*t));
         }
 
-        Self { frp, display_object, glyphs, divs, centers, truncation, baseline_anim }
+        Self {
+            frp,
+            display_object,
+            glyphs,
+            divs,
+            centers,
+            truncation,
+            soft_wraps,
+            row_offsets_x,
+            row_height,
+            baseline_anim,
+        }
     }
 
     /// Get glyph for the provided column or create a new one if it does not exist.
@@ -355,17 +377,85 @@ impl View {
         self.centers.binary_search_by(|t| t.partial_cmp(&x_pos).unwrap()).unwrap_both()
     }
 
-    /// Get the division by column.
+    /// Get the division by column. For soft-wrapped lines, the result is relative to the start of
+    /// the row containing the column.
     pub fn div_by_column(&self, column: Column) -> f32 {
+        let row_offset_x = self.row_offsets_x[self.row_of_column(column)];
         if column.value < self.divs.len() {
-            self.divs[column.value]
+            self.divs[column.value] - row_offset_x
         } else {
             // Requested column is bigger then glyph amount. This can happen for example when text
             // is truncated and the cursor is in the truncated area.
-            *self.divs.last()
+            *self.divs.last() - row_offset_x
+        }
+    }
+
+    /// Finds the div close to the given x-axis position in the provided row. The last column of a
+    /// row is not returned for rows followed by another row, as it is displayed in the next row.
+    pub fn div_index_close_to_in_row(&self, row: usize, x_pos: f32) -> usize {
+        let row = row.min(self.row_count() - 1);
+        let index = self.div_index_close_to(x_pos + self.row_offsets_x[row]);
+        let row_start = if row == 0 { 0 } else { self.soft_wraps[row - 1].value };
+        let row_last = match self.soft_wraps.get(row) {
+            Some(row_end) => row_end.value - 1,
+            None => self.divs.len() - 1,
+        };
+        index.clamp(row_start, row_last)
+    }
+
+    /// Set the soft wrap points of the line and move the glyphs to their rows. The glyphs have to
+    /// be placed as if the line was not wrapped, and the `divs` have to be already set.
+    pub fn set_soft_wraps(&mut self, soft_wraps: Vec<Column>, row_height: f32) {
+        let row_starts = iter::once(Column(0)).chain(soft_wraps.iter().copied());
+        let row_offsets_x = row_starts.map(|column| self.divs[column.value]).collect_vec();
+        // This is safe, as the vector always contains the offset of the first row.
+        self.row_offsets_x = NonEmptyVec::try_from(row_offsets_x).unwrap();
+        self.soft_wraps = soft_wraps;
+        self.row_height = row_height;
+        for (column, glyph) in self.glyphs.iter().enumerate() {
+            let row = self.row_of_column(Column(column));
+            if row > 0 {
+                glyph.set_xy(Vector2(self.divs[column] - self.row_offsets_x[row], self.row_y(row)));
+            }
         }
     }
 
+    /// Check whether the line is soft-wrapped.
+    pub fn is_wrapped(&self) -> bool {
+        !self.soft_wraps.is_empty()
+    }
+
+    /// Number of rows the line is displayed in.
+    pub fn row_count(&self) -> usize {
+        self.soft_wraps.len() + 1
+    }
+
+    /// Index of the row containing the column.
+    pub fn row_of_column(&self, column: Column) -> usize {
+        self.soft_wraps.partition_point(|&wrap| wrap <= column)
+    }
+
+    /// The y-axis position of the row baseline relative to the line baseline.
+    pub fn row_y(&self, row: usize) -> f32 {
+        -(row as f32) * self.row_height
+    }
+
+    /// The height of all rows except the first one. The line metrics describe the first row only.
+    pub fn wrapped_rows_height(&self) -> f32 {
+        -self.row_y(self.row_count() - 1)
+    }
+
+    /// The width of the widest row if the line is soft-wrapped.
+    pub fn wrapped_width(&self) -> Option<f32> {
+        self.is_wrapped().then(|| {
+            let row_ends = self.soft_wraps.iter().map(|column| self.divs[column.value]);
+            let row_ends = row_ends.chain(iter::once(*self.divs.last()));
+            let row_widths =
+                row_ends.zip(self.row_offsets_x.iter()).map(|(end, start)| end - start);
+            row_widths.fold(0.0, f32::max)
+        })
+    }
+
     /// Resize glyph vector and use the provided constructor to create missing glyphs if any.
     pub fn resize_with(&mut self, size: usize, cons: impl Fn() -> Glyph) {
         let display_object = self.display_object().clone_ref();
@@ -9,9 +9,11 @@ use enso_text::unit::*;
 use crate::buffer;
 use crate::buffer::formatting;
 use crate::buffer::formatting::Formatting;
+use crate::buffer::wrap;
 use crate::buffer::FromInContextSnapped;
 use crate::buffer::Transform;
 use crate::buffer::TryFromInContext;
+use crate::component::gutter::Gutter;
 use crate::component::line;
 use crate::component::selection;
 use crate::component::Selection;
@@ -141,7 +143,9 @@ impl Lines {
 
         let start_x = get_pos_x(start_location);
         let end_x = get_pos_x(end_location);
-        let y = self.borrow()[start_location.line].baseline();
+        let lines = self.borrow();
+        let line = &lines[start_location.line];
+        let y = line.baseline() + line.row_y(line.row_of_column(start_location.offset));
         let start_pos = Vector2(start_x, y);
         let end_pos = Vector2(end_x, y);
         (start_pos, end_pos)
@@ -331,6 +335,14 @@ ensogl_core::define_endpoints_2! {
         /// Please note that you have to set the view width as well.
         set_long_text_truncation_mode(bool),
 
+        /// Set the soft wrapping mode. Lines longer than the wrap width are displayed in several
+        /// rows, without modifying the text. The long text truncation mode is ignored for wrapped
+        /// text.
+        set_wrap_mode(buffer::WrapMode),
+
+        /// Show or hide the line number gutter on the left side of the text area.
+        set_gutter_visible(bool),
+
         // === NOT FINISHED YET ===
         // The following endpoints control the view area of the text area. They are not finished
         // yet and using them will probably cause panics and rendering issues.
@@ -349,6 +361,8 @@ ensogl_core::define_endpoints_2! {
         single_line_mode(bool),
         view_width(Option<f32>),
         long_text_truncation_mode(bool),
+        wrap_mode(buffer::WrapMode),
+        gutter_visible(bool),
         glyph_system    (Option<glyph::System>),
 
         // === Internal API ===
@@ -661,6 +675,12 @@ impl Text {
 
             out.long_text_truncation_mode <+ self.frp.set_long_text_truncation_mode;
             eval_ self.frp.set_long_text_truncation_mode (m.redraw());
+
+            out.wrap_mode <+ self.frp.set_wrap_mode;
+            eval_ self.frp.set_wrap_mode (m.redraw());
+
+            out.gutter_visible <+ self.frp.set_gutter_visible;
+            eval self.frp.set_gutter_visible ((visible) m.set_gutter_visible(*visible));
         }
     }
 
@@ -693,6 +713,9 @@ impl Text {
     pub fn add_to_scene_layer(&self, layer: &display::scene::Layer) {
         self.data.layer.set(layer.clone_ref());
         layer.add(self);
+        if let Some(gutter) = &*self.data.gutter.borrow() {
+            gutter.add_to_scene_layer(layer);
+        }
     }
 }
 
@@ -726,6 +749,8 @@ pub struct TextModelData {
     // FIXME[ao]: this is a temporary solution to handle properly areas in different views. Should
     //            be replaced with proper object management.
     layer:          CloneRefCell<display::scene::Layer>,
+    /// Line number gutter. It is created when it is shown for the first time.
+    gutter:         RefCell<Option<Gutter>>,
 }
 
 impl TextModel {
@@ -754,6 +779,7 @@ impl TextModel {
         let width_dirty = default();
         let height_dirty = default();
         let shaped_lines = default();
+        let gutter = default();
 
         let frp = frp.downgrade();
         let data = TextModelData {
@@ -768,6 +794,7 @@ impl TextModel {
             width_dirty,
             height_dirty,
             shaped_lines,
+            gutter,
         };
         Self { rc: Rc::new(data) }.init()
     }
@@ -846,18 +873,23 @@ impl TextModel {
     fn screen_to_text_location(&self, screen_pos: Vector2) -> Location {
         let object_space = self.screen_to_object_space(screen_pos);
         let mut view_line = ViewLine(0);
+        let mut row = 0;
         let lines = self.lines.borrow();
-        for line in &*lines {
-            // We are adding half of the gap here, so if someone clicks between the lines, the line
-            // closer to the mouse pointer will be selected.
-            let height = line.baseline() + line.metrics().descender + line.metrics().gap / 2.0;
-            if height < object_space.y {
-                break;
+        'lines: for line in &*lines {
+            for line_row in 0..line.row_count() {
+                row = line_row;
+                // We are adding half of the gap here, so if someone clicks between the lines, the
+                // line closer to the mouse pointer will be selected.
+                let baseline = line.baseline() + line.row_y(line_row);
+                let height = baseline + line.metrics().descender + line.metrics().gap / 2.0;
+                if height < object_space.y {
+                    break 'lines;
+                }
             }
             view_line += ViewLine(1);
         }
         let view_line = std::cmp::min(view_line, self.lines.last_line_index());
-        let div_index = self.lines.borrow()[view_line].div_index_close_to(object_space.x);
+        let div_index = lines[view_line].div_index_close_to_in_row(row, object_space.x);
         let line = Line::from_in_context_snapped(self, view_line);
         let column = Column(div_index);
         let out = Location(line, column);
@@ -1318,6 +1350,7 @@ impl TextModel {
             }
         });
         self.position_sorted_line_ranges(sorted_line_ranges);
+        self.update_gutter();
     }
 
     /// Redraw the line. This will re-position all line glyphs.
@@ -1329,6 +1362,8 @@ impl TextModel {
         let mut column = Column(0);
         let mut to_be_truncated = 0;
         let mut truncated = false;
+        let mut soft_wraps = vec![];
+        let mut row_height = 0.0;
         let default_size = self.buffer.formatting.font_size().default;
         let line_index = Line::from_in_context_snapped(self, view_line);
         self.with_shaped_line(line_index, |shaped_line| {
@@ -1336,9 +1371,12 @@ impl TextModel {
                 ShapedLine::NonEmpty { glyph_sets } => {
                     let glyph_system = self.glyph_system.borrow();
                     let view_width = self.frp.output.view_width.value();
+                    let wrap_width = self.frp.output.wrap_mode.value().width(view_width);
                     let long_text_truncation_mode =
-                        self.frp.output.long_text_truncation_mode.value();
+                        self.frp.output.long_text_truncation_mode.value() && wrap_width.is_none();
                     let line_range = self.buffer.byte_range_of_view_line_index_snapped(view_line);
+                    let line_text = self.rope.sub(line_range.start..line_range.end).to_string();
+                    let mut wrap_units = vec![];
                     let line_style = self.buffer.sub_style(line_range.start..line_range.end);
                     let mut line_style_iter = line_style.iter_bytes();
                     let mut glyph_offset_x = 0.0;
@@ -1372,6 +1410,10 @@ impl TextModel {
                             let gap = shaped_glyph_set.line_gap as f32 / scale;
                             let x_advance = shaped_glyph.position.x_advance as f32 / scale;
                             let glyph_rhs = glyph_offset_x + x_advance;
+                            let glyph_text = line_text.get(glyph_byte_start.value..);
+                            let first_char = glyph_text.and_then(|text| text.chars().next());
+                            let is_whitespace = first_char.map_or(false, char::is_whitespace);
+                            wrap_units.push(wrap::WrapUnit::new(x_advance, is_whitespace));
 
                             if long_text_truncation_mode {
                                 if let Some(view_width) = view_width {
@@ -1411,9 +1453,14 @@ impl TextModel {
                     }
                     if let Some(line_metrics) = line_metrics {
                         line.set_metrics(line_metrics);
+                        row_height =
+                            line_metrics.ascender - line_metrics.descender + line_metrics.gap;
                     } else {
                         warn!("Internal error. Line metrics was not computed.")
                     }
+                    if let Some(wrap_width) = wrap_width {
+                        soft_wraps = wrap::wrap_points(&wrap_units, wrap_width);
+                    }
                 }
                 ShapedLine::Empty { prev_glyph_info } => {
                     if let Some((offset, shaped_glyph_set)) = prev_glyph_info {
@@ -1443,6 +1490,12 @@ impl TextModel {
             line.glyphs.truncate(column.value);
             line.set_truncated(None);
         }
+
+        if line.row_count() != soft_wraps.len() + 1 {
+            self.height_dirty.set(true);
+        }
+        self.buffer.set_soft_wraps(line_index, soft_wraps.clone());
+        line.set_soft_wraps(soft_wraps, row_height);
     }
 
     /// Clear shaped lines cache and redraw lines in the provided range. Clearing the cache is
@@ -1479,6 +1532,11 @@ impl TextModel {
         let cursor_map = self.selection_map.borrow().location_map.get(&view_line).cloned();
         let cursor_map = cursor_map.unwrap_or_default();
         let line = &self.lines.borrow()[view_line];
+        // Glyphs of soft-wrapped lines are placed in several rows, so they can't follow cursors
+        // moving along a single row.
+        if line.is_wrapped() {
+            return;
+        }
 
         let mut attached_glyphs = vec![];
         let mut last_cursor: Option<Selection> = None;
@@ -1672,6 +1730,11 @@ impl TextModel {
     /// Change a default value of a property.
     fn set_property_default(&self, property: Option<formatting::ResolvedProperty>) {
         if let Some(property) = property {
+            if property.tag() == formatting::PropertyTag::FontSize {
+                if let Some(gutter) = &*self.gutter.borrow() {
+                    gutter.set_property_default(property);
+                }
+            }
             if Self::property_change_invalidates_cache(property) {
                 self.set_property_default_with_line_redraw(property)
             } else {
@@ -1746,12 +1809,56 @@ impl TextModel {
         // Remove old Glyph structures, as they still refer to the old Glyph System.
         self.take_lines();
         self.redraw();
+        if let Some(gutter) = &*self.gutter.borrow() {
+            gutter.set_font(font_name);
+        }
         glyph_system
     }
 }
 
 
 
+// ==============
+// === Gutter ===
+// ==============
+
+impl TextModel {
+    /// Show or hide the line number gutter. The gutter is created when it is shown for the first
+    /// time and uses the same font and default font size as the text area.
+    fn set_gutter_visible(&self, visible: bool) {
+        let mut gutter = self.gutter.borrow_mut();
+        if visible {
+            let gutter = gutter.get_or_insert_with(|| {
+                let gutter = Gutter::new(&self.app);
+                let font_name = self.glyph_system.borrow().font.name().normalized.clone();
+                let font_size = self.buffer.formatting.font_size().default;
+                gutter.set_font(font_name);
+                gutter.set_property_default(formatting::ResolvedProperty::FontSize(font_size));
+                gutter.add_to_scene_layer(&self.layer.get());
+                gutter
+            });
+            self.display_object.add_child(gutter);
+        } else if let Some(gutter) = &*gutter {
+            gutter.unset_parent();
+        }
+        drop(gutter);
+        self.update_gutter();
+    }
+
+    /// Update the line numbers displayed in the gutter, if it is visible.
+    fn update_gutter(&self) {
+        if let Some(gutter) = &*self.gutter.borrow() {
+            if self.frp.output.gutter_visible.value() {
+                let lines = self.lines.borrow();
+                let row_counts = lines.iter().map(|line| line.row_count()).collect_vec();
+                gutter.set_line_numbers(self.buffer.first_view_line(), &row_counts);
+            }
+        }
+    }
+}
+
+
+
 // ========================
 // === Line Positioning ===
 // ========================
@@ -1771,7 +1878,9 @@ impl TextModel {
             } else {
                 let prev_line_index = ViewLine(line_index.value - 1);
                 let prev_line = &lines[prev_line_index];
-                let offset = prev_line.metrics().descender + ascender - line.metrics().gap;
+                let prev_line_bottom =
+                    prev_line.metrics().descender - prev_line.wrapped_rows_height();
+                let offset = prev_line_bottom + ascender - line.metrics().gap;
                 prev_line.baseline() + offset
             };
             let new_baseline = new_baseline.round();
@@ -1832,6 +1941,10 @@ impl TextModel {
                     if width > max_width {
                         max_width = width;
                     }
+                } else if let Some(width) = line.wrapped_width() {
+                    if width > max_width {
+                        max_width = width;
+                    }
                 } else {
                     let last_glyph = line.glyphs.iter().rev().find(|g| !g.attached_to_cursor.get());
                     let width = last_glyph.map(|g| g.x() + g.x_advance.get()).unwrap_or_default();
@@ -1854,14 +1967,16 @@ impl TextModel {
     fn compute_height_if_dirty(&self) -> Option<f32> {
         self.height_dirty.get().then(|| {
             self.height_dirty.set(false);
-            let mut max_height = -self.lines.borrow().last().descent.value();
+            let lines = self.lines.borrow();
+            let last_line = lines.last();
+            let mut max_height = -last_line.descent.value() + last_line.wrapped_rows_height();
             let selection_map = self.selection_map.borrow();
 
             for (view_line, map) in &selection_map.location_map {
                 for selection_id in map.values() {
                     let selection = selection_map.id_map.get(selection_id).unwrap();
                     let baseline = selection.position.value().y;
-                    let descender = self.lines.borrow()[*view_line].metrics.value().descender;
+                    let descender = lines[*view_line].metrics.value().descender;
                     let height = -baseline - descender;
                     if height > max_height {
                         max_height = height;
@@ -17,6 +17,7 @@ use crate::system::web;
 
 pub mod command;
 pub mod frp;
+pub mod recording;
 pub mod shortcut;
 pub mod tooltip;
 pub mod view;
@@ -0,0 +1,382 @@
+//! Recording and replaying of the application input. The [`Recorder`] collects all mouse events,
+//! keyboard events, and shortcut commands of an [`Application`], together with the number of the
+//! frame they occurred in, into a serializable [`InputLog`]. The [`Replayer`] feeds such a log back
+//! into another application frame by frame. This allows attaching a recording to a bug report and
+//! turning it into a regression test.
+
+use crate::control::callback::traits::*;
+use crate::prelude::*;
+
+use crate::application::Application;
+use crate::control::callback;
+use crate::control::io::mouse;
+use crate::control::io::mouse::MouseManager;
+use crate::frp;
+use crate::frp::io::keyboard::KeyWithCode;
+use crate::frp::io::keyboard::KeyboardSource;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// ====================
+// === KeyEventData ===
+// ====================
+
+/// The values of a keyboard event, as reported by the browser. See [`KeyWithCode`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct KeyEventData {
+    pub key:  String,
+    pub code: String,
+}
+
+impl KeyEventData {
+    /// Recreate the keyboard event.
+    pub fn to_key_with_code(&self) -> KeyWithCode {
+        KeyWithCode::new(self.key.clone(), self.code.clone())
+    }
+}
+
+impl From<&KeyWithCode> for KeyEventData {
+    fn from(event: &KeyWithCode) -> Self {
+        let key = event.key.key_value();
+        let code = event.code.clone();
+        Self { key, code }
+    }
+}
+
+
+
+// ================
+// === InputLog ===
+// ================
+
+/// A single input event of the application.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub enum InputEvent {
+    MouseDown(mouse::EventData),
+    MouseUp(mouse::EventData),
+    MouseMove(mouse::EventData),
+    MouseWheel(mouse::EventData),
+    MouseEnter(mouse::EventData),
+    MouseLeave(mouse::EventData),
+    KeyDown(KeyEventData),
+    KeyUp(KeyEventData),
+    WindowDefocused,
+    /// A shortcut command triggered by the input. It is not replayed, as replaying the input
+    /// triggers the command again. It allows checking if the replay behaves as the recording.
+    Command(String),
+}
+
+/// An input event with the number of frames which started before it occurred.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct RecordedEvent {
+    pub frame: usize,
+    pub event: InputEvent,
+}
+
+/// A serializable log of the application input.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct InputLog {
+    pub events: Vec<RecordedEvent>,
+}
+
+impl InputLog {
+    /// Serialize the log to JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize the log from JSON.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// The number of frames needed to replay all the events of this log.
+    pub fn frame_count(&self) -> usize {
+        self.events.iter().map(|event| event.frame + 1).max().unwrap_or_default()
+    }
+
+    /// Names of the shortcut commands triggered during the recording, in order.
+    pub fn commands(&self) -> Vec<&str> {
+        let commands = self.events.iter().filter_map(|recorded| match &recorded.event {
+            InputEvent::Command(command) => Some(command.as_str()),
+            _ => None,
+        });
+        commands.collect()
+    }
+}
+
+
+
+// ================
+// === Recorder ===
+// ================
+
+#[derive(Debug, Default)]
+struct RecorderModel {
+    frame:  Cell<usize>,
+    events: RefCell<Vec<RecordedEvent>>,
+}
+
+impl RecorderModel {
+    fn record(&self, event: InputEvent) {
+        let frame = self.frame.get();
+        self.events.borrow_mut().push(RecordedEvent { frame, event });
+    }
+
+    fn record_command(&self, name: &Option<ImString>) {
+        if let Some(name) = name {
+            self.record(InputEvent::Command(name.to_string()));
+        }
+    }
+
+    fn next_frame(&self) {
+        self.frame.set(self.frame.get() + 1);
+    }
+}
+
+/// Records the input of an [`Application`]. The recording stops when the recorder is dropped.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Recorder {
+    model:   Rc<RecorderModel>,
+    network: frp::Network,
+    handles: Rc<Vec<callback::Handle>>,
+}
+
+impl Recorder {
+    /// Constructor. Starts recording immediately.
+    pub fn new(app: &Application) -> Self {
+        let model = Rc::new(RecorderModel::default());
+        let manager = &app.display.default_scene.mouse.mouse_manager;
+        let down = f!((e: &mouse::Down) model.record(InputEvent::MouseDown(e.data())));
+        let up = f!((e: &mouse::Up) model.record(InputEvent::MouseUp(e.data())));
+        let move_ = f!((e: &mouse::Move) model.record(InputEvent::MouseMove(e.data())));
+        let wheel = f!((e: &mouse::Wheel) model.record(InputEvent::MouseWheel(e.wheel_data())));
+        let enter = f!((e: &mouse::Enter) model.record(InputEvent::MouseEnter(e.data())));
+        let leave = f!((e: &mouse::Leave) model.record(InputEvent::MouseLeave(e.data())));
+        let handles = vec![
+            app.display.on.before_frame.add(f_!(model.next_frame())),
+            manager.on_down.add(down),
+            manager.on_up.add(up),
+            manager.on_move.add(move_),
+            manager.on_wheel.add(wheel),
+            manager.on_enter.add(enter),
+            manager.on_leave.add(leave),
+        ];
+        let handles = Rc::new(handles);
+        let keyboard = &app.display.default_scene.keyboard.frp.source;
+        let shortcuts = &app.shortcuts;
+        frp::new_network! { network
+            eval keyboard.down ((key) model.record(InputEvent::KeyDown(key.into())));
+            eval keyboard.up ((key) model.record(InputEvent::KeyUp(key.into())));
+            eval_ keyboard.window_defocused (model.record(InputEvent::WindowDefocused));
+            eval shortcuts.currently_handled ((name) model.record_command(name));
+        }
+        Self { model, network, handles }
+    }
+
+    /// The number of frames which started since the recording began.
+    pub fn frame(&self) -> usize {
+        self.model.frame.get()
+    }
+
+    /// The input recorded so far.
+    pub fn log(&self) -> InputLog {
+        InputLog { events: self.model.events.borrow().clone() }
+    }
+}
+
+
+
+// ================
+// === Replayer ===
+// ================
+
+#[derive(Debug)]
+struct ReplayerModel {
+    mouse:    MouseManager,
+    keyboard: KeyboardSource,
+    events:   Vec<RecordedEvent>,
+    next:     Cell<usize>,
+    frame:    Cell<usize>,
+}
+
+impl ReplayerModel {
+    fn step_frame(&self) {
+        let frame = self.frame.get();
+        while let Some(recorded) = self.events.get(self.next.get()) {
+            if recorded.frame > frame {
+                break;
+            }
+            self.next.set(self.next.get() + 1);
+            self.dispatch(&recorded.event);
+        }
+        self.frame.set(frame + 1);
+    }
+
+    fn dispatch(&self, event: &InputEvent) {
+        let manager = &self.mouse;
+        let shape = manager.shape();
+        match event {
+            InputEvent::MouseDown(data) =>
+                manager.on_down.run_all(&mouse::Down::synthetic(*data, shape)),
+            InputEvent::MouseUp(data) => manager.on_up.run_all(&mouse::Up::synthetic(*data, shape)),
+            InputEvent::MouseMove(data) =>
+                manager.on_move.run_all(&mouse::Move::synthetic(*data, shape)),
+            InputEvent::MouseWheel(data) =>
+                manager.on_wheel.run_all(&mouse::Wheel::synthetic(*data, shape)),
+            InputEvent::MouseEnter(data) =>
+                manager.on_enter.run_all(&mouse::Enter::synthetic(*data, shape)),
+            InputEvent::MouseLeave(data) =>
+                manager.on_leave.run_all(&mouse::Leave::synthetic(*data, shape)),
+            InputEvent::KeyDown(key) => self.keyboard.down.emit(key.to_key_with_code()),
+            InputEvent::KeyUp(key) => self.keyboard.up.emit(key.to_key_with_code()),
+            InputEvent::WindowDefocused => self.keyboard.window_defocused.emit(()),
+            InputEvent::Command(_) => {}
+        }
+    }
+}
+
+/// Feeds a recorded [`InputLog`] into an [`Application`]. Before each frame, all events recorded
+/// before the corresponding frame of the recording are dispatched. The replay stops when the
+/// replayer is dropped.
+///
+/// Recorded shortcut commands are not dispatched, as they are triggered by the replayed input. To
+/// verify them, record the replayed application with a [`Recorder`] and compare the
+/// [`InputLog::commands`] of both logs.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Replayer {
+    model:  Rc<ReplayerModel>,
+    handle: callback::Handle,
+}
+
+impl Replayer {
+    /// Constructor. The replay starts with the next frame of the application.
+    pub fn new(app: &Application, log: InputLog) -> Self {
+        let scene = &app.display.default_scene;
+        let mouse = scene.mouse.mouse_manager.clone_ref();
+        let keyboard = scene.keyboard.frp.source.clone_ref();
+        let mut events = log.events;
+        events.sort_by_key(|recorded| recorded.frame);
+        let next = default();
+        let frame = default();
+        let model = Rc::new(ReplayerModel { mouse, keyboard, events, next, frame });
+        let handle = app.display.on.before_frame.add(f_!(model.step_frame()));
+        Self { model, handle }
+    }
+
+    /// Dispatch the events of the next frame without waiting for the application to draw it.
+    pub fn step_frame(&self) {
+        self.model.step_frame()
+    }
+
+    /// The number of replayed frames.
+    pub fn frame(&self) -> usize {
+        self.model.frame.get()
+    }
+
+    /// Check whether all the events of the log were dispatched.
+    pub fn is_finished(&self) -> bool {
+        self.model.next.get() >= self.model.events.len()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::animation::TimeInfo;
+    use crate::frp::io::keyboard::Key;
+    use crate::frp::io::keyboard::Side;
+
+    fn key(key: &str, code: &str) -> KeyWithCode {
+        KeyWithCode::new(key.into(), code.into())
+    }
+
+    fn mouse_data(client_x: i32, client_y: i32) -> mouse::EventData {
+        mouse::EventData { client_x, client_y, ..default() }
+    }
+
+    fn run_frames(app: &Application, count: usize) {
+        for _ in 0..count {
+            app.display.on.before_frame.run_all(TimeInfo::new());
+        }
+    }
+
+    #[test]
+    fn key_event_data_round_trip() {
+        for (key_value, code) in [("a", "KeyA"), ("Shift", "ShiftLeft"), ("ArrowUp", "ArrowUp")] {
+            let event = key(key_value, code);
+            let data = KeyEventData::from(&event);
+            assert_eq!(data.key, key_value);
+            assert_eq!(data.to_key_with_code().key, event.key);
+            assert_eq!(data.to_key_with_code().code, event.code);
+        }
+    }
+
+    #[test]
+    fn log_json_round_trip() {
+        let events = vec![
+            RecordedEvent { frame: 0, event: InputEvent::MouseMove(mouse_data(10, 20)) },
+            RecordedEvent { frame: 2, event: InputEvent::KeyDown((&key("a", "KeyA")).into()) },
+            RecordedEvent { frame: 2, event: InputEvent::WindowDefocused },
+            RecordedEvent { frame: 3, event: InputEvent::Command("cancel".into()) },
+        ];
+        let log = InputLog { events };
+        let json = log.to_json().unwrap();
+        assert_eq!(InputLog::from_json(&json).unwrap(), log);
+        assert_eq!(log.frame_count(), 4);
+        assert_eq!(log.commands(), vec!["cancel"]);
+    }
+
+    #[test]
+    fn recording_and_replay() {
+        let app = Application::new("root");
+        let recorder = Recorder::new(&app);
+        let manager = &app.display.default_scene.mouse.mouse_manager;
+        let keyboard = &app.display.default_scene.keyboard.frp.source;
+        let shape = manager.shape();
+        manager.on_move.run_all(&mouse::Move::synthetic(mouse_data(10, 20), shape));
+        run_frames(&app, 2);
+        keyboard.down.emit(key("Control", "ControlLeft"));
+        manager.on_down.run_all(&mouse::Down::synthetic(mouse_data(30, 40), shape));
+        run_frames(&app, 1);
+        manager.on_up.run_all(&mouse::Up::synthetic(mouse_data(30, 40), shape));
+        keyboard.up.emit(key("Control", "ControlLeft"));
+        let log = recorder.log();
+        assert_eq!(recorder.frame(), 3);
+        assert_eq!(log.events.len(), 5);
+        assert_eq!(log.frame_count(), 4);
+        let log = InputLog::from_json(&log.to_json().unwrap()).unwrap();
+
+        let replayed_app = Application::new("root");
+        let replayer = Replayer::new(&replayed_app, log.clone());
+        let replay_recorder = Recorder::new(&replayed_app);
+        let keys = Rc::new(RefCell::new(Vec::new()));
+        let keyboard = &replayed_app.display.default_scene.keyboard.frp;
+        frp::new_network! { network
+            eval keyboard.down ([keys](key) keys.borrow_mut().push(key.clone()));
+        }
+        run_frames(&replayed_app, log.frame_count() - 1);
+        assert!(!replayer.is_finished());
+        run_frames(&replayed_app, 1);
+        assert!(replayer.is_finished());
+        assert_eq!(replayer.frame(), log.frame_count());
+        assert_eq!(replay_recorder.log(), log);
+        assert_eq!(*keys.borrow(), vec![Key::Control(Side::Left)]);
+        drop(network);
+    }
+}
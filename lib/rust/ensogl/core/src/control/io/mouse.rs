@@ -130,3 +130,10 @@ define_bindings! { target, gloabl_target,
     MouseEvent::mouseenter => on_enter (target, Enter),
     WheelEvent::wheel      => on_wheel (target, Wheel),
 }
+
+impl MouseManager {
+    /// The current shape of the DOM object the mouse positions are measured in.
+    pub fn shape(&self) -> web::dom::Shape {
+        self.dom.shape.value()
+    }
+}
@@ -6,10 +6,38 @@ use web::traits::*;
 use crate::system::web;
 
 use enso_frp::io::mouse;
+use serde::Deserialize;
+use serde::Serialize;
 use web::dom::Shape;
 
 
 
+// =================
+// === EventData ===
+// =================
+
+/// The values of a mouse event, independent of the JavaScript event object. Allows recording the
+/// events and dispatching synthetic ones, for example when replaying the recorded input. The
+/// coordinates are stored as reported by the browser, before converting them to the EnsoGL space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct EventData {
+    pub client_x:   i32,
+    pub client_y:   i32,
+    pub screen_x:   i32,
+    pub screen_y:   i32,
+    pub movement_x: i32,
+    pub movement_y: i32,
+    pub button:     i16,
+    pub ctrl_key:   bool,
+    /// The scroll amount of wheel events. Zero for other events.
+    pub delta_x:    f64,
+    /// The scroll amount of wheel events. Zero for other events.
+    pub delta_y:    f64,
+}
+
+
+
 // =============
 // === Event ===
 // =============
@@ -19,6 +47,8 @@ use web::dom::Shape;
 #[derivative(Default(bound = ""))]
 pub struct Event<EventType, JsEvent> {
     js_event:   Option<JsEvent>,
+    /// The values used if the event was not created by the browser.
+    data:       Option<EventData>,
     shape:      Shape,
     event_type: PhantomData<EventType>,
 }
@@ -55,8 +85,46 @@ where JsEvent: AsRef<web::MouseEvent>
     /// Constructor.
     pub fn new(js_event: JsEvent, shape: Shape) -> Self {
         let js_event = Some(js_event);
+        let data = None;
+        let event_type = default();
+        Self { js_event, data, shape, event_type }
+    }
+
+    /// Constructor of an event not backed by a JavaScript event object.
+    pub fn synthetic(data: EventData, shape: Shape) -> Self {
+        let js_event = None;
+        let data = Some(data);
         let event_type = default();
-        Self { js_event, shape, event_type }
+        Self { js_event, data, shape, event_type }
+    }
+
+    /// Read a value from the JavaScript event, or from the synthetic event data.
+    fn read<T: Default>(
+        &self,
+        from_js_event: impl FnOnce(&web::MouseEvent) -> T,
+        from_data: impl FnOnce(&EventData) -> T,
+    ) -> T {
+        match (&self.js_event, &self.data) {
+            (Some(js_event), _) => from_js_event(js_event.as_ref()),
+            (None, Some(data)) => from_data(data),
+            (None, None) => default(),
+        }
+    }
+
+    /// The values of this event, allowing to recreate it with [`Event::synthetic`].
+    pub fn data(&self) -> EventData {
+        EventData {
+            client_x:   self.read(|e| e.client_x(), |d| d.client_x),
+            client_y:   self.read(|e| e.client_y(), |d| d.client_y),
+            screen_x:   self.read(|e| e.screen_x(), |d| d.screen_x),
+            screen_y:   self.read(|e| e.screen_y(), |d| d.screen_y),
+            movement_x: self.read(|e| e.movement_x(), |d| d.movement_x),
+            movement_y: self.read(|e| e.movement_y(), |d| d.movement_y),
+            button:     self.read(|e| e.button(), |d| d.button),
+            ctrl_key:   self.read(|e| e.ctrl_key(), |d| d.ctrl_key),
+            delta_x:    self.data.map(|d| d.delta_x).unwrap_or_default(),
+            delta_y:    self.data.map(|d| d.delta_y).unwrap_or_default(),
+        }
     }
 
     /// The horizontal coordinate within the application's viewport at which the event occurred (as
@@ -65,7 +133,7 @@ where JsEvent: AsRef<web::MouseEvent>
     /// For example, clicking on the left edge of the viewport will always result in a mouse event
     /// with a [`client_x`] value of 0, regardless of whether the page is scrolled horizontally.
     pub fn client_x(&self) -> i32 {
-        self.js_event.as_ref().map(|t| t.as_ref().client_x()).unwrap_or_default()
+        self.read(|t| t.client_x(), |d| d.client_x)
     }
 
     /// The vertical coordinate within the application's viewport at which the event occurred (as
@@ -74,8 +142,7 @@ where JsEvent: AsRef<web::MouseEvent>
     /// For example, clicking on the bottom edge of the viewport will always result in a mouse event
     /// with a [`client_y`] value of 0, regardless of whether the page is scrolled horizontally.
     pub fn client_y(&self) -> i32 {
-        self.shape.height as i32
-            - self.js_event.as_ref().map(|t| t.as_ref().client_y()).unwrap_or_default()
+        self.shape.height as i32 - self.read(|t| t.client_y(), |d| d.client_y)
     }
 
     /// The coordinate within the application's viewport at which the event occurred (as opposed to
@@ -97,13 +164,12 @@ where JsEvent: AsRef<web::MouseEvent>
 
     /// The horizontal coordinate (offset) of the mouse pointer in global (screen) coordinates.
     pub fn screen_x(&self) -> f32 {
-        self.js_event.as_ref().map(|t| t.as_ref().screen_x()).unwrap_or_default() as f32
+        self.read(|t| t.screen_x(), |d| d.screen_x) as f32
     }
 
     /// The vertical coordinate (offset) of the mouse pointer in global (screen) coordinates.
     pub fn screen_y(&self) -> f32 {
-        self.shape.height
-            - self.js_event.as_ref().map(|t| t.as_ref().screen_y()).unwrap_or_default() as f32
+        self.shape.height - self.read(|t| t.screen_y(), |d| d.screen_y) as f32
     }
 
     /// The coordinate (offset) of the mouse pointer in global (screen) coordinates.
@@ -115,14 +181,14 @@ where JsEvent: AsRef<web::MouseEvent>
     /// previous mousemove event. In other words, the value of the property is computed like this:
     /// `current_event.movement_x = current_event.screen_x() - previous_event.screen_x()`.
     pub fn movement_x(&self) -> i32 {
-        self.js_event.as_ref().map(|t| t.as_ref().movement_x()).unwrap_or_default()
+        self.read(|t| t.movement_x(), |d| d.movement_x)
     }
 
     /// The difference in the Y coordinate of the mouse pointer between the given event and the
     /// previous mousemove event. In other words, the value of the property is computed like this:
     /// `current_event.movement_y = current_event.screen_y() - previous_event.screen_y()`.
     pub fn movement_y(&self) -> i32 {
-        -self.js_event.as_ref().map(|t| t.as_ref().movement_y()).unwrap_or_default()
+        -self.read(|t| t.movement_y(), |d| d.movement_y)
     }
 
     /// The difference in the coordinate of the mouse pointer between the given event and the
@@ -134,9 +200,7 @@ where JsEvent: AsRef<web::MouseEvent>
 
     /// Indicates which button was pressed on the mouse to trigger the event.
     pub fn button(&self) -> mouse::Button {
-        mouse::Button::from_code(
-            self.js_event.as_ref().map(|t| t.as_ref().button().into()).unwrap_or_default(),
-        )
+        mouse::Button::from_code(self.read(|t| t.button(), |d| d.button).into())
     }
 
     /// Return the position relative to the event handler that was used to catch the event. If the
@@ -171,7 +235,7 @@ where JsEvent: AsRef<web::MouseEvent>
 
     /// Check whether the `ctrl` key was pressed when the event was triggered.
     pub fn ctrl_key(&self) -> bool {
-        self.js_event.as_ref().map(|t| t.as_ref().ctrl_key()).unwrap_or_default()
+        self.read(|t| t.ctrl_key(), |d| d.ctrl_key)
     }
 
     /// Prevent the default action of the event.
@@ -184,9 +248,10 @@ where JsEvent: AsRef<web::MouseEvent>
         self,
     ) -> Event<EventPhantomType<NewEventType>, JsEvent> {
         let js_event = self.js_event;
+        let data = self.data;
         let shape = self.shape;
         let event_type = default();
-        Event { js_event, shape, event_type }
+        Event { js_event, data, shape, event_type }
     }
 }
 
@@ -331,11 +396,18 @@ define_events! {
 impl Wheel {
     /// The horizontal scroll amount.
     pub fn delta_x(&self) -> f64 {
-        self.js_event.as_ref().map(|t| t.delta_x()).unwrap_or_default()
+        let from_data = || self.data.map(|d| d.delta_x).unwrap_or_default();
+        self.js_event.as_ref().map(|t| t.delta_x()).unwrap_or_else(from_data)
     }
 
     /// The vertical scroll amount.
     pub fn delta_y(&self) -> f64 {
-        self.js_event.as_ref().map(|t| t.delta_y()).unwrap_or_default()
+        let from_data = || self.data.map(|d| d.delta_y).unwrap_or_default();
+        self.js_event.as_ref().map(|t| t.delta_y()).unwrap_or_else(from_data)
+    }
+
+    /// The values of this event, including the scroll amount. See [`Event::data`].
+    pub fn wheel_data(&self) -> EventData {
+        EventData { delta_x: self.delta_x(), delta_y: self.delta_y(), ..self.data() }
     }
 }
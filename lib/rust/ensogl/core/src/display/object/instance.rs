@@ -491,6 +491,39 @@
 //! ```
 //!
 //!
+//! ## Wrapping items.
+//! By default, all items are placed in a single row, unless the column count was limited. If you
+//! enable wrapping with the [`set_wrap`] method, the items will be moved to the next row when they
+//! do not fit the container anymore. In contrast to the rows of a regular grid, every wrapped row
+//! is laid out independently, so the items in consecutive rows are not aligned to common columns.
+//! This is similar to the CSS flexbox with `flex-wrap` enabled and is useful when creating "image
+//! walls" or lists of tags, where items have different lengths. If the container hugs its children,
+//! the available space is limited by its maximum size. In the column flow, the items are wrapped to
+//! the next column instead. For example, the following code places three items in two rows:
+//!
+//! ```
+//! // ╔ root ═══════════════════════════════╗
+//! // ║ ╭ node3 ──────────╮                 ║
+//! // ║ │                 │                 ║
+//! // ║ ╰─────────────────╯                 ║
+//! // ║ ╭ node1 ──────────╮ ╭ node2 ──────╮ ║
+//! // ║ │                 │ │             │ ║
+//! // ║ ╰─────────────────╯ ╰─────────────╯ ║
+//! // ╚═════════════════════════════════════╝
+//!
+//! # use ensogl_core::prelude::*;
+//! # use ensogl_core::display;
+//! let root = display::object::Instance::new();
+//! let node1 = root.new_child();
+//! let node2 = root.new_child();
+//! let node3 = root.new_child();
+//! node1.set_size((4.0, 2.0));
+//! node2.set_size((3.0, 2.0));
+//! node3.set_size((4.0, 2.0));
+//! root.use_auto_layout().set_wrap(true).set_gap((1.0, 1.0)).set_size_x(8.0);
+//! ```
+//!
+//!
 //! ## The column/row gap.
 //! The gap between columns/rows specifies the size of the grid lines. You can think of it like
 //! setting the width of the gutters between the columns/rows. The gutters are only created between
@@ -593,6 +626,25 @@
 //! ```
 //!
 //!
+//! ## Minimum and maximum size.
+//! Every item can limit its size with the [`set_min_size`] and [`set_max_size`] methods. The limits
+//! are applied whenever the item size is computed, no matter if it was set to a fixed value, it
+//! hugs its children, or it was resized by growing or shrinking. Only limits expressed in pixels
+//! are taken into account. For example, the following code creates an item that hugs its children,
+//! but is never wider than 5 pixels:
+//!
+//! ```
+//! # use ensogl_core::prelude::*;
+//! # use ensogl_core::display;
+//! let root = display::object::Instance::new();
+//! let node1 = root.new_child();
+//! let node1_1 = node1.new_child();
+//! node1_1.set_size((8.0, 2.0));
+//! node1.set_max_size_x(5.0);
+//! root.use_auto_layout();
+//! ```
+//!
+//!
 //! ## Percentage size units.
 //! Sizes of all dimensions (element sizes, margins, paddings, column widths, row heights, etc.) can
 //! be expressed as a percentage value of the parent container's size. Numbers are equipped with a
@@ -885,24 +937,6 @@
 //! the Grid layout yet. See the `grid-template-areas` property to learn more about it:
 //! https://css-tricks.com/snippets/css/complete-guide-grid/#aa-grid-template-areas.
 //!
-//! ### The masonry layout.
-//! The masonry layout is a layout where rows or columns are not aligned and can be shifted in one
-//! direction. This mode is useful when creating "image walls" or lists of tags, where items have
-//! different lengths. For example, it will allow to place items in the following layout:
-//!
-//! ╔════════════════════════════════════════════════════════════════════════╗
-//! ║ ╭───────────── ▶ ◀ ────────────┬─── ▶ ◀ ───┬─────────── ▶ ◀ ─────────╮ ║
-//! ║ │  ╭────────────────────────╮  ┆ ╭───────╮ ┆ ╭─────────────────────╮ ▼ ║
-//! ║ │  ╰────────────────────────╯  ┆ ╰───────╯ ┆ ╰─────────────────────╯ ▲ ▼
-//! ║ ├╌╌╌╌╌╌╌╌╌╌╌╌┬╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌╌┴╌╌╌╌┬╌╌╌╌╌╌┴╌╌╌╌╌╌╌┬╌╌╌╌╌╌╌╌┬╌╌╌╌╌╌╌╌╯ ▲
-//! ║ │  ╭──────╮  ┆ ╭──────────────────╮ ┆ ╭──────────╮ ┆ ╭────╮ ▼          ║
-//! ║ │  ╰──────╯  ┆ ╰──────────────────╯ ┆ ╰──────────╯ ┆ ╰────╯ ▲          ║
-//! ║ ╰─── ▶ ◀ ────┴───────── ▶ ◀ ────────┴──── ▶ ◀ ─────┴─ ▶ ◀ ──╯          ║
-//! ╚════════════════════════════════════════════════════════════════════════╝
-//!
-//! To learn more about the masonry layout, see the following article:
-//! https://css-tricks.com/snippets/css/complete-guide-grid/#aa-masonry.
-//!
 //!
 //!
 //!
@@ -1010,6 +1044,40 @@
 //! node2_2.set_xy((1.0, -1.0));
 //! ```
 //!
+//!
+//!
+//!
+//! # Anchoring.
+//! Any child can be anchored to the edges or the center of its parent, independently of the
+//! parent's layout. An object anchored in a given axis is not placed by the parent's layout in
+//! that axis and is not taken into account when the parent hugs its children. In the grid layout,
+//! an object anchored in any axis is not placed in the grid at all. The anchored objects are placed
+//! after the parent size is computed, so they follow the parent when it is resized. The offsets
+//! can be expressed in pixels or in percent of the parent size. For example, the following code
+//! places a close button in the top right corner of a panel, while the title is placed in the
+//! panel's center and the background covers the whole panel:
+//!
+//! ```
+//! // ╔ panel ════════════════════════╗
+//! // ║                     ╭ close ╮ ║
+//! // ║                     ╰───────╯ ║
+//! // ║        ╭ title ──────╮        ║
+//! // ║        ╰─────────────╯        ║
+//! // ║                               ║
+//! // ╚═══════════════════════════════╝
+//!
+//! # use ensogl_core::prelude::*;
+//! # use ensogl_core::display;
+//! let panel = display::object::Instance::new();
+//! let background = panel.new_child();
+//! let title = panel.new_child();
+//! let close = panel.new_child();
+//! panel.use_auto_layout().set_size((20.0, 10.0));
+//! background.set_anchor_fill();
+//! title.set_size((8.0, 2.0)).set_anchor_center();
+//! close.set_size((2.0, 2.0)).set_anchor_right(1.0).set_anchor_top(1.0);
+//! ```
+//!
 //! # Shape view alignment inside layout objects.
 //!
 //! The shape views defined using `shape!` can have their own set alignment, which defines how the
@@ -1114,6 +1182,7 @@ use crate::display::scene::Scene;
 use enso_types::Dim;
 use nalgebra::Matrix4;
 use nalgebra::Vector3;
+use std::panic::Location;
 use transformation::CachedTransformation;
 use unit2::Fraction;
 
@@ -1151,6 +1220,13 @@ pub const DEFAULT_NAME: &str = "UnnamedDisplayObject";
 )]
 pub struct Id(usize);
 
+impl Id {
+    /// The key of the display object in the [`frp::leak_detector`].
+    pub fn tracking_key(self) -> frp::leak_detector::ObjectKey {
+        frp::leak_detector::ObjectKey::new("display::object::Instance", self.0)
+    }
+}
+
 /// The index of a child of a display object.
 #[derive(
     Clone, CloneRef, Copy, Debug, Default, Deref, Display, Eq, From, Hash, Into, PartialEq, Ord,
@@ -1212,39 +1288,52 @@ pub struct Model {
 impl Instance {
     /// Constructor with default name. Will have DOM debugging enabled if [`ENABLE_DOM_DEBUG_ALL`]
     /// flag is enabled.
+    #[track_caller]
     pub fn new() -> Self {
         Self::new_named_with_debug(DEFAULT_NAME, ENABLE_DOM_DEBUG_ALL)
     }
 
     /// Constructor with DOM debugging always disabled.
+    #[track_caller]
     pub fn new_no_debug() -> Self {
         Self::new_named_with_debug(DEFAULT_NAME, false)
     }
 
     /// Constructor with DOM debugging always enabled.
+    #[track_caller]
     pub fn new_debug() -> Self {
         Self::new_named_with_debug(DEFAULT_NAME, true)
     }
 
     /// Constructor with custom name. Will have DOM debugging enabled if [`ENABLE_DOM_DEBUG_ALL`]
     /// flag is enabled.
+    #[track_caller]
     pub fn new_named(name: &'static str) -> Self {
         Self::new_named_with_debug(name, ENABLE_DOM_DEBUG_ALL)
     }
 
     /// Constructor with custom name and DOM debugging always disabled.
+    #[track_caller]
     pub fn new_named_no_debug(name: &'static str) -> Self {
         Self::new_named_with_debug(name, false)
     }
 
     /// Constructor with custom name and DOM debugging always enabled.
+    #[track_caller]
     pub fn new_named_debug(name: &'static str) -> Self {
         Self::new_named_with_debug(name, true)
     }
 
     /// Constructor with custom name and DOM debugging enabled with an argument.
+    #[track_caller]
     pub fn new_named_with_debug(name: &'static str, enable_debug: bool) -> Self {
-        Self { def: InstanceDef::new(name, enable_debug) }
+        let instance = Self { def: InstanceDef::new(name, enable_debug) };
+        if frp::leak_detector::is_enabled() {
+            let key = instance.id().tracking_key();
+            frp::leak_detector::track(key, name, Location::caller());
+            frp::leak_detector::add_reference(key, instance.network.id().tracking_key());
+        }
+        instance
     }
 }
 
@@ -1289,6 +1378,8 @@ impl Model {
 
 impl Drop for Model {
     fn drop(&mut self) {
+        let ptr: *const Self = self;
+        frp::leak_detector::untrack(Id(ptr as *const () as usize).tracking_key());
         if ENABLE_DOM_DEBUG {
             if let Some(dom) = self.debug_dom.take() {
                 dom.remove();
@@ -1300,6 +1391,7 @@ impl Drop for Model {
 // === Impls ===
 
 impl Default for Instance {
+    #[track_caller]
     fn default() -> Self {
         Self::new()
     }
@@ -1335,6 +1427,12 @@ impl Display for InstanceDef {
     }
 }
 
+impl frp::leak_detector::TrackedRef for Instance {
+    fn tracked_key(&self) -> Option<frp::leak_detector::ObjectKey> {
+        Some(self.id().tracking_key())
+    }
+}
+
 
 
 // ====================
@@ -2754,7 +2852,11 @@ pub struct LayoutModel {
     size:          Cell<Vector2<Size>>,
     grow_factor:   Cell<Vector2<f32>>,
     shrink_factor: Cell<Vector2<f32>>,
+    anchor:        Cell<Vector2<Option<Anchor>>>,
     computed_size: Cell<Vector2<f32>>,
+    /// The number of children in consecutive lines of the wrapping auto layout. The lines are
+    /// computed in the horizontal layout pass and reused in the vertical one.
+    wrapped_lines: RefCell<Vec<usize>>,
 }
 
 impl Model {
@@ -3107,6 +3209,77 @@ pub trait LayoutOps: Object {
         self.display_object().layout.padding.set(padding);
         self
     }
+
+    /// Get the anchoring of the object.
+    fn anchor(&self) -> Vector2<Option<Anchor>> {
+        self.display_object().layout.anchor.get()
+    }
+
+    /// Modify the anchoring of the object. An object anchored in a given axis is not placed by the
+    /// layout of its parent. Instead, it is placed relative to the edges or the center of the
+    /// parent. See the docs of this module to learn more.
+    #[enso_shapely::gen(update, set)]
+    fn modify_anchor(&self, f: impl FnOnce(&mut Vector2<Option<Anchor>>)) -> &Self {
+        self.display_object().modify_layout(|l| l.anchor.modify_(f));
+        self
+    }
+
+    /// Anchor the object at the given distance from the left edge of its parent.
+    fn set_anchor_left(&self, offset: impl Into<Unit>) -> &Self {
+        self.set_anchor_x(Anchor::Start(offset.into()))
+    }
+
+    /// Anchor the object at the given distance from the right edge of its parent.
+    fn set_anchor_right(&self, offset: impl Into<Unit>) -> &Self {
+        self.set_anchor_x(Anchor::End(offset.into()))
+    }
+
+    /// Anchor the object at the given distance from the bottom edge of its parent.
+    fn set_anchor_bottom(&self, offset: impl Into<Unit>) -> &Self {
+        self.set_anchor_y(Anchor::Start(offset.into()))
+    }
+
+    /// Anchor the object at the given distance from the top edge of its parent.
+    fn set_anchor_top(&self, offset: impl Into<Unit>) -> &Self {
+        self.set_anchor_y(Anchor::End(offset.into()))
+    }
+
+    /// Anchor the object to the horizontal center of its parent.
+    fn set_anchor_center_x(&self) -> &Self {
+        self.set_anchor_x(Anchor::Center(default()))
+    }
+
+    /// Anchor the object to the vertical center of its parent.
+    fn set_anchor_center_y(&self) -> &Self {
+        self.set_anchor_y(Anchor::Center(default()))
+    }
+
+    /// Anchor the object to the center of its parent.
+    fn set_anchor_center(&self) -> &Self {
+        self.set_anchor_center_x().set_anchor_center_y()
+    }
+
+    /// Stretch the object between the left and right edges of its parent, leaving the given space
+    /// at both sides.
+    fn set_anchor_stretch_x(&self, left: impl Into<Unit>, right: impl Into<Unit>) -> &Self {
+        self.set_anchor_x(Anchor::Stretch(SideSpacing::new(left.into(), right.into())))
+    }
+
+    /// Stretch the object between the bottom and top edges of its parent, leaving the given space
+    /// at both sides.
+    fn set_anchor_stretch_y(&self, bottom: impl Into<Unit>, top: impl Into<Unit>) -> &Self {
+        self.set_anchor_y(Anchor::Stretch(SideSpacing::new(bottom.into(), top.into())))
+    }
+
+    /// Stretch the object to cover its whole parent.
+    fn set_anchor_fill(&self) -> &Self {
+        self.set_anchor_stretch_x(0.0, 0.0).set_anchor_stretch_y(0.0, 0.0)
+    }
+
+    /// Remove the anchoring of the object, so it is placed by the layout of its parent again.
+    fn unset_anchor(&self) -> &Self {
+        self.set_anchor(Vector2(None, None))
+    }
 }
 
 
@@ -3248,6 +3421,9 @@ pub struct AutoLayout {
     /// The number of columns and rows in the grid. If it's set to [`None`], the columns and rows
     /// will grow on demand.
     pub columns_and_rows_count: Vector2<Option<usize>>,
+    /// Indicates whether the items should be wrapped to the next row (or column, in the column
+    /// flow) when they do not fit the container. Each row is then laid out independently.
+    pub wrap: bool,
 }
 
 /// A trait alias for accessing columns and rows.
@@ -3429,6 +3605,13 @@ pub trait AutoLayoutOps: Object {
         self.display_object().modify_auto_layout(|l| l.flow = AutoLayoutFlow::Column);
         self
     }
+
+    /// Control whether the items should be wrapped to the next row (or column, in the column flow)
+    /// when they do not fit the container. See the docs of this module to learn more.
+    fn set_wrap(&self, wrap: bool) -> &Self {
+        self.display_object().modify_auto_layout(|l| l.wrap = wrap);
+        self
+    }
 }
 
 
@@ -3550,6 +3733,7 @@ where
     Vector2<bool>: DimSetter<Self>,
     Vector2<f32>: DimSetter<Self>,
     Vector2<Unit>: DimSetter<Self>,
+    Vector2<Option<Anchor>>: DimSetter<Self>,
     Vector3<f32>: DimSetter<Self>,
     (NonEmptyVec<ColumnOrRow>, NonEmptyVec<ColumnOrRow>):
         DimRef<Self, Output = NonEmptyVec<ColumnOrRow>>;
@@ -3585,7 +3769,23 @@ impl Model {
     fn reset_size_to_static_values<Dim>(&self, x: Dim, parent_size: f32)
     where Dim: ResolutionDim {
         let size = self.resolve_size_static_values(x, parent_size);
-        self.layout.computed_size.set_dim(x, size);
+        self.layout.computed_size.set_dim(x, self.clamp_to_size_limits(x, size));
+    }
+
+    /// Clamp the size to the [`min_size`] and [`max_size`] of this display object. Only the
+    /// constraints expressed in pixels are taken into account. If the constraints contradict each
+    /// other, the minimum size wins.
+    fn clamp_to_size_limits<Dim>(&self, x: Dim, size: f32) -> f32
+    where Dim: ResolutionDim {
+        let min_size = self.layout.min_size.get_dim(x).resolve_pixels_or_default();
+        let max_size = self.layout.max_size.get_dim(x).resolve_pixels().unwrap_or(f32::INFINITY);
+        f32::max(min_size, f32::min(max_size, size))
+    }
+
+    /// Check whether this display object is anchored in any axis.
+    fn is_anchored(&self) -> bool {
+        let anchor = self.layout.anchor.get();
+        anchor.x.is_some() || anchor.y.is_some()
     }
 
     fn resolve_size_static_values<Dim>(&self, x: Dim, parent_size: f32) -> f32
@@ -3632,7 +3832,8 @@ impl Model {
     where Dim: ResolutionDim {
         let hug_children = pass_cfg != PassConfig::DoNotHugDirectChildren;
         let hug_children = hug_children && self.layout.size.get_dim(x).is_hug();
-        let children = self.children();
+        let (anchored_children, children): (Vec<_>, Vec<_>) =
+            self.children().into_iter().partition(|child| child.layout.anchor.get_dim(x).is_some());
         let old_child_computed_sizes: Vec<f32> =
             children.iter().map(|child| child.layout.computed_size.get_dim(x)).collect();
 
@@ -3667,7 +3868,7 @@ impl Model {
         }
 
         if hug_children {
-            self.layout.computed_size.set_dim(x, max_x);
+            self.layout.computed_size.set_dim(x, self.clamp_to_size_limits(x, max_x));
         }
 
         // Resolve aligned children and hug them again.
@@ -3685,7 +3886,7 @@ impl Model {
                 }
             }
             if hug_children {
-                self.layout.computed_size.set_dim(x, max_x);
+                self.layout.computed_size.set_dim(x, self.clamp_to_size_limits(x, max_x));
             }
         }
 
@@ -3705,6 +3906,7 @@ impl Model {
                         let child_static_size = child.resolve_size_static_values(x, self_size);
                         desired_child_size = desired_child_size.max(child_static_size);
                     }
+                    let desired_child_size = child.clamp_to_size_limits(x, desired_child_size);
 
                     if desired_child_size != child_size || child.should_refresh_layout() {
                         child.layout.computed_size.set_dim(x, desired_child_size);
@@ -3726,6 +3928,43 @@ impl Model {
                 child.dirty.computed_size.set();
             }
         }
+
+        self.refresh_anchored_children(x, &anchored_children);
+    }
+
+    /// Size and place the children anchored in the [`x`] axis. The anchored children are resolved
+    /// after all other children, as their placement depends on the final size of this object. In
+    /// the grid layout, children anchored only in the other axis are passed here as well, as they
+    /// are not part of the grid. They keep their position in the [`x`] axis.
+    ///
+    /// # Meaning of the function parameters.
+    /// In order to make the code easy to understand, all variables in layout functions were named
+    /// as if the code was updating horizontal layout only. In reality, the variable [`x`] can be
+    /// set to either [`X`] or [`Y`] to update horizontal and vertical axis, respectively.
+    fn refresh_anchored_children<Dim>(&self, x: Dim, children: &[Instance])
+    where Dim: ResolutionDim {
+        let self_size = self.layout.computed_size.get_dim(x);
+        for child in children {
+            let old_size = child.layout.computed_size.get_dim(x);
+            let anchor = child.layout.anchor.get_dim(x);
+            if let Some(size) = anchor.and_then(|anchor| anchor.resolve_size(self_size)) {
+                let size = child.clamp_to_size_limits(x, size);
+                if size != old_size || child.should_refresh_layout() {
+                    child.layout.computed_size.set_dim(x, size);
+                    child.refresh_layout_internal(x, PassConfig::DoNotHugDirectChildren);
+                }
+            } else if child.should_propagate_parent_layout_refresh(x) {
+                child.reset_size_to_static_values(x, self_size);
+                child.refresh_layout_internal(x, PassConfig::Default);
+            }
+            if let Some(anchor) = anchor {
+                let size = child.layout.computed_size.get_dim(x);
+                child.set_position_dim(x, anchor.resolve_position(self_size, size));
+            }
+            if child.layout.computed_size.get_dim(x) != old_size {
+                child.dirty.computed_size.set();
+            }
+        }
     }
 }
 
@@ -3829,23 +4068,10 @@ impl Model {
                 for child in &children {
                     let child_grow_factor = child.layout.grow_factor.get_dim(x);
                     let child_shrink_factor = child.layout.shrink_factor.get_dim(x);
-
-                    match child.layout.size.get_dim(x) {
-                        Size::Hug => {
-                            let child_can_grow_or_shrink =
-                                child_grow_factor > 0.0 || child_shrink_factor > 0.0;
-                            let refresh_child = child_can_grow_or_shrink
-                                || child.should_propagate_parent_layout_refresh(x);
-                            if refresh_child {
-                                child.reset_size_to_static_values(x, self_const_size);
-                                child.refresh_layout_internal(x, PassConfig::Default);
-                            }
-                        }
-                        Size::Fixed(unit) => {
-                            max_child_fr = max(max_child_fr, unit.as_fraction_or_default());
-                            child.reset_size_to_static_values(x, self_const_size);
-                        }
-                    };
+                    if let Size::Fixed(unit) = child.layout.size.get_dim(x) {
+                        max_child_fr = max(max_child_fr, unit.as_fraction_or_default());
+                    }
+                    child.refresh_grid_child_static_size(x, self_const_size);
 
                     let child_margin = child.layout.margin.get_dim(x).resolve_pixels_or_default();
                     let child_size = child.layout.computed_size.get_dim(x) + child_margin.total();
@@ -3895,6 +4121,23 @@ impl Model {
         }
     }
 
+    /// Reset the size of this object, a child of a grid layout, to its static value. If the size is
+    /// set to hug and it might have changed, the layout of this object is refreshed to compute it.
+    fn refresh_grid_child_static_size<Dim>(&self, x: Dim, parent_size: f32)
+    where Dim: ResolutionDim {
+        match self.layout.size.get_dim(x) {
+            Size::Hug => {
+                let can_grow_or_shrink = self.layout.grow_factor.get_dim(x) > 0.0
+                    || self.layout.shrink_factor.get_dim(x) > 0.0;
+                if can_grow_or_shrink || self.should_propagate_parent_layout_refresh(x) {
+                    self.reset_size_to_static_values(x, parent_size);
+                    self.refresh_layout_internal(x, PassConfig::Default);
+                }
+            }
+            Size::Fixed(_) => self.reset_size_to_static_values(x, parent_size),
+        }
+    }
+
     fn shrink_or_grow_coeff(f: impl Fn(f32, f32) -> f32, total_factor: f32, space: f32) -> f32 {
         if total_factor > 0.0 {
             f(0.0, space / total_factor)
@@ -3917,17 +4160,161 @@ impl Model {
     /// set to either [`X`] or [`Y`] to update horizontal and vertical axis, respectively.
     fn refresh_grid_layout<Dim>(&self, x: Dim, opts: &AutoLayout)
     where Dim: ResolutionDim {
-        let children = self.children();
-        if children.is_empty() {
-            return;
+        let (anchored_children, children): (Vec<_>, Vec<_>) =
+            self.children().into_iter().partition(|child| child.is_anchored());
+        if !children.is_empty() {
+            let old_child_computed_sizes: Vec<f32> =
+                children.iter().map(|child| child.layout.computed_size.get_dim(x)).collect();
+            if opts.wrap {
+                self.refresh_wrapped_grid_layout(x, opts, &children);
+            } else {
+                let unresolved_columns = self.divide_children_to_columns(x, opts, &children);
+                let columns = self.resolve_columns(x, opts, unresolved_columns);
+                self.place_columns(x, opts, columns);
+            }
+            for (child, old_size) in children.iter().zip(old_child_computed_sizes) {
+                if child.layout.computed_size.get_dim(x) != old_size {
+                    child.dirty.computed_size.set();
+                }
+            }
         }
-        let old_child_computed_sizes: Vec<f32> =
-            children.iter().map(|child| child.layout.computed_size.get_dim(x)).collect();
+        self.refresh_anchored_children(x, &anchored_children);
+    }
 
-        let unresolved_columns = self.divide_children_to_columns(x, opts, &children);
-        let mut columns = self.resolve_columns(x, opts, unresolved_columns);
+    /// Refresh the grid layout with enabled wrapping. The children are first divided into lines.
+    /// In the flow direction, every line is resolved independently, as if it was a separate grid
+    /// with a single row. In the other direction, every line is a single row of the grid.
+    ///
+    /// # Meaning of the function parameters.
+    /// In order to make the code easy to understand, all variables in layout functions were named
+    /// as if the code was updating horizontal layout only. In reality, the variable [`x`] can be
+    /// set to either [`X`] or [`Y`] to update horizontal and vertical axis, respectively.
+    fn refresh_wrapped_grid_layout<Dim>(&self, x: Dim, opts: &AutoLayout, children: &[Instance])
+    where Dim: ResolutionDim {
+        if !x.last_pass() {
+            let lines = match opts.flow {
+                AutoLayoutFlow::Row => self.divide_children_to_lines(X, opts, children, true),
+                AutoLayoutFlow::Column => self.divide_children_to_lines(Y, opts, children, false),
+            };
+            *self.layout.wrapped_lines.borrow_mut() = lines;
+        }
+        let mut lines = self.layout.wrapped_lines.borrow().clone();
+        if lines.iter().sum::<usize>() != children.len() {
+            lines = vec![children.len()];
+        }
+        let axis_defs = opts.columns_and_rows.get_dim(x);
+        let mut children_iter = children.iter();
+        if x.matches_flow_direction(opts.flow) {
+            let lines = lines.into_iter().map(|line_len| {
+                let line = children_iter.by_ref().take(line_len);
+                let unresolved_columns = axis_defs.iter().cycle().zip(line).map(|(axis, child)| {
+                    let axis = *axis;
+                    let children = vec![child.clone_ref()];
+                    UnresolvedColumn { axis, children }
+                });
+                self.resolve_columns(x, opts, unresolved_columns.collect())
+            });
+            let lines = lines.collect_vec();
+            if self.layout.size.get_dim(x).is_hug() {
+                let padding = self.layout.padding.get_dim(x).resolve_pixels_or_default().total();
+                let gap = opts.gap.get_dim(x).resolve_pixels_or_default();
+                let line_size = |columns: &Vec<ResolvedColumn>| {
+                    let gap_count = (columns.len() - 1) as f32;
+                    columns.iter().map(|column| column.computed_size).sum::<f32>() + gap * gap_count
+                };
+                let max_line_size = lines.iter().map(line_size).fold(0.0, f32::max);
+                self.layout.computed_size.update_dim(x, |t| t.max(padding + max_line_size));
+            }
+            for columns in lines {
+                self.place_columns(x, opts, columns);
+            }
+        } else {
+            let unresolved_rows = axis_defs.iter().cycle().zip(lines).map(|(axis, line_len)| {
+                let axis = *axis;
+                let children = children_iter.by_ref().take(line_len).cloned().collect_vec();
+                UnresolvedColumn { axis, children }
+            });
+            let rows = self.resolve_columns(x, opts, unresolved_rows.collect());
+            self.place_columns(x, opts, rows);
+        }
+    }
 
+    /// Divide the children into lines of the wrapping grid layout. Children are placed in the
+    /// current line until the next one does not fit the space available in this object. If this
+    /// object hugs its children, the space is limited by its [`max_size`]. The line length is also
+    /// limited by the column count (or row count, in the column flow), if set.
+    ///
+    /// The lines are computed in the first layout pass. If [`x`] is not the direction of this pass,
+    /// the children sizes are not refreshed. Instead, their static sizes are used and, if they hug
+    /// their children, the sizes computed during the previous layout refresh.
+    ///
+    /// # Meaning of the function parameters.
+    /// In order to make the code easy to understand, all variables in layout functions were named
+    /// as if the code was updating horizontal layout only. In reality, the variable [`x`] can be
+    /// set to either [`X`] or [`Y`] to update horizontal and vertical axis, respectively.
+    fn divide_children_to_lines<Dim>(
+        &self,
+        x: Dim,
+        opts: &AutoLayout,
+        children: &[Instance],
+        current_pass: bool,
+    ) -> Vec<usize>
+    where
+        Dim: ResolutionDim,
+    {
+        let computed_size = self.layout.computed_size.get_dim(x);
+        let self_size = match self.layout.size.get_dim(x) {
+            Size::Hug => self.layout.max_size.get_dim(x).resolve_pixels().unwrap_or(f32::INFINITY),
+            Size::Fixed(_) if current_pass => computed_size,
+            Size::Fixed(unit) => unit.resolve_pixels().unwrap_or(computed_size),
+        };
+        let padding = self.layout.padding.get_dim(x).resolve_pixels_or_default().total();
+        let space = self_size - padding;
+        let gap = opts.gap.get_dim(x).resolve_pixels_or_default();
+        let max_line_len = opts.columns_and_rows_count.get_dim(x).unwrap_or(usize::MAX);
+        let self_const_size = self.layout.size.get_dim(x).resolve_pixels_or_default();
+        let mut lines = Vec::new();
+        let mut line_len = 0;
+        let mut line_size = 0.0;
+        for child in children {
+            let child_size = if current_pass {
+                child.refresh_grid_child_static_size(x, self_const_size);
+                child.layout.computed_size.get_dim(x)
+            } else {
+                match child.layout.size.get_dim(x) {
+                    Size::Hug => child.layout.computed_size.get_dim(x),
+                    Size::Fixed(_) => {
+                        let size = child.resolve_size_static_values(x, self_const_size);
+                        child.clamp_to_size_limits(x, size)
+                    }
+                }
+            };
+            let child_margin = child.layout.margin.get_dim(x).resolve_pixels_or_default();
+            let child_size = child_size + child_margin.total();
+            let extended_line_size = line_size + gap + child_size;
+            let fits = extended_line_size <= space && line_len < max_line_len;
+            if line_len > 0 && !fits {
+                lines.push(line_len);
+                line_len = 0;
+            }
+            line_size = if line_len == 0 { child_size } else { extended_line_size };
+            line_len += 1;
+        }
+        if line_len > 0 {
+            lines.push(line_len);
+        }
+        lines
+    }
 
+    /// Compute the final sizes of the columns, resize the children to fit them if they are allowed
+    /// to, and place the children in the columns.
+    ///
+    /// # Meaning of the function parameters.
+    /// In order to make the code easy to understand, all variables in layout functions were named
+    /// as if the code was updating horizontal layout only. In reality, the variable [`x`] can be
+    /// set to either [`X`] or [`Y`] to update horizontal and vertical axis, respectively.
+    fn place_columns<Dim>(&self, x: Dim, opts: &AutoLayout, mut columns: Vec<ResolvedColumn>)
+    where Dim: ResolutionDim {
         // === Compute the static size (no grow, shrink, nor fraction yet) ===
 
         let gap_def = opts.gap.get_dim(x);
@@ -3949,9 +4336,11 @@ impl Model {
         total_fr += padding_def.start.as_fraction_or_default();
         total_fr += padding_def.end.as_fraction_or_default();
         total_fr += gap_def.as_fraction_or_default() * gap_count;
-        if self.layout.size.get_dim(x).is_hug() && space_left < 0.0 {
-            self.layout.computed_size.update_dim(x, |t| t - space_left);
-            space_left = 0.0;
+        if self.layout.size.get_dim(x).is_hug() {
+            let hugged_size = self_size - f32::min(0.0, space_left);
+            let clamped_size = self.clamp_to_size_limits(x, hugged_size);
+            self.layout.computed_size.set_dim(x, clamped_size);
+            space_left += clamped_size - self_size;
         }
 
 
@@ -3992,7 +4381,7 @@ impl Model {
             let column_size = column.computed_size + fr_diff;
             let column_size = f32::max(column.min_size, column_size);
             let column_size = f32::min(column.max_size, column_size);
-            for (child, previous_size) in column.children.iter().zip(&old_child_computed_sizes) {
+            for child in &column.children {
                 let child_base_size = child.layout.computed_size.get_dim(x);
                 let child_unused_space = f32::max(0.0, column_size - child_base_size);
                 let unresolved_margin = child.layout.margin.get_dim(x);
@@ -4044,9 +4433,6 @@ impl Model {
                 let child_offset = child_unused_space * alignment.normalized();
                 let child_left = pos_x + child_offset + margin.start;
                 child.set_position_dim(x, child_left);
-                if *previous_size != child_width {
-                    child.dirty.computed_size.set();
-                }
             }
             pos_x += column_size + gap;
         }
@@ -5316,6 +5702,28 @@ mod hierarchy_tests {
         ]);
         drop(network);
     }
+
+    #[test]
+    fn leak_detection() {
+        use frp::leak_detector;
+        leak_detector::enable();
+        let checkpoint = leak_detector::checkpoint();
+        let leaking = Instance::new_named("leaking");
+        let dropped = Instance::new_named("dropped");
+        leaking.network.store(&leaking);
+        let leaking_key = leaking.id().tracking_key();
+        let network_key = leaking.network.id().tracking_key();
+        drop(leaking);
+        drop(dropped);
+
+        let survivors = leak_detector::survivors(checkpoint);
+        let survivors = survivors.iter().map(|object| object.key).collect_vec();
+        assert_eq!(survivors, vec![network_key, leaking_key]);
+        let cycles = leak_detector::cycles();
+        let cycles = cycles.iter().map(|cycle| cycle.iter().map(|object| object.key).collect_vec());
+        assert_eq!(cycles.collect_vec(), vec![vec![network_key, leaking_key]]);
+        leak_detector::disable();
+    }
 }
 
 
@@ -6653,4 +7061,174 @@ mod layout_tests {
         root.update(&world.default_scene);
         assert_eq!(root.computed_size(), Vector2(10.0, 10.0));
     }
+
+    /// ```text
+    /// ╔ root ═════════════════╗
+    /// ║ ╭ node3 ──╮           ║
+    /// ║ │         │           ║
+    /// ║ ╰─────────╯           ║
+    /// ║ ╭ node1 ──╮ ╭ node2 ╮ ║
+    /// ║ │         │ │       │ ║
+    /// ║ ╰─────────╯ ╰───────╯ ║
+    /// ╚═══════════════════════╝
+    /// ```
+    #[test]
+    fn test_wrapped_row_flow() {
+        let test = TestFlatChildren3::new();
+        test.root.use_auto_layout().set_wrap(true).set_gap((1.0, 1.0)).set_size_x(8.0);
+        test.node1.set_size((4.0, 2.0));
+        test.node2.set_size((3.0, 2.0));
+        test.node3.set_size((4.0, 3.0));
+        test.run(|| {
+            test.assert_root_computed_size(8.0, 6.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(5.0, 0.0)
+                .assert_node3_position(0.0, 3.0)
+                .assert_node1_computed_size(4.0, 2.0)
+                .assert_node2_computed_size(3.0, 2.0)
+                .assert_node3_computed_size(4.0, 3.0);
+        });
+
+        test.root.set_size_x(13.0);
+        test.run(|| {
+            test.assert_root_computed_size(13.0, 3.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(5.0, 0.0)
+                .assert_node3_position(9.0, 0.0);
+        });
+    }
+
+    #[test]
+    fn test_wrapped_row_flow_hug_with_max_size() {
+        let test = TestFlatChildren3::new();
+        test.root.use_auto_layout().set_wrap(true).set_gap((1.0, 1.0)).set_max_size_x(8.0);
+        test.node1.set_size((4.0, 2.0));
+        test.node2.set_size((3.0, 2.0));
+        test.node3.set_size((4.0, 3.0));
+        test.run(|| {
+            test.assert_root_computed_size(8.0, 6.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(5.0, 0.0)
+                .assert_node3_position(0.0, 3.0);
+        });
+
+        test.root.unset_max_size_x();
+        test.run(|| {
+            test.assert_root_computed_size(13.0, 3.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(5.0, 0.0)
+                .assert_node3_position(9.0, 0.0);
+        });
+    }
+
+    /// ```text
+    /// ╔ root ═══════════════╗
+    /// ║ ╭ node2 ╮           ║
+    /// ║ │       │           ║
+    /// ║ ╰───────╯           ║
+    /// ║ ╭ node1 ╮ ╭ node3 ╮ ║
+    /// ║ │       │ │       │ ║
+    /// ║ ╰───────╯ ╰───────╯ ║
+    /// ╚═════════════════════╝
+    /// ```
+    #[test]
+    fn test_wrapped_column_flow() {
+        let test = TestFlatChildren3::new();
+        test.root.use_auto_layout().set_column_flow().set_wrap(true).set_size_y(5.0);
+        test.node1.set_size((2.0, 2.0));
+        test.node2.set_size((2.0, 2.0));
+        test.node3.set_size((2.0, 2.0));
+        test.run(|| {
+            test.assert_root_computed_size(4.0, 5.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(0.0, 2.0)
+                .assert_node3_position(2.0, 0.0);
+        });
+    }
+
+    #[test]
+    fn test_min_and_max_size_constraints() {
+        let test = TestFlatChildren3::new();
+        test.root.use_auto_layout();
+        test.node1.set_size((10.0, 2.0)).set_max_size_x(4.0);
+        test.node2.set_size((1.0, 1.0)).set_min_size((3.0, 3.0));
+        test.node3.set_max_size((5.0, 6.0));
+        let inner = test.node3.new_child_named("inner");
+        inner.set_size((8.0, 8.0));
+        test.run(|| {
+            test.assert_root_computed_size(12.0, 6.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(4.0, 0.0)
+                .assert_node3_position(7.0, 0.0)
+                .assert_node1_computed_size(4.0, 2.0)
+                .assert_node2_computed_size(3.0, 3.0)
+                .assert_node3_computed_size(5.0, 6.0);
+        });
+    }
+
+    /// ```text
+    /// ╔ root ═════════════════════╗
+    /// ║ ╭ node3 ────────────────╮ ║
+    /// ║ │             ╭ node2 ╮ │ ║
+    /// ║ │             ╰───────╯ │ ║
+    /// ║ │ ╭ node1 ╮             │ ║
+    /// ║ │ ╰───────╯             │ ║
+    /// ║ ╰───────────────────────╯ ║
+    /// ╚═══════════════════════════╝
+    /// ```
+    #[test]
+    fn test_anchoring_in_auto_layout() {
+        let test = TestFlatChildren3::new();
+        test.root.use_auto_layout().set_size((20.0, 10.0));
+        test.node1.set_size((4.0, 4.0));
+        test.node2.set_size((2.0, 2.0)).set_anchor_right(1.0).set_anchor_top(1.0);
+        test.node3.set_anchor_fill();
+        test.run(|| {
+            test.assert_root_computed_size(20.0, 10.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(17.0, 7.0)
+                .assert_node3_position(0.0, 0.0)
+                .assert_node1_computed_size(4.0, 4.0)
+                .assert_node2_computed_size(2.0, 2.0)
+                .assert_node3_computed_size(20.0, 10.0);
+        });
+
+        // Anchored children are not hugged.
+        test.root.set_size_hug();
+        test.run(|| {
+            test.assert_root_computed_size(4.0, 4.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(1.0, 1.0)
+                .assert_node3_computed_size(4.0, 4.0);
+        });
+    }
+
+    #[test]
+    fn test_anchoring_in_manual_layout() {
+        let test = TestFlatChildren3::new();
+        test.node1.set_size((6.0, 6.0));
+        test.node2.set_size((2.0, 2.0)).set_anchor_center();
+        test.node3.set_size((2.0, 2.0)).set_anchor_left(1.0);
+        test.node3.set_xy((0.0, 3.0));
+        test.run(|| {
+            test.assert_root_computed_size(6.0, 6.0)
+                .assert_node1_position(0.0, 0.0)
+                .assert_node2_position(2.0, 2.0)
+                .assert_node3_position(1.0, 3.0);
+        });
+
+        test.root.set_size((10.0, 10.0));
+        test.run(|| {
+            test.assert_root_computed_size(10.0, 10.0)
+                .assert_node2_position(4.0, 4.0)
+                .assert_node3_position(1.0, 3.0);
+        });
+
+        test.node2.unset_anchor();
+        test.node2.set_xy((7.0, 0.0));
+        test.root.set_size_hug();
+        test.run(|| {
+            test.assert_root_computed_size(9.0, 6.0).assert_node2_position(7.0, 0.0);
+        });
+    }
 }
@@ -254,3 +254,54 @@ macro_rules! with_display_object_side_spacing_matrix {
         $f! { $([$($args)*])? [[x left start] [x right end] [y bottom start] [y top end]] }
     }
 }
+
+
+
+// ==============
+// === Anchor ===
+// ==============
+
+/// Anchoring of a display object to the edges or the center of its parent. Anchored objects are
+/// not placed by the layout of their parent and are not taken into account when the parent hugs
+/// its children. Instead, they are positioned relative to the computed size of the parent. The
+/// offsets can be expressed in pixels or in percent of the parent size. Fractions resolve to zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Anchor {
+    /// Place the object at the given distance from the start edge (left or bottom) of the parent.
+    Start(Unit),
+    /// Center the object in the parent and move it by the given offset.
+    Center(Unit),
+    /// Place the object at the given distance from the end edge (right or top) of the parent.
+    End(Unit),
+    /// Stretch the object between the edges of the parent, leaving the given space at each side.
+    Stretch(SideSpacing),
+}
+
+impl Anchor {
+    /// The size of the object resulting from the anchoring, if the anchoring determines it.
+    pub fn resolve_size(self, parent_size: f32) -> Option<f32> {
+        match self {
+            Anchor::Stretch(space) => {
+                let start = resolve_anchor_offset(space.start, parent_size);
+                let end = resolve_anchor_offset(space.end, parent_size);
+                Some(f32::max(0.0, parent_size - start - end))
+            }
+            _ => None,
+        }
+    }
+
+    /// The position of the object of the given size within the parent.
+    pub fn resolve_position(self, parent_size: f32, size: f32) -> f32 {
+        match self {
+            Anchor::Start(offset) => resolve_anchor_offset(offset, parent_size),
+            Anchor::Center(offset) =>
+                (parent_size - size) / 2.0 + resolve_anchor_offset(offset, parent_size),
+            Anchor::End(offset) => parent_size - size - resolve_anchor_offset(offset, parent_size),
+            Anchor::Stretch(space) => resolve_anchor_offset(space.start, parent_size),
+        }
+    }
+}
+
+fn resolve_anchor_offset(offset: Unit, parent_size: f32) -> f32 {
+    offset.resolve_const_and_percent(parent_size).unwrap_or_default()
+}
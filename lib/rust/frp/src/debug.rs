@@ -183,7 +183,12 @@ pub trait GraphvizBuilder {
 
 pub fn display_graphviz(viz: Graphviz) {
     let code: String = viz.into();
-    let url = percent_encoding::utf8_percent_encode(&code, percent_encoding::NON_ALPHANUMERIC);
+    display_graphviz_code(&code);
+}
+
+/// Displays the Graphviz Dot code in a new tab in a web browser.
+pub fn display_graphviz_code(code: &str) {
+    let url = percent_encoding::utf8_percent_encode(code, percent_encoding::NON_ALPHANUMERIC);
     let url = format!("https://dreampuf.github.io/GraphvizOnline/#{url}");
     crate::web::window.open_with_url_and_target(&url, "_blank").unwrap();
 }
@@ -0,0 +1,467 @@
+//! Introspection of FRP networks. Allows listing nodes of a network, their kinds and output types,
+//! and the links between them, exporting this description to Graphviz DOT and JSON formats, and
+//! counting the events emitted by each node. It is meant to help debugging why an event did or
+//! did not propagate through a network.
+//!
+//! The description of a network is created with [`Network::introspect`]. The emission counting is
+//! disabled by default, as it slows down the event propagation. Enable it with
+//! [`start_counting_emissions`] before emitting the investigated events.
+//!
+//! [`Network::introspect`]: crate::Network::introspect
+
+use crate::prelude::*;
+
+use crate::network::Item;
+use crate::network::LinkType;
+use crate::node::HasId;
+use crate::node::HasLabel;
+use crate::node::Id;
+use crate::node::Label;
+use crate::stream::HasNodeKind;
+use crate::stream::HasOutputTypeLabel;
+use crate::stream::HasTargets;
+use crate::stream::InputBehaviors;
+
+
+
+// =========================
+// === Emission Counting ===
+// =========================
+
+thread_local! {
+    static COUNTING_EMISSIONS: Cell<bool> = default();
+    static EMISSION_COUNTS: RefCell<HashMap<Id, usize>> = default();
+}
+
+/// Start counting the events emitted by each node on the current thread.
+pub fn start_counting_emissions() {
+    COUNTING_EMISSIONS.with(|counting| counting.set(true));
+}
+
+/// Stop counting the emitted events. The counts gathered so far are kept.
+pub fn stop_counting_emissions() {
+    COUNTING_EMISSIONS.with(|counting| counting.set(false));
+}
+
+/// Check if the emitted events are being counted.
+pub fn is_counting_emissions() -> bool {
+    COUNTING_EMISSIONS.with(|counting| counting.get())
+}
+
+/// Forget all the gathered counts. As node ids are derived from their memory addresses, a dropped
+/// node's count may be attributed to a new node, unless the counts are reset in between.
+pub fn reset_emission_counts() {
+    EMISSION_COUNTS.with(|counts| counts.borrow_mut().clear());
+}
+
+/// The number of events emitted by the node while the counting was enabled.
+pub fn emission_count(id: Id) -> usize {
+    EMISSION_COUNTS.with(|counts| counts.borrow().get(&id).copied().unwrap_or_default())
+}
+
+pub(crate) fn record_emission(id: Id) {
+    EMISSION_COUNTS.with(|counts| *counts.borrow_mut().entry(id).or_default() += 1);
+}
+
+
+
+// ================
+// === NodeInfo ===
+// ================
+
+/// Description of a single FRP node.
+#[derive(Clone, Debug)]
+pub struct NodeInfo {
+    #[allow(missing_docs)]
+    pub id:             Id,
+    /// The label of the node, usually containing the module, name and line of its definition.
+    pub label:          Label,
+    /// The kind of the node, like `Map`, `Gate` or `Sampler`.
+    pub kind:           Label,
+    /// The full name of the node's output type.
+    pub output_type:    Label,
+    /// The number of emitted events. Present only if the emissions are being counted.
+    pub emission_count: Option<usize>,
+}
+
+impl NodeInfo {
+    /// Create the description of the network item.
+    pub fn new(item: &dyn Item) -> Self {
+        let id = item.id();
+        let label = item.label();
+        let kind = item.node_kind();
+        let output_type = item.output_type_label();
+        let emission_count = is_counting_emissions().as_some_from(|| emission_count(id));
+        Self { id, label, kind, output_type, emission_count }
+    }
+
+    /// The name of the node as defined in the `frp::extend!` macro, extracted from its label.
+    pub fn name(&self) -> &str {
+        let name = self.label.rsplit("::").next().unwrap_or(self.label);
+        name.split(':').next().unwrap_or(name)
+    }
+
+    /// The output type name without the module paths.
+    pub fn short_output_type(&self) -> String {
+        short_type_name(self.output_type)
+    }
+}
+
+
+
+// ================
+// === LinkInfo ===
+// ================
+
+/// Description of a link between two FRP nodes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct LinkInfo {
+    pub source: Id,
+    pub target: Id,
+    pub tp:     LinkType,
+}
+
+
+
+// ====================
+// === NetworkGraph ===
+// ====================
+
+/// Description of the structure of an FRP network, created by [`crate::Network::introspect`].
+///
+/// The event links are discovered from the registered event targets of each node, and the behavior
+/// links from the behaviors sampled by the nodes. A node both receiving events from another one and
+/// sampling it is linked with it by a [`LinkType::Mixed`] link. The links may connect nodes of
+/// different networks.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkGraph {
+    /// The label of the network.
+    pub label:   String,
+    /// The nodes, in the order of their registration.
+    pub nodes:   Vec<NodeInfo>,
+    #[allow(missing_docs)]
+    pub links:   Vec<LinkInfo>,
+    /// The descriptions of the bridge networks registered in this network.
+    pub bridges: Vec<NetworkGraph>,
+}
+
+impl NetworkGraph {
+    /// Constructor of an empty graph.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self { label: label.into(), ..default() }
+    }
+
+    /// Add the network item, and the links leading to and from it.
+    pub fn add_node(&mut self, item: &dyn Item) {
+        let id = item.id();
+        self.nodes.push(NodeInfo::new(item));
+        for target in item.target_ids() {
+            self.add_link(id, target, LinkType::Event);
+        }
+        for link in item.input_behaviors() {
+            self.add_link(link.source, id, link.tp);
+        }
+    }
+
+    /// Add the link. If the nodes are already linked, the link types are merged.
+    pub fn add_link(&mut self, source: Id, target: Id, tp: LinkType) {
+        let existing = self.links.iter_mut().find(|l| l.source == source && l.target == target);
+        match existing {
+            Some(link) if link.tp != tp => link.tp = LinkType::Mixed,
+            Some(_) => {}
+            None => self.links.push(LinkInfo { source, target, tp }),
+        }
+    }
+
+    /// Find the node with the given id, in this network or in its bridges.
+    pub fn node(&self, id: Id) -> Option<&NodeInfo> {
+        let local = self.nodes.iter().find(|node| node.id == id);
+        local.or_else(|| self.bridges.iter().find_map(|bridge| bridge.node(id)))
+    }
+
+    /// Find the node with the given name, in this network or in its bridges. See
+    /// [`NodeInfo::name`].
+    pub fn node_by_name(&self, name: &str) -> Option<&NodeInfo> {
+        let local = self.nodes.iter().find(|node| node.name() == name);
+        local.or_else(|| self.bridges.iter().find_map(|bridge| bridge.node_by_name(name)))
+    }
+
+    /// The links leading from the node.
+    pub fn links_from(&self, id: Id) -> impl Iterator<Item = &LinkInfo> {
+        self.all_links().into_iter().filter(move |link| link.source == id)
+    }
+
+    /// The links leading to the node.
+    pub fn links_to(&self, id: Id) -> impl Iterator<Item = &LinkInfo> {
+        self.all_links().into_iter().filter(move |link| link.target == id)
+    }
+
+    fn all_links(&self) -> Vec<&LinkInfo> {
+        let bridge_links = self.bridges.iter().flat_map(|bridge| bridge.all_links());
+        self.links.iter().chain(bridge_links).collect()
+    }
+}
+
+
+// === Graphviz ===
+
+impl NetworkGraph {
+    /// Export the graph to the Graphviz DOT format. Behavior links are dashed, and mixed links are
+    /// bold and dashed. The bridge networks are drawn as clusters, and the nodes of other networks
+    /// connected to this one are drawn as dotted boxes.
+    pub fn to_dot(&self) -> String {
+        let mut code = String::new();
+        code.push_str(&format!("digraph \"{}\" {{\n", escape_dot(&self.label)));
+        code.push_str("    rankdir=TD;\n");
+        code.push_str("    graph [fontname=\"Helvetica Neue\" fontsize=11];\n");
+        code.push_str(
+            "    node [fontname=\"Helvetica Neue\" fontsize=11 shape=box style=rounded];\n",
+        );
+        code.push_str("    edge [fontname=\"Helvetica Neue\" fontsize=11 arrowsize=.7];\n");
+        self.write_dot_body(&mut code, 1);
+        let links = self.all_links();
+        let endpoints = links.iter().flat_map(|link| [link.source, link.target]);
+        let external = endpoints.filter(|id| self.node(*id).is_none()).unique();
+        for id in external {
+            code.push_str(&format!(
+                "    n{} [label=\"external\" style=dotted];\n",
+                usize::from(id)
+            ));
+        }
+        for link in links {
+            let style = match link.tp {
+                LinkType::Event => "",
+                LinkType::Behavior => " [style=dashed]",
+                LinkType::Mixed => " [style=\"dashed,bold\"]",
+            };
+            let source = usize::from(link.source);
+            let target = usize::from(link.target);
+            code.push_str(&format!("    n{source} -> n{target}{style};\n"));
+        }
+        code.push('}');
+        code
+    }
+
+    fn write_dot_body(&self, code: &mut String, depth: usize) {
+        let indent = "    ".repeat(depth);
+        for node in &self.nodes {
+            let id = usize::from(node.id);
+            let name = escape_dot(node.name());
+            let details = escape_dot(&format!("{}: {}", node.kind, node.short_output_type()));
+            let count = node.emission_count.map(|count| format!(" [{count}]")).unwrap_or_default();
+            code.push_str(&format!("{indent}n{id} [label=\"{name}\\n{details}{count}\"];\n"));
+        }
+        for (index, bridge) in self.bridges.iter().enumerate() {
+            let label = escape_dot(&bridge.label);
+            code.push_str(&format!("{indent}subgraph cluster_{depth}_{index} {{\n"));
+            code.push_str(&format!("{indent}    label=\"{label}\";\n"));
+            bridge.write_dot_body(code, depth + 1);
+            code.push_str(&format!("{indent}}}\n"));
+        }
+    }
+}
+
+
+// === JSON ===
+
+impl NetworkGraph {
+    /// Export the graph to JSON. The nodes are described by their `id`, `label`, `name`, `kind`,
+    /// `outputType` and `emissionCount` (`null` if the emissions were not counted), and the links
+    /// by their `source`, `target` and `type` (`event`, `behavior` or `mixed`).
+    pub fn to_json(&self) -> String {
+        let nodes = self.nodes.iter().map(|node| {
+            let id = usize::from(node.id);
+            let label = escape_json(node.label);
+            let name = escape_json(node.name());
+            let kind = escape_json(node.kind);
+            let output_type = escape_json(node.output_type);
+            let count = node.emission_count.map_or_else(|| "null".into(), |c| c.to_string());
+            format!(
+                "{{\"id\":{id},\"label\":{label},\"name\":{name},\"kind\":{kind},\
+                 \"outputType\":{output_type},\"emissionCount\":{count}}}"
+            )
+        });
+        let links = self.links.iter().map(|link| {
+            let source = usize::from(link.source);
+            let target = usize::from(link.target);
+            let tp = match link.tp {
+                LinkType::Event => "event",
+                LinkType::Behavior => "behavior",
+                LinkType::Mixed => "mixed",
+            };
+            format!("{{\"source\":{source},\"target\":{target},\"type\":\"{tp}\"}}")
+        });
+        let bridges = self.bridges.iter().map(|bridge| bridge.to_json());
+        let label = escape_json(&self.label);
+        let nodes = nodes.collect_vec().join(",");
+        let links = links.collect_vec().join(",");
+        let bridges = bridges.collect_vec().join(",");
+        format!(
+            "{{\"label\":{label},\"nodes\":[{nodes}],\"links\":[{links}],\"bridges\":[{bridges}]}}"
+        )
+    }
+}
+
+
+
+// ===============
+// === Helpers ===
+// ===============
+
+/// Strip the module paths from all the type names in the given type, e.g.
+/// `core::option::Option<alloc::string::String>` becomes `Option<String>`.
+fn short_type_name(name: &str) -> String {
+    let is_delimiter = |c: char| !(c.is_alphanumeric() || c == '_' || c == ':');
+    let mut short = String::with_capacity(name.len());
+    for part in name.split_inclusive(is_delimiter) {
+        let (path, delimiter) = match part.char_indices().last() {
+            Some((index, c)) if is_delimiter(c) => part.split_at(index),
+            _ => (part, ""),
+        };
+        short.push_str(path.rsplit("::").next().unwrap_or(path));
+        short.push_str(delimiter);
+    }
+    short
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Quote the text as a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate as frp;
+    use crate::node::HasId;
+
+    #[test]
+    fn listing_nodes_and_links() {
+        frp::new_network! { network
+            source  <- source::<i32>();
+            enabled <- source::<bool>();
+            mapped  <- source.map(|value| value.to_string());
+            gated   <- mapped.gate(&enabled);
+        }
+        let graph = network.introspect();
+        let node = |name| graph.node_by_name(name).unwrap();
+        let kinds = graph.nodes.iter().map(|node| (node.name(), node.kind)).collect_vec();
+        let expected = [("source", "Source"), ("enabled", "Source"), ("mapped", "Map")];
+        assert_eq!(kinds[..3], expected);
+        assert_eq!(node("gated").kind, "Gate");
+        assert_eq!(node("source").short_output_type(), "i32");
+        assert_eq!(node("mapped").short_output_type(), "String");
+
+        let event = |from, to| LinkInfo {
+            source: node(from).id,
+            target: node(to).id,
+            tp:     LinkType::Event,
+        };
+        assert!(graph.links.contains(&event("source", "mapped")));
+        assert!(graph.links.contains(&event("mapped", "gated")));
+        let behavior = graph.links_to(node("gated").id).find(|l| l.source == node("enabled").id);
+        assert_eq!(behavior.map(|link| link.tp), Some(LinkType::Behavior));
+        assert_eq!(graph.links_from(gated.id()).count(), 0);
+    }
+
+    #[test]
+    fn counting_emissions() {
+        frp::new_network! { network
+            source <- source::<i32>();
+            even   <- source.filter(|value| value % 2 == 0);
+        }
+        assert_eq!(network.introspect().nodes[0].emission_count, None);
+        start_counting_emissions();
+        for value in 0..5 {
+            source.emit(value);
+        }
+        stop_counting_emissions();
+        source.emit(6);
+        assert_eq!(emission_count(source.id()), 5);
+        assert_eq!(emission_count(even.id()), 3);
+        start_counting_emissions();
+        let graph = network.introspect();
+        assert_eq!(graph.node_by_name("even").and_then(|node| node.emission_count), Some(3));
+        stop_counting_emissions();
+        reset_emission_counts();
+        assert_eq!(emission_count(source.id()), 0);
+    }
+
+    #[test]
+    fn bridge_networks() {
+        frp::new_network! { network1
+            source <- source::<()>();
+        }
+        frp::new_network! { network2
+            count <- source.count();
+        }
+        frp::new_bridge_network! { [network1, network2] bridge
+            tagged <- count.map(|count| *count > 1);
+        }
+        let graph = network1.introspect();
+        assert_eq!(graph.bridges.len(), 1);
+        assert_eq!(graph.bridges[0].label, "bridge");
+        assert_eq!(graph.node_by_name("tagged").map(|node| node.id), Some(tagged.id()));
+        let dot = graph.to_dot();
+        assert!(dot.contains("subgraph cluster_1_0"));
+        let (source, count) = (usize::from(source.id()), usize::from(count.id()));
+        assert!(dot.contains(&format!("n{count} [label=\"external\"")));
+        assert!(dot.contains(&format!("n{source} -> n{count};")));
+        drop(network2);
+        assert!(network1.introspect().bridges.is_empty());
+    }
+
+    #[test]
+    fn exporting_to_json() {
+        let mut graph = NetworkGraph::new("net\"work");
+        graph.nodes.push(NodeInfo {
+            id:             1.into(),
+            label:          "crate::module::node:12",
+            kind:           "Map",
+            output_type:    "core::option::Option<alloc::string::String>",
+            emission_count: None,
+        });
+        graph.add_link(1.into(), 2.into(), LinkType::Event);
+        graph.add_link(1.into(), 2.into(), LinkType::Behavior);
+        let expected = "{\"label\":\"net\\\"work\",\"nodes\":[{\"id\":1,\
+            \"label\":\"crate::module::node:12\",\"name\":\"node\",\"kind\":\"Map\",\
+            \"outputType\":\"core::option::Option<alloc::string::String>\",\
+            \"emissionCount\":null}],\"links\":[{\"source\":1,\"target\":2,\"type\":\"mixed\"}],\
+            \"bridges\":[]}";
+        assert_eq!(graph.to_json(), expected);
+        assert_eq!(graph.nodes[0].short_output_type(), "Option<String>");
+    }
+
+    #[test]
+    fn shortening_type_names() {
+        assert_eq!(short_type_name("i32"), "i32");
+        assert_eq!(short_type_name("(usize, alloc::vec::Vec<i32>)"), "(usize, Vec<i32>)");
+        assert_eq!(short_type_name("&[a::B; 2]"), "&[B; 2]");
+    }
+}
@@ -70,6 +70,22 @@ macro_rules! define_keys {
             $($arrow,)*
         }
 
+        impl Key {
+            /// The `key` value of a JavaScript keyboard event producing this key. Together with the
+            /// event `code`, it allows recreating the key with [`Key::new`].
+            pub fn key_value(&self) -> String {
+                match self {
+                    $(Self::$side(_) => stringify!($side).into(),)*
+                    $(Self::$regular => stringify!($regular).into(),)*
+                    $(
+                        Self::Arrow(ArrowDirection::$arrow) =>
+                            concat!("Arrow", stringify!($arrow)).into(),
+                    )*
+                    Self::Character(key) | Self::Other(key) => key.clone(),
+                }
+            }
+        }
+
         // === KEY_NAME_MAP ===
 
         lazy_static! {
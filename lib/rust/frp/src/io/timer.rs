@@ -1,11 +1,16 @@
-//! Browser timer handlers wrapped in FRP API.
+//! Browser timer handlers wrapped in FRP API, and the virtual clock driving them in tests.
 
 
 
 mod delayed_interval;
 mod interval;
 mod timeout;
+mod virtual_clock;
 
 pub use delayed_interval::*;
 pub use interval::*;
 pub use timeout::*;
+pub use virtual_clock::VirtualClock;
+pub use virtual_clock::DEFAULT_FRAME_DURATION;
+
+pub(crate) use timeout::RawTimeout;
@@ -4,6 +4,9 @@ use crate::prelude::*;
 
 use crate as frp;
 
+use crate::io::timer::virtual_clock::TimerHandle;
+use crate::io::timer::virtual_clock::VirtualTimer;
+
 use enso_web::window;
 use enso_web::Closure;
 
@@ -59,8 +62,8 @@ impl Timeout {
             on_expired <- any_mut();
         }
 
-        let closure: TimerClosure = Closure::new(f!(on_expired.emit(())));
-        let raw_timeout = Rc::new(RawTimeout::new(closure));
+        let callback: Rc<dyn Fn()> = Rc::new(f!(on_expired.emit(())));
+        let raw_timeout = Rc::new(RawTimeout::new(callback));
 
         frp::extend! { network
             restart <- any_mut::<i32>();
@@ -78,31 +81,46 @@ impl Timeout {
 // === RawTimeout ===
 // ==================
 
-#[derive(Debug)]
-struct RawTimeout {
+/// One-shot timer calling the provided callback, used by nodes measuring time themselves.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub(crate) struct RawTimeout {
+    #[derivative(Debug = "ignore")]
+    callback:     Rc<dyn Fn()>,
     closure:      TimerClosure,
-    timer_handle: RefCell<Option<i32>>,
+    timer_handle: RefCell<Option<TimerHandle>>,
 }
 
 impl RawTimeout {
-    fn new(closure: TimerClosure) -> Self {
-        Self { closure, timer_handle: default() }
+    /// Constructor. Timer is initially not started.
+    pub(crate) fn new(callback: Rc<dyn Fn()>) -> Self {
+        let closure_callback = callback.clone();
+        let closure: TimerClosure = Closure::new(move || closure_callback());
+        Self { callback, closure, timer_handle: default() }
     }
 
-    fn restart(&self, time: i32) {
+    /// Start the timer, cancelling the previous run if it was already started.
+    pub(crate) fn restart(&self, time: i32) {
+        if let Some(timer) = VirtualTimer::schedule(&self.callback, time, false) {
+            self.set_timer_handle(Some(TimerHandle::Virtual(timer)));
+            return;
+        }
         let js_func = self.closure.as_js_function();
         let result = window.set_timeout_with_callback_and_timeout_and_arguments_0(js_func, time);
         let handle = result.expect("setTimeout should never fail when callback is a function.");
-        self.set_timer_handle(Some(handle));
+        self.set_timer_handle(Some(TimerHandle::Browser(handle)));
     }
 
-    fn cancel(&self) {
+    /// Stop the timer if it is running.
+    pub(crate) fn cancel(&self) {
         self.set_timer_handle(None);
     }
 
-    fn set_timer_handle(&self, handle: Option<i32>) {
-        if let Some(old_handle) = self.timer_handle.replace(handle) {
-            window.clear_timeout_with_handle(old_handle);
+    fn set_timer_handle(&self, handle: Option<TimerHandle>) {
+        match self.timer_handle.replace(handle) {
+            Some(TimerHandle::Browser(old_handle)) => window.clear_timeout_with_handle(old_handle),
+            Some(TimerHandle::Virtual(old_timer)) => old_timer.cancel(),
+            None => {}
         }
     }
 }
@@ -0,0 +1,387 @@
+//! Virtual clock, allowing to test time-dependent FRP networks deterministically, without a
+//! browser.
+//!
+//! Once a [`VirtualClock`] is installed, all the timers ([`Timeout`], [`Interval`] and
+//! [`DelayedInterval`]) started on the current thread are scheduled in the virtual time instead of
+//! using the browser APIs. The time does not pass on its own: it is advanced by the test with
+//! [`VirtualClock::advance`] or [`VirtualClock::step_frame`]. Each expired timer is handled like a
+//! separate event loop task: the microtasks scheduled by its handler (see [`crate::microtasks`])
+//! are flushed before the next timer is handled, so nodes like `debounce` behave as in the
+//! browser.
+//!
+//! ```text
+//! advance(10ms):    ▶ flush ─ timer(3ms) ▶ flush ─ timer(7ms) ▶ flush ─ now = 10ms
+//! step_frame():     ▶ advance(frame duration) ─ on_frame ▶ flush
+//! ```
+//!
+//! [`Timeout`]: crate::io::timer::Timeout
+//! [`Interval`]: crate::io::timer::Interval
+//! [`DelayedInterval`]: crate::io::timer::DelayedInterval
+
+use crate::prelude::*;
+
+use crate as frp;
+
+use crate::microtasks::flush_microtasks;
+
+use std::time::Duration;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The default duration of a single frame, as in a 60 FPS animation loop.
+pub const DEFAULT_FRAME_DURATION: Duration = Duration::from_micros(16_667);
+
+/// The minimal period of the virtual interval timers. Prevents infinite loops when the timer is
+/// started with zero period.
+const MIN_INTERVAL_PERIOD: Duration = Duration::from_millis(1);
+
+
+
+// ==================
+// === ClockModel ===
+// ==================
+
+thread_local! {
+    static INSTALLED: RefCell<Weak<RefCell<ClockModel>>> = default();
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct ScheduledTimer {
+    id:       u64,
+    due:      Duration,
+    period:   Option<Duration>,
+    #[derivative(Debug = "ignore")]
+    callback: Rc<dyn Fn()>,
+}
+
+#[derive(Debug)]
+struct ClockModel {
+    now:            Duration,
+    next_id:        u64,
+    timers:         Vec<ScheduledTimer>,
+    frame_duration: Duration,
+}
+
+impl Default for ClockModel {
+    fn default() -> Self {
+        let now = default();
+        let next_id = default();
+        let timers = default();
+        let frame_duration = DEFAULT_FRAME_DURATION;
+        Self { now, next_id, timers, frame_duration }
+    }
+}
+
+impl ClockModel {
+    fn schedule(
+        &mut self,
+        callback: Rc<dyn Fn()>,
+        delay: Duration,
+        period: Option<Duration>,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let due = self.now + delay;
+        self.timers.push(ScheduledTimer { id, due, period, callback });
+        id
+    }
+
+    fn cancel(&mut self, id: u64) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+
+    /// Remove the earliest timer expiring not later than `limit`, moving the time to its expiry.
+    /// Periodic timers are scheduled again.
+    fn pop_expired(&mut self, limit: Duration) -> Option<Rc<dyn Fn()>> {
+        let earliest =
+            self.timers.iter().enumerate().min_by_key(|(_, timer)| (timer.due, timer.id));
+        let index = earliest.filter(|(_, timer)| timer.due <= limit).map(|(index, _)| index)?;
+        let timer = &mut self.timers[index];
+        self.now = timer.due;
+        let callback = timer.callback.clone();
+        match timer.period {
+            Some(period) => timer.due += period,
+            None => drop(self.timers.remove(index)),
+        }
+        Some(callback)
+    }
+}
+
+
+
+// ====================
+// === VirtualClock ===
+// ====================
+
+/// A clock driving the timers in virtual time. See the module documentation to learn more.
+///
+/// The clock is active as long as it, or any of its clones, is alive. The virtual timers started
+/// under the clock are never fired after it is dropped.
+#[derive(Clone, CloneRef, Debug)]
+pub struct VirtualClock {
+    model:        Rc<RefCell<ClockModel>>,
+    network:      frp::Network,
+    /// Emitted with the current time on each [`VirtualClock::step_frame`] call, like an animation
+    /// loop. Drive the tested animations with it.
+    pub on_frame: frp::Stream<Duration>,
+    frame:        frp::Any<Duration>,
+}
+
+impl VirtualClock {
+    /// Create a clock and install it for the current thread, replacing the previously installed
+    /// one. The virtual time starts at zero.
+    pub fn install() -> Self {
+        let model = Rc::new(RefCell::new(ClockModel::default()));
+        INSTALLED.with(|installed| *installed.borrow_mut() = Rc::downgrade(&model));
+        let network = frp::Network::new("VirtualClock");
+        frp::extend! { network
+            frame <- any_mut::<Duration>();
+        }
+        let on_frame = frame.clone_ref().into();
+        Self { model, network, on_frame, frame }
+    }
+
+    /// Check if this clock is the one installed for the current thread.
+    pub fn is_installed(&self) -> bool {
+        INSTALLED.with(|installed| installed.borrow().ptr_eq(&Rc::downgrade(&self.model)))
+    }
+
+    /// The current virtual time, measured from the clock installation.
+    pub fn now(&self) -> Duration {
+        self.model.borrow().now
+    }
+
+    /// The number of active timers.
+    pub fn pending_timers(&self) -> usize {
+        self.model.borrow().timers.len()
+    }
+
+    /// Set the duration of the frames stepped by [`VirtualClock::step_frame`].
+    pub fn set_frame_duration(&self, duration: Duration) {
+        self.model.borrow_mut().frame_duration = duration;
+    }
+
+    /// Flush the microtasks, finishing the current event loop task.
+    pub fn flush_microtasks(&self) {
+        flush_microtasks();
+    }
+
+    /// Advance the time by the given duration, firing all the timers expiring in the meantime, in
+    /// the order of their expiry. The microtasks are flushed before and after handling each timer.
+    pub fn advance(&self, duration: Duration) {
+        let limit = self.now() + duration;
+        flush_microtasks();
+        loop {
+            let expired = self.model.borrow_mut().pop_expired(limit);
+            let Some(callback) = expired else { break };
+            callback();
+            flush_microtasks();
+        }
+        self.model.borrow_mut().now = limit;
+    }
+
+    /// Advance the time by the given number of milliseconds. See [`VirtualClock::advance`].
+    pub fn advance_ms(&self, ms: u64) {
+        self.advance(Duration::from_millis(ms))
+    }
+
+    /// Advance the time to the expiry of the earliest active timer and fire it. Returns `false` if
+    /// there are no active timers.
+    pub fn advance_to_next_timer(&self) -> bool {
+        let next = self.model.borrow().timers.iter().map(|timer| timer.due).min();
+        next.map(|due| self.advance(due.saturating_sub(self.now()))).is_some()
+    }
+
+    /// Advance the time by a single frame, and emit the `on_frame` event once the expired timers
+    /// were handled.
+    pub fn step_frame(&self) {
+        let frame_duration = self.model.borrow().frame_duration;
+        self.advance(frame_duration);
+        self.frame.emit(self.now());
+        flush_microtasks();
+    }
+
+    /// Step the given number of frames. See [`VirtualClock::step_frame`].
+    pub fn step_frames(&self, count: usize) {
+        for _ in 0..count {
+            self.step_frame();
+        }
+    }
+}
+
+
+
+// ===================
+// === TimerHandle ===
+// ===================
+
+/// A handle of the running timer, used by the timer IO sources.
+#[derive(Debug)]
+pub(crate) enum TimerHandle {
+    /// Timer handle returned by the browser API.
+    Browser(i32),
+    /// Timer scheduled in the installed [`VirtualClock`].
+    Virtual(VirtualTimer),
+}
+
+/// A timer scheduled in a [`VirtualClock`].
+#[derive(Debug)]
+pub(crate) struct VirtualTimer {
+    model: Weak<RefCell<ClockModel>>,
+    id:    u64,
+}
+
+impl VirtualTimer {
+    /// Schedule the callback in the installed virtual clock. Returns `None` if there is no
+    /// installed clock, in which case the browser timers should be used.
+    pub(crate) fn schedule(callback: &Rc<dyn Fn()>, delay_ms: i32, repeat: bool) -> Option<Self> {
+        let model = INSTALLED.with(|installed| installed.borrow().upgrade())?;
+        let delay = Duration::from_millis(delay_ms.max(0) as u64);
+        let period = repeat.as_some_from(|| delay.max(MIN_INTERVAL_PERIOD));
+        let id = model.borrow_mut().schedule(callback.clone(), delay, period);
+        Some(Self { model: Rc::downgrade(&model), id })
+    }
+
+    /// Stop the timer. It will not fire anymore.
+    pub(crate) fn cancel(self) {
+        if let Some(model) = self.model.upgrade() {
+            model.borrow_mut().cancel(self.id);
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::timer::DelayedInterval;
+    use crate::io::timer::DelayedIntervalConfig;
+    use crate::io::timer::Interval;
+    use crate::io::timer::Timeout;
+
+    #[test]
+    fn timeout() {
+        let clock = VirtualClock::install();
+        let network = frp::Network::new("test");
+        let timeout = Timeout::new(&network);
+        frp::extend! { network
+            count <- timeout.on_expired.count();
+            count <- count.sampler();
+        }
+        timeout.restart.emit(10);
+        clock.advance_ms(9);
+        assert_eq!(count.value(), 0);
+        clock.advance_ms(1);
+        assert_eq!(count.value(), 1);
+        clock.advance_ms(100);
+        assert_eq!(count.value(), 1);
+
+        timeout.restart.emit(10);
+        clock.advance_ms(5);
+        timeout.restart.emit(10);
+        clock.advance_ms(5);
+        assert_eq!(count.value(), 1);
+        timeout.cancel.emit(());
+        clock.advance_ms(100);
+        assert_eq!(count.value(), 1);
+        assert_eq!(clock.pending_timers(), 0);
+    }
+
+    #[test]
+    fn interval() {
+        let clock = VirtualClock::install();
+        let network = frp::Network::new("test");
+        let interval = Interval::new(&network);
+        frp::extend! { network
+            count <- interval.on_interval.count();
+            count <- count.sampler();
+        }
+        interval.restart.emit(10);
+        clock.advance_ms(35);
+        assert_eq!(count.value(), 3);
+        interval.restart.emit(2);
+        clock.advance_ms(10);
+        assert_eq!(count.value(), 8);
+        interval.stop.emit(());
+        clock.advance_ms(100);
+        assert_eq!(count.value(), 8);
+    }
+
+    #[test]
+    fn delayed_interval() {
+        let clock = VirtualClock::install();
+        let network = frp::Network::new("test");
+        let timer = DelayedInterval::new(&network);
+        let times = Rc::new(RefCell::new(vec![]));
+        frp::extend! { network
+            eval_ timer.on_trigger ([times, clock] times.borrow_mut().push(clock.now()));
+        }
+        timer.restart.emit(DelayedIntervalConfig::new(5, 2));
+        clock.advance_ms(10);
+        timer.stop.emit(());
+        clock.advance_ms(10);
+        let ms = Duration::from_millis;
+        assert_eq!(*times.borrow(), vec![ms(5), ms(7), ms(9)]);
+    }
+
+    #[test]
+    fn debounce_between_timers() {
+        let clock = VirtualClock::install();
+        let network = frp::Network::new("test");
+        let interval = Interval::new(&network);
+        let values = Rc::new(RefCell::new(vec![]));
+        frp::extend! { network
+            count     <- interval.on_interval.count();
+            doubled   <- count.map(|count| count * 2);
+            combined  <- all(count, doubled);
+            debounced <- combined.debounce();
+            eval debounced ((value) values.borrow_mut().push(*value));
+        }
+        interval.restart.emit(10);
+        clock.advance_ms(20);
+        assert_eq!(*values.borrow(), vec![(1, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn frames() {
+        let clock = VirtualClock::install();
+        clock.set_frame_duration(Duration::from_millis(10));
+        let network = frp::Network::new("test");
+        let timeout = Timeout::new(&network);
+        let events = Rc::new(RefCell::new(vec![]));
+        frp::extend! { network
+            eval_ timeout.on_expired (events.borrow_mut().push("timeout"));
+            eval_ clock.on_frame (events.borrow_mut().push("frame"));
+        }
+        timeout.restart.emit(15);
+        clock.step_frames(2);
+        assert_eq!(*events.borrow(), vec!["frame", "timeout", "frame"]);
+        assert_eq!(clock.now(), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn dropping_clock() {
+        let clock = VirtualClock::install();
+        assert!(clock.is_installed());
+        let callback: Rc<dyn Fn()> = Rc::new(|| {});
+        assert!(VirtualTimer::schedule(&callback, 10, false).is_some());
+        drop(clock);
+        assert!(VirtualTimer::schedule(&callback, 10, false).is_none());
+        let clock = VirtualClock::install();
+        assert!(!clock.advance_to_next_timer());
+        VirtualTimer::schedule(&callback, 10, false);
+        assert!(clock.advance_to_next_timer());
+        assert_eq!(clock.now(), Duration::from_millis(10));
+    }
+}
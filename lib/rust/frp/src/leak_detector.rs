@@ -0,0 +1,412 @@
+//! Debug-mode tracker of long-living objects, like FRP networks and display objects. It helps
+//! finding objects which are never dropped, usually because a closure or a stored item holds a
+//! strong reference to an object owning it.
+//!
+//! The tracker is disabled by default. When enabled with [`enable`], every tracked object is
+//! registered on creation together with the place in code that created it, and unregistered when
+//! it is dropped. The registered objects can then be queried in two ways:
+//! - [`survivors`] lists the objects created after a [`checkpoint`] which are still alive. Take a
+//!   checkpoint, create and drop a component, and every survivor is a leak.
+//! - [`cycles`] lists groups of objects referencing each other. The references are known only for
+//!   items passed to [`Network::store`], bridge networks, and objects registering their owned
+//!   objects with [`add_reference`]. References captured by closures are not visible.
+//!
+//! The tracker works on the current thread only, so it can be used in native tests. In release
+//! builds, the tracking is compiled out.
+//!
+//! [`Network::store`]: crate::Network::store
+
+use crate::prelude::*;
+
+use std::panic::Location;
+
+
+
+// =================
+// === ObjectKey ===
+// =================
+
+/// Identifier of a tracked object. The `kind` distinguishes objects of different types, whose
+/// `id`s may overlap.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[allow(missing_docs)]
+pub struct ObjectKey {
+    pub kind: &'static str,
+    pub id:   usize,
+}
+
+impl ObjectKey {
+    /// Constructor.
+    pub fn new(kind: &'static str, id: usize) -> Self {
+        Self { kind, id }
+    }
+}
+
+
+
+// ==================
+// === TrackedRef ===
+// ==================
+
+/// Values which are references to tracked objects. When such a value is stored in a tracked
+/// object, for example with [`crate::Network::store`], a reference between the objects is
+/// registered. Types which are tracked should specialize this trait.
+pub trait TrackedRef {
+    /// The key of the referenced object, if the value references a tracked object.
+    fn tracked_key(&self) -> Option<ObjectKey>;
+}
+
+impl<T> TrackedRef for T {
+    default fn tracked_key(&self) -> Option<ObjectKey> {
+        None
+    }
+}
+
+
+
+// =====================
+// === TrackedObject ===
+// =====================
+
+/// Description of a tracked object.
+#[derive(Clone, Debug)]
+pub struct TrackedObject {
+    /// The key of the object.
+    pub key:      ObjectKey,
+    /// Human readable label of the object, like the network label.
+    pub label:    String,
+    /// The place in code which created the object.
+    pub location: &'static Location<'static>,
+    sequence:     u64,
+}
+
+impl Display for TrackedObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = &self.key;
+        write!(f, "{} '{}' ({:#x}) created at {}", key.kind, self.label, key.id, self.location)
+    }
+}
+
+
+
+// ================
+// === Registry ===
+// ================
+
+#[derive(Debug)]
+struct Entry {
+    object:     TrackedObject,
+    references: Vec<ObjectKey>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    enabled:       bool,
+    next_sequence: u64,
+    objects:       HashMap<ObjectKey, Entry>,
+}
+
+fn sorted_by_creation(mut objects: Vec<TrackedObject>) -> Vec<TrackedObject> {
+    objects.sort_by_key(|object| object.sequence);
+    objects
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = default();
+}
+
+fn with_registry<T>(f: impl FnOnce(&mut Registry) -> T) -> T {
+    REGISTRY.with(|registry| f(&mut registry.borrow_mut()))
+}
+
+/// Start tracking the objects created on the current thread. Has no effect in release builds.
+pub fn enable() {
+    with_registry(|registry| registry.enabled = cfg!(debug_assertions));
+}
+
+/// Stop tracking new objects and forget all the tracked ones.
+pub fn disable() {
+    with_registry(|registry| {
+        registry.enabled = false;
+        registry.objects.clear();
+    });
+}
+
+/// Check if the newly created objects are tracked.
+pub fn is_enabled() -> bool {
+    cfg!(debug_assertions) && with_registry(|registry| registry.enabled)
+}
+
+/// Register a newly created object. Does nothing if the tracker is disabled.
+pub fn track(key: ObjectKey, label: impl Into<String>, location: &'static Location<'static>) {
+    if is_enabled() {
+        let label = label.into();
+        with_registry(|registry| {
+            let sequence = registry.next_sequence;
+            registry.next_sequence += 1;
+            let object = TrackedObject { key, label, location, sequence };
+            registry.objects.insert(key, Entry { object, references: default() });
+        })
+    }
+}
+
+/// Unregister a dropped object.
+pub fn untrack(key: ObjectKey) {
+    if cfg!(debug_assertions) {
+        // The registry may be already destroyed if the object is dropped during the thread exit.
+        let _ = REGISTRY.try_with(|registry| registry.borrow_mut().objects.remove(&key));
+    }
+}
+
+/// Register a strong reference from the `owner` object to the `owned` one. Does nothing if the
+/// owner is not tracked.
+pub fn add_reference(owner: ObjectKey, owned: ObjectKey) {
+    if is_enabled() {
+        with_registry(|registry| {
+            if let Some(entry) = registry.objects.get_mut(&owner) {
+                entry.references.push(owned);
+            }
+        })
+    }
+}
+
+/// All the tracked objects which are still alive, in the order of their creation.
+pub fn alive() -> Vec<TrackedObject> {
+    with_registry(|registry| {
+        let objects = registry.objects.values().map(|entry| entry.object.clone()).collect();
+        sorted_by_creation(objects)
+    })
+}
+
+
+
+// ==================
+// === Checkpoint ===
+// ==================
+
+/// A point in time separating the objects created before it from the ones created after it. See
+/// [`survivors`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    sequence: u64,
+}
+
+/// Mark the current point in time.
+pub fn checkpoint() -> Checkpoint {
+    with_registry(|registry| Checkpoint { sequence: registry.next_sequence })
+}
+
+/// The tracked objects created after the checkpoint which are still alive, in the order of their
+/// creation.
+pub fn survivors(checkpoint: Checkpoint) -> Vec<TrackedObject> {
+    with_registry(|registry| {
+        let entries = registry.objects.values();
+        let after_checkpoint = entries.filter(|entry| entry.object.sequence >= checkpoint.sequence);
+        let objects = after_checkpoint.map(|entry| entry.object.clone()).collect();
+        sorted_by_creation(objects)
+    })
+}
+
+
+
+// ==============
+// === Cycles ===
+// ==============
+
+/// Groups of alive objects referencing each other, directly or through other objects. Objects in
+/// such groups are never dropped, unless the cycle is broken manually. Each group lists the
+/// objects in the order of their creation.
+pub fn cycles() -> Vec<Vec<TrackedObject>> {
+    with_registry(|registry| {
+        let mut search = CycleSearch::new(registry);
+        let objects = registry.objects.values().map(|entry| entry.object.clone()).collect();
+        for object in &sorted_by_creation(objects) {
+            if !search.indices.contains_key(&object.key) {
+                search.visit(object.key);
+            }
+        }
+        search.cycles.into_iter().map(sorted_by_creation).collect()
+    })
+}
+
+/// Tarjan's search of strongly connected components of the reference graph.
+struct CycleSearch<'t> {
+    registry:   &'t Registry,
+    next_index: usize,
+    indices:    HashMap<ObjectKey, usize>,
+    low_links:  HashMap<ObjectKey, usize>,
+    stack:      Vec<ObjectKey>,
+    on_stack:   HashSet<ObjectKey>,
+    cycles:     Vec<Vec<TrackedObject>>,
+}
+
+impl<'t> CycleSearch<'t> {
+    fn new(registry: &'t Registry) -> Self {
+        let next_index = default();
+        let indices = default();
+        let low_links = default();
+        let stack = default();
+        let on_stack = default();
+        let cycles = default();
+        Self { registry, next_index, indices, low_links, stack, on_stack, cycles }
+    }
+
+    fn references(&self, key: ObjectKey) -> impl Iterator<Item = ObjectKey> + 't {
+        let registry = self.registry;
+        let references = registry.objects.get(&key).map(|entry| entry.references.iter());
+        let references = references.into_iter().flatten().copied();
+        references.filter(move |owned| registry.objects.contains_key(owned))
+    }
+
+    fn visit(&mut self, key: ObjectKey) {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.indices.insert(key, index);
+        self.low_links.insert(key, index);
+        self.stack.push(key);
+        self.on_stack.insert(key);
+        for owned in self.references(key) {
+            if !self.indices.contains_key(&owned) {
+                self.visit(owned);
+                let low_link = self.low_links[&key].min(self.low_links[&owned]);
+                self.low_links.insert(key, low_link);
+            } else if self.on_stack.contains(&owned) {
+                let low_link = self.low_links[&key].min(self.indices[&owned]);
+                self.low_links.insert(key, low_link);
+            }
+        }
+        if self.low_links[&key] == index {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(&member);
+                component.push(member);
+                if member == key {
+                    break;
+                }
+            }
+            let is_self_referencing = || self.references(key).any(|owned| owned == key);
+            if component.len() > 1 || is_self_referencing() {
+                let objects = &self.registry.objects;
+                let cycle = component.iter().map(|member| objects[member].object.clone());
+                self.cycles.push(cycle.collect());
+            }
+        }
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// Summary of possible leaks: the objects surviving a checkpoint and the reference cycles.
+#[derive(Clone, Debug)]
+pub struct LeakReport {
+    /// See [`survivors`].
+    pub survivors: Vec<TrackedObject>,
+    /// See [`cycles`].
+    pub cycles:    Vec<Vec<TrackedObject>>,
+}
+
+impl LeakReport {
+    /// Check if no possible leaks were found.
+    pub fn is_empty(&self) -> bool {
+        self.survivors.is_empty() && self.cycles.is_empty()
+    }
+}
+
+impl Display for LeakReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Objects surviving the checkpoint: {}", self.survivors.len())?;
+        for object in &self.survivors {
+            writeln!(f, "  {object}")?;
+        }
+        writeln!(f, "Reference cycles: {}", self.cycles.len())?;
+        for (index, cycle) in self.cycles.iter().enumerate() {
+            writeln!(f, "  Cycle {index}:")?;
+            for object in cycle {
+                writeln!(f, "    {object}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Gather the objects surviving the checkpoint and the reference cycles.
+pub fn report(checkpoint: Checkpoint) -> LeakReport {
+    LeakReport { survivors: survivors(checkpoint), cycles: cycles() }
+}
+
+/// Print the [`report`] to the console, if any possible leaks were found.
+pub fn log_report(checkpoint: Checkpoint) {
+    let report = report(checkpoint);
+    if !report.is_empty() {
+        warn!("Possible memory leaks found.\n{report}");
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Network;
+
+    fn labels(objects: &[TrackedObject]) -> Vec<&str> {
+        objects.iter().map(|object| object.label.as_str()).collect()
+    }
+
+    #[test]
+    fn survivors_of_checkpoint() {
+        enable();
+        let before = Network::new("before");
+        let checkpoint = checkpoint();
+        let dropped = Network::new("dropped");
+        let survivor = Network::new("survivor");
+        drop(dropped);
+        assert_eq!(labels(&survivors(checkpoint)), vec!["survivor"]);
+        assert_eq!(labels(&alive()), vec!["before", "survivor"]);
+        assert!(survivors(checkpoint)[0].location.file().ends_with("leak_detector.rs"));
+        drop(survivor);
+        assert!(survivors(checkpoint).is_empty());
+        drop(before);
+        disable();
+    }
+
+    #[test]
+    fn cycles_through_stored_items() {
+        enable();
+        let checkpoint = checkpoint();
+        let network1 = Network::new("network1");
+        let network2 = Network::new("network2");
+        let network3 = Network::new("network3");
+        let lonely = Network::new("lonely");
+        network1.store(&network2);
+        network2.store(&network1);
+        network2.store(&network3);
+        lonely.store(&lonely);
+        drop((network1, network2, network3, lonely));
+
+        let cycles = cycles();
+        let cycles = cycles.iter().map(|cycle| labels(cycle)).collect_vec();
+        assert_eq!(cycles, vec![vec!["network1", "network2"], vec!["lonely"]]);
+        let report = report(checkpoint);
+        assert_eq!(labels(&report.survivors), vec!["network1", "network2", "network3", "lonely"]);
+        assert!(!report.is_empty());
+        disable();
+    }
+
+    #[test]
+    fn disabled_tracker() {
+        let network = Network::new("network");
+        network.store(&network);
+        assert!(alive().is_empty());
+        assert!(cycles().is_empty());
+    }
+}
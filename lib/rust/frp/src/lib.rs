@@ -171,7 +171,9 @@ pub mod data;
 pub mod debug;
 pub mod fan;
 pub mod future;
+pub mod introspection;
 pub mod io;
+pub mod leak_detector;
 pub mod macros;
 pub mod microtasks;
 pub mod network;
@@ -354,4 +356,109 @@ mod dynamic_mode_tests {
         let true_count = input.iter().filter(|&&val| val).count();
         assert_eq!(passed_events.get(), true_count);
     }
+
+    #[test]
+    fn test_throttle() {
+        let clock = frp::io::timer::VirtualClock::install();
+        let edges =
+            [frp::ThrottleEdge::Leading, frp::ThrottleEdge::Trailing, frp::ThrottleEdge::Both];
+        let expected = [vec![1, 3, 4], vec![2, 3, 4], vec![1, 2, 3, 4]];
+        for (edge, expected) in edges.into_iter().zip(expected) {
+            let values: Rc<RefCell<Vec<i32>>> = default();
+            frp::new_network! { network
+                source    <- source::<i32>();
+                throttled <- source.throttle(30, edge);
+                eval throttled ([values](value) values.borrow_mut().push(*value));
+            }
+            source.emit(1);
+            clock.advance_ms(10);
+            source.emit(2);
+            clock.advance_ms(30);
+            source.emit(3);
+            clock.advance_ms(40);
+            source.emit(4);
+            clock.advance_ms(100);
+            assert_eq!(*values.borrow(), expected);
+            assert_eq!(clock.pending_timers(), 0);
+        }
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let values: Rc<RefCell<Vec<Vec<i32>>>> = default();
+        frp::new_network! { network
+            source <- source::<i32>();
+            window <- source.sliding_window(3);
+            eval window ([values](window) values.borrow_mut().push(window.clone()));
+        }
+        for value in 1..=4 {
+            source.emit(value);
+        }
+        assert_eq!(*values.borrow(), vec![vec![1], vec![1, 2], vec![1, 2, 3], vec![2, 3, 4]]);
+    }
+
+    #[test]
+    fn test_buffer_count() {
+        let values: Rc<RefCell<Vec<Vec<i32>>>> = default();
+        frp::new_network! { network
+            source <- source::<i32>();
+            batch  <- source.buffer_count(3);
+            eval batch ([values](batch) values.borrow_mut().push(batch.clone()));
+        }
+        for value in 1..=7 {
+            source.emit(value);
+        }
+        assert_eq!(*values.borrow(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+
+    #[test]
+    fn test_distinct_until_changed_by() {
+        let values: Rc<RefCell<Vec<(i32, char)>>> = default();
+        frp::new_network! { network
+            source   <- source::<(i32, char)>();
+            distinct <- source.distinct_until_changed_by(|value| value.0);
+            eval distinct ([values](value) values.borrow_mut().push(*value));
+        }
+        for value in [(1, 'a'), (1, 'b'), (2, 'b'), (2, 'c'), (1, 'c')] {
+            source.emit(value);
+        }
+        assert_eq!(*values.borrow(), vec![(1, 'a'), (2, 'b'), (1, 'c')]);
+    }
+
+    #[test]
+    fn test_zip() {
+        let values: Rc<RefCell<Vec<(i32, char)>>> = default();
+        frp::new_network! { network
+            numbers <- source::<i32>();
+            letters <- source::<char>();
+            zipped  <- numbers.zip(&letters);
+            eval zipped ([values](value) values.borrow_mut().push(*value));
+        }
+        numbers.emit(1);
+        numbers.emit(2);
+        assert!(values.borrow().is_empty());
+        letters.emit('a');
+        letters.emit('b');
+        letters.emit('c');
+        assert_eq!(*values.borrow(), vec![(1, 'a'), (2, 'b')]);
+        numbers.emit(3);
+        assert_eq!(*values.borrow(), vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+    }
+
+    #[test]
+    fn test_scan() {
+        let values: Rc<RefCell<Vec<i32>>> = default();
+        frp::new_network! { network
+            source <- source::<i32>();
+            reset  <- source::<()>();
+            sum    <- source.scan(&reset, 0, |sum: &i32, value| sum + value);
+            eval sum ([values](sum) values.borrow_mut().push(*sum));
+        }
+        source.emit(1);
+        source.emit(2);
+        source.emit(3);
+        reset.emit(());
+        source.emit(4);
+        assert_eq!(*values.borrow(), vec![1, 3, 6, 0, 4]);
+    }
 }
@@ -4,9 +4,15 @@ use crate::node::*;
 use crate::prelude::*;
 
 use crate::debug;
+use crate::introspection::NetworkGraph;
+use crate::leak_detector;
+use crate::leak_detector::ObjectKey;
+use crate::leak_detector::TrackedRef;
 use crate::stream;
 use crate::stream::Stream;
 
+use std::panic::Location;
+
 
 
 // ==========
@@ -17,6 +23,13 @@ use crate::stream::Stream;
 #[derive(Clone, CloneRef, Copy, Debug, Default, Display, Eq, From, Hash, Into, PartialEq)]
 pub struct NetworkId(usize);
 
+impl NetworkId {
+    /// The key of the network in the [`leak_detector`].
+    pub fn tracking_key(self) -> ObjectKey {
+        ObjectKey::new("frp::Network", self.0)
+    }
+}
+
 
 
 // ===============
@@ -40,8 +53,8 @@ pub struct WeakNetwork {
 }
 
 /// Network item.
-pub trait Item: HasId + HasLabel + stream::HasOutputTypeLabel {}
-impl<T> Item for T where T: HasId + HasLabel + stream::HasOutputTypeLabel {}
+pub trait Item: HasId + HasLabel + stream::Introspectable {}
+impl<T> Item for T where T: HasId + HasLabel + stream::Introspectable {}
 
 /// Internal data of `Network`.
 #[derive(Derivative)]
@@ -73,20 +86,29 @@ impl NetworkData {
 
 impl Drop for NetworkData {
     fn drop(&mut self) {
+        let ptr: *const Self = self;
+        leak_detector::untrack(NetworkId(ptr as *const () as usize).tracking_key());
         self.bridges.borrow().iter().for_each(|subnetwork| subnetwork.destroy())
     }
 }
 
 impl Network {
     /// Constructor.
+    #[track_caller]
     pub fn new(label: impl Into<String>) -> Self {
         Self::new_with_string(label.into())
     }
 
     /// Non-generic constructor.
+    #[track_caller]
     fn new_with_string(label: String) -> Self {
-        let data = Rc::new(NetworkData::new(label));
-        Self { data }
+        if leak_detector::is_enabled() {
+            let network = Self { data: Rc::new(NetworkData::new(label.clone())) };
+            leak_detector::track(network.id().tracking_key(), label, Location::caller());
+            network
+        } else {
+            Self { data: Rc::new(NetworkData::new(label)) }
+        }
     }
 
     /// Get the weak version.
@@ -107,6 +129,9 @@ impl Network {
     /// Store arbitrary item in this network. Used as a convenient storage of data associated with
     /// network, like animation instances.
     pub fn store<T: 'static + CloneRef>(&self, item: &T) {
+        if let Some(key) = item.tracked_key() {
+            leak_detector::add_reference(self.id().tracking_key(), key);
+        }
         let item = item.clone_ref();
         self.store_boxed(Box::new(item));
     }
@@ -150,16 +175,38 @@ impl Network {
 
     /// Registers the provided bridge network as child of this network.
     pub fn register_bridge_network(&self, sub_network: &BridgeNetwork) {
+        if let Some(key) = sub_network.tracked_key() {
+            leak_detector::add_reference(self.id().tracking_key(), key);
+        }
         self.data.bridges.borrow_mut().push(sub_network.clone_ref())
     }
 
     /// Draw the network using GraphViz.
     pub fn draw(&self) {
-        let mut viz = debug::Graphviz::default();
-        self.data.nodes.borrow().iter().for_each(|node| {
-            viz.add_node(node.id().into(), node.output_type_label(), node.label());
-        });
-        debug::display_graphviz(viz);
+        debug::display_graphviz_code(&self.introspect().to_dot());
+    }
+
+    /// Describe the current structure of the network: its nodes, links between them, and its
+    /// bridge networks. See the [`crate::introspection`] module to learn more.
+    pub fn introspect(&self) -> NetworkGraph {
+        let nodes = self.data.nodes.borrow();
+        let links = self.data.links.borrow();
+        let bridges = self.data.bridges.borrow();
+        let mut graph = NetworkGraph::new(self.data.label.clone());
+        for node in nodes.iter() {
+            graph.add_node(&**node);
+        }
+        for (target, link) in links.iter() {
+            graph.add_link(link.source, *target, link.tp);
+        }
+        graph.bridges = bridges.iter().filter_map(|bridge| bridge.introspect()).collect();
+        graph
+    }
+}
+
+impl TrackedRef for Network {
+    fn tracked_key(&self) -> Option<ObjectKey> {
+        Some(self.id().tracking_key())
     }
 }
 
@@ -217,6 +264,12 @@ impl BridgeNetwork {
         Self { data }
     }
 
+    /// Describe the current structure of the network. Returns `None` if the bridge was already
+    /// destroyed. See [`Network::introspect`].
+    pub fn introspect(&self) -> Option<NetworkGraph> {
+        self.data.borrow().as_ref().map(|network| network.introspect())
+    }
+
     fn destroy(&self) {
         self.data.take();
         // Beware: doing it in another, also intuitive way
@@ -225,6 +278,12 @@ impl BridgeNetwork {
     }
 }
 
+impl TrackedRef for BridgeNetwork {
+    fn tracked_key(&self) -> Option<ObjectKey> {
+        self.data.borrow().as_ref().and_then(|network| network.tracked_key())
+    }
+}
+
 impl From<Network> for BridgeNetwork {
     fn from(net: Network) -> Self {
         let data = Rc::new(RefCell::new(Some(net)));
@@ -270,7 +329,7 @@ impl Link {
 }
 
 /// Type of the link between FRP nodes.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[allow(missing_docs)]
 pub enum LinkType {
     Event,
@@ -14,6 +14,7 @@ use crate::prelude::*;
 use enso_generics::traits::*;
 
 use crate::data::watch;
+use crate::io::timer::RawTimeout;
 use crate::microtasks::next_microtask;
 use crate::stream;
 use crate::stream::CallStack;
@@ -23,6 +24,7 @@ use crate::stream::Stream;
 use crate::stream::ValueProvider;
 
 use enso_generics as generics;
+use std::collections::VecDeque;
 
 
 
@@ -308,6 +310,127 @@ impl Network {
         self.register(OwnedFold::new(label, event))
     }
 
+    /// Limit the rate of events to at most one per `period_ms` milliseconds. The first event
+    /// received while the node is idle opens a window of `period_ms`. The `edge` argument decides
+    /// which events are passed: the one that opened the window ([`ThrottleEdge::Leading`]), the
+    /// last one received within the window, emitted when the window closes
+    /// ([`ThrottleEdge::Trailing`]), or both of them ([`ThrottleEdge::Both`]). A trailing emission
+    /// opens a new window, so the output rate never exceeds the limit. With [`ThrottleEdge::Both`]
+    /// an event is never emitted twice.
+    ///
+    /// ```text
+    /// Time [10ms]:     0─1─2─3─4─5─6─7─8─9─
+    /// Input:           1─2─────3───────4───
+    /// Leading  (30ms): 1───────3───────4───
+    /// Trailing (30ms): ──────2─────3─────4─
+    /// Both     (30ms): 1─────2─────3─────4─
+    /// ```
+    ///
+    /// The windows are measured with browser timers, or with the [`crate::io::timer::VirtualClock`]
+    /// if one is installed.
+    pub fn throttle<T>(
+        &self,
+        label: Label,
+        src: &T,
+        period_ms: i32,
+        edge: ThrottleEdge,
+    ) -> Stream<Output<T>>
+    where
+        T: EventOutput,
+    {
+        self.register(OwnedThrottle::new(label, src, period_ms, edge))
+    }
+
+    /// Emit the last `size` incoming events on every event. Until `size` events are received, the
+    /// emitted windows are shorter. Use [`Network::buffer_count`] for non-overlapping batches.
+    ///
+    /// ```text
+    /// Input:           ─1───2─────3───────4───────
+    /// Output (size 3): ─[1]─[1,2]─[1,2,3]─[2,3,4]─
+    /// ```
+    pub fn sliding_window<T>(&self, label: Label, src: &T, size: usize) -> Stream<Vec<Output<T>>>
+    where T: EventOutput {
+        self.register(OwnedSlidingWindow::new(label, src, size))
+    }
+
+    /// Collect the incoming events into non-overlapping batches of `size` elements and emit every
+    /// batch once it is complete. Events of an incomplete batch are never emitted.
+    ///
+    /// ```text
+    /// Input:           ─1─2─3───────4─5─6───────7─
+    /// Output (size 3): ─────[1,2,3]─────[4,5,6]───
+    /// ```
+    pub fn buffer_count<T>(&self, label: Label, src: &T, size: usize) -> Stream<Vec<Output<T>>>
+    where T: EventOutput {
+        self.register(OwnedBufferCount::new(label, src, size))
+    }
+
+    /// Pass the incoming event only if the key computed from it differs from the key of the last
+    /// passed event. The first event is always passed. Unlike [`Network::on_change`], the events
+    /// do not need to be comparable, and only the relevant part of them can be compared.
+    ///
+    /// ```text
+    /// Input:          ─(1,a)─(1,b)─(2,b)─(2,c)─(1,c)─
+    /// Output (by _0): ─(1,a)───────(2,b)───────(1,c)─
+    /// ```
+    pub fn distinct_until_changed_by<T, F, K>(
+        &self,
+        label: Label,
+        src: &T,
+        key: F,
+    ) -> Stream<Output<T>>
+    where
+        T: EventOutput,
+        K: 'static + PartialEq,
+        F: 'static + Fn(&Output<T>) -> K,
+    {
+        self.register(OwnedDistinctUntilChangedBy::new(label, src, key))
+    }
+
+    /// Pair the incoming events of both streams in the order they were received: the n-th event of
+    /// the first stream is emitted together with the n-th event of the second stream. Events
+    /// waiting for their pair are queued, so the streams should emit events at a similar rate.
+    /// Use [`Network::all`] if you want to pair events with the latest value of the other stream
+    /// instead.
+    ///
+    /// ```text
+    /// Input 1: ─1─2─3────────────────────────
+    /// Input 2: ──────────a───────b─────c─────
+    /// Output:  ──────────(1,a)───(2,b)─(3,c)─
+    /// ```
+    pub fn zip<T1, T2>(&self, label: Label, t1: &T1, t2: &T2) -> Stream<(Output<T1>, Output<T2>)>
+    where
+        T1: EventOutput,
+        T2: EventOutput, {
+        self.register(OwnedZip::new(label, t1, t2))
+    }
+
+    /// Accumulate the incoming events with the provided function, starting with the `init` state,
+    /// and emit the new state on every event. Every event of the `reset` stream restores the
+    /// `init` state and emits it.
+    ///
+    /// ```text
+    /// Input:        ─1─2───3───────4─
+    /// Reset:        ───────────x─────
+    /// Output (sum): ─1─3───6───0───4─
+    /// ```
+    pub fn scan<T1, T2, S, F>(
+        &self,
+        label: Label,
+        src: &T1,
+        reset: &T2,
+        init: S,
+        f: F,
+    ) -> Stream<S>
+    where
+        T1: EventOutput,
+        T2: EventOutput,
+        S: Data,
+        F: 'static + Fn(&S, &Output<T1>) -> S,
+    {
+        self.register(OwnedScan::new(label, src, reset, init, f))
+    }
+
     /// Get the 0-based index of the incoming event.
     pub fn _0<T1>(&self, label: Label, event: &T1) -> Stream<generics::ItemAt0<Output<T1>>>
     where
@@ -2669,6 +2792,10 @@ where
         self.gate.is_dropped()
     }
 
+    fn consumer_id(&self) -> Id {
+        self.gate.id()
+    }
+
     fn on_event_if_exists(&self, stack: CallStack, new_active: &bool) -> bool {
         if let Some(gate) = self.gate.upgrade() {
             match (gate.state.get(), new_active) {
@@ -3377,6 +3504,438 @@ impl<T1> stream::InputBehaviors for FoldData<T1> {
 
 
 
+// ================
+// === Throttle ===
+// ================
+
+/// The events passed by the [`Network::throttle`] node.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ThrottleEdge {
+    /// Pass the event opening a window.
+    Leading,
+    /// Pass the last event received within a window when the window closes.
+    Trailing,
+    /// Pass both the event opening a window and the last event received within it.
+    Both,
+}
+
+impl ThrottleEdge {
+    fn is_leading(self) -> bool {
+        matches!(self, Self::Leading | Self::Both)
+    }
+
+    fn is_trailing(self) -> bool {
+        matches!(self, Self::Trailing | Self::Both)
+    }
+}
+
+#[derive(Debug)]
+pub struct ThrottleData<T: HasOutput> {
+    period_ms:   i32,
+    edge:        ThrottleEdge,
+    window_open: Cell<bool>,
+    pending:     RefCell<Option<Output<T>>>,
+    timeout:     RefCell<Option<RawTimeout>>,
+}
+
+pub type OwnedThrottle<T> = stream::Node<ThrottleData<T>>;
+pub type Throttle<T> = stream::WeakNode<ThrottleData<T>>;
+
+impl<T: HasOutput> HasOutput for ThrottleData<T> {
+    type Output = Output<T>;
+}
+
+impl<T: EventOutput> OwnedThrottle<T> {
+    /// Constructor.
+    pub fn new(label: Label, src: &T, period_ms: i32, edge: ThrottleEdge) -> Self {
+        let window_open = default();
+        let pending = default();
+        let timeout = default();
+        let definition = ThrottleData { period_ms, edge, window_open, pending, timeout };
+        let this = Self::construct_and_connect(label, src, definition);
+        let weak = this.downgrade();
+        let on_window_closed: Rc<dyn Fn()> = Rc::new(move || {
+            if let Some(node) = weak.upgrade() {
+                node.on_window_closed();
+            }
+        });
+        this.timeout.replace(Some(RawTimeout::new(on_window_closed)));
+        this
+    }
+
+    fn open_window(&self) {
+        self.window_open.set(true);
+        if let Some(timeout) = &*self.timeout.borrow() {
+            timeout.restart(self.period_ms);
+        }
+    }
+
+    fn on_window_closed(&self) {
+        let pending = self.pending.borrow_mut().take();
+        match pending {
+            Some(value) => {
+                self.open_window();
+                self.emit_event(&default(), &value);
+            }
+            None => self.window_open.set(false),
+        }
+    }
+}
+
+impl<T: EventOutput> stream::EventConsumer<Output<T>> for OwnedThrottle<T> {
+    fn on_event(&self, stack: CallStack, value: &Output<T>) {
+        if self.window_open.get() {
+            if self.edge.is_trailing() {
+                self.pending.replace(Some(value.clone()));
+            }
+        } else {
+            self.open_window();
+            if self.edge.is_leading() {
+                self.emit_event(stack, value);
+            } else {
+                self.pending.replace(Some(value.clone()));
+            }
+        }
+    }
+}
+
+
+
+// =====================
+// === SlidingWindow ===
+// =====================
+
+#[derive(Debug)]
+pub struct SlidingWindowData<T: HasOutput> {
+    size:   usize,
+    window: RefCell<VecDeque<Output<T>>>,
+}
+
+pub type OwnedSlidingWindow<T> = stream::Node<SlidingWindowData<T>>;
+pub type SlidingWindow<T> = stream::WeakNode<SlidingWindowData<T>>;
+
+impl<T: HasOutput> HasOutput for SlidingWindowData<T> {
+    type Output = Vec<Output<T>>;
+}
+
+impl<T: EventOutput> OwnedSlidingWindow<T> {
+    /// Constructor.
+    pub fn new(label: Label, src: &T, size: usize) -> Self {
+        let window = RefCell::new(VecDeque::with_capacity(size + 1));
+        let definition = SlidingWindowData { size, window };
+        Self::construct_and_connect(label, src, definition)
+    }
+}
+
+impl<T: EventOutput> stream::EventConsumer<Output<T>> for OwnedSlidingWindow<T> {
+    fn on_event(&self, stack: CallStack, value: &Output<T>) {
+        let values = {
+            let mut window = self.window.borrow_mut();
+            window.push_back(value.clone());
+            while window.len() > self.size {
+                window.pop_front();
+            }
+            window.iter().cloned().collect()
+        };
+        self.emit_event(stack, &values);
+    }
+}
+
+
+
+// ===================
+// === BufferCount ===
+// ===================
+
+#[derive(Debug)]
+pub struct BufferCountData<T: HasOutput> {
+    size:   usize,
+    buffer: RefCell<Vec<Output<T>>>,
+}
+
+pub type OwnedBufferCount<T> = stream::Node<BufferCountData<T>>;
+pub type BufferCount<T> = stream::WeakNode<BufferCountData<T>>;
+
+impl<T: HasOutput> HasOutput for BufferCountData<T> {
+    type Output = Vec<Output<T>>;
+}
+
+impl<T: EventOutput> OwnedBufferCount<T> {
+    /// Constructor.
+    pub fn new(label: Label, src: &T, size: usize) -> Self {
+        let buffer = RefCell::new(Vec::with_capacity(size));
+        let definition = BufferCountData { size, buffer };
+        Self::construct_and_connect(label, src, definition)
+    }
+}
+
+impl<T: EventOutput> stream::EventConsumer<Output<T>> for OwnedBufferCount<T> {
+    fn on_event(&self, stack: CallStack, value: &Output<T>) {
+        let batch = {
+            let mut buffer = self.buffer.borrow_mut();
+            buffer.push(value.clone());
+            (buffer.len() >= self.size).as_some_from(|| mem::take(&mut *buffer))
+        };
+        if let Some(batch) = batch {
+            self.emit_event(stack, &batch);
+        }
+    }
+}
+
+
+
+// ==============================
+// === DistinctUntilChangedBy ===
+// ==============================
+
+pub struct DistinctUntilChangedByData<T, F, K> {
+    phantom:  PhantomData<T>,
+    key:      F,
+    last_key: RefCell<Option<K>>,
+}
+
+pub type OwnedDistinctUntilChangedBy<T, F, K> = stream::Node<DistinctUntilChangedByData<T, F, K>>;
+pub type DistinctUntilChangedBy<T, F, K> = stream::WeakNode<DistinctUntilChangedByData<T, F, K>>;
+
+impl<T, F, K> HasOutput for DistinctUntilChangedByData<T, F, K>
+where
+    T: EventOutput,
+    K: 'static + PartialEq,
+    F: 'static + Fn(&Output<T>) -> K,
+{
+    type Output = Output<T>;
+}
+
+impl<T, F, K> OwnedDistinctUntilChangedBy<T, F, K>
+where
+    T: EventOutput,
+    K: 'static + PartialEq,
+    F: 'static + Fn(&Output<T>) -> K,
+{
+    /// Constructor.
+    pub fn new(label: Label, src: &T, key: F) -> Self {
+        let definition =
+            DistinctUntilChangedByData { phantom: PhantomData, key, last_key: default() };
+        Self::construct_and_connect(label, src, definition)
+    }
+}
+
+impl<T, F, K> stream::EventConsumer<Output<T>> for OwnedDistinctUntilChangedBy<T, F, K>
+where
+    T: EventOutput,
+    K: 'static + PartialEq,
+    F: 'static + Fn(&Output<T>) -> K,
+{
+    fn on_event(&self, stack: CallStack, value: &Output<T>) {
+        let key = (self.key)(value);
+        let changed = self.last_key.borrow().as_ref() != Some(&key);
+        if changed {
+            self.last_key.replace(Some(key));
+            self.emit_event(stack, value);
+        }
+    }
+}
+
+impl<T, F, K> Debug for DistinctUntilChangedByData<T, F, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DistinctUntilChangedByData")
+    }
+}
+
+
+
+// ===========
+// === Zip ===
+// ===========
+
+#[derive(Debug)]
+pub struct ZipData<T1: EventOutput, T2: EventOutput> {
+    _src1:  T1,
+    _src2:  T2,
+    queue1: RefCell<VecDeque<Output<T1>>>,
+    queue2: RefCell<VecDeque<Output<T2>>>,
+}
+pub type OwnedZip<T1, T2> = stream::Node<ZipData<T1, T2>>;
+pub type Zip<T1, T2> = stream::WeakNode<ZipData<T1, T2>>;
+struct ZipSecondInput<T1: EventOutput, T2: EventOutput> {
+    zip: Zip<T1, T2>,
+}
+
+impl<T1, T2> HasOutput for ZipData<T1, T2>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+{
+    type Output = (Output<T1>, Output<T2>);
+}
+
+impl<T1, T2> OwnedZip<T1, T2>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+{
+    /// Constructor.
+    pub fn new(label: Label, t1: &T1, t2: &T2) -> Self {
+        let _src1 = t1.clone_ref();
+        let _src2 = t2.clone_ref();
+        let definition = ZipData { _src1, _src2, queue1: default(), queue2: default() };
+        let this = Self::construct(label, definition);
+        let weak = this.downgrade();
+        let second_input = ZipSecondInput { zip: weak.clone() };
+        t1.register_target(weak.into());
+        t2.register_target(stream::EventInput::new(Rc::new(second_input)));
+        this
+    }
+
+    fn on_second_event(&self, stack: CallStack, value: &Output<T2>) {
+        let first = self.queue1.borrow_mut().pop_front();
+        match first {
+            Some(first) => self.emit_event(stack, &(first, value.clone())),
+            None => self.queue2.borrow_mut().push_back(value.clone()),
+        }
+    }
+}
+
+impl<T1, T2> stream::EventConsumer<Output<T1>> for OwnedZip<T1, T2>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+{
+    fn on_event(&self, stack: CallStack, value: &Output<T1>) {
+        let second = self.queue2.borrow_mut().pop_front();
+        match second {
+            Some(second) => self.emit_event(stack, &(value.clone(), second)),
+            None => self.queue1.borrow_mut().push_back(value.clone()),
+        }
+    }
+}
+
+impl<T1, T2> stream::WeakEventConsumer<Output<T2>> for ZipSecondInput<T1, T2>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+{
+    fn is_dropped(&self) -> bool {
+        self.zip.is_dropped()
+    }
+
+    fn consumer_id(&self) -> Id {
+        self.zip.id()
+    }
+
+    fn on_event_if_exists(&self, stack: CallStack, value: &Output<T2>) -> bool {
+        if let Some(zip) = self.zip.upgrade() {
+            zip.on_second_event(stack, value);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+
+
+// ============
+// === Scan ===
+// ============
+
+pub struct ScanData<T1, T2, S, F> {
+    _src:     T1,
+    _reset:   T2,
+    init:     S,
+    state:    RefCell<S>,
+    function: F,
+}
+pub type OwnedScan<T1, T2, S, F> = stream::Node<ScanData<T1, T2, S, F>>;
+pub type Scan<T1, T2, S, F> = stream::WeakNode<ScanData<T1, T2, S, F>>;
+struct ScanReset<T1, T2, S, F> {
+    scan: Scan<T1, T2, S, F>,
+}
+
+impl<T1, T2, S, F> HasOutput for ScanData<T1, T2, S, F>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+    S: Data,
+    F: 'static + Fn(&S, &Output<T1>) -> S,
+{
+    type Output = S;
+}
+
+impl<T1, T2, S, F> OwnedScan<T1, T2, S, F>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+    S: Data,
+    F: 'static + Fn(&S, &Output<T1>) -> S,
+{
+    /// Constructor.
+    pub fn new(label: Label, src: &T1, reset: &T2, init: S, function: F) -> Self {
+        let _src = src.clone_ref();
+        let _reset = reset.clone_ref();
+        let state = RefCell::new(init.clone());
+        let definition = ScanData { _src, _reset, init, state, function };
+        let this = Self::construct(label, definition);
+        let weak = this.downgrade();
+        let on_reset = ScanReset { scan: weak.clone() };
+        src.register_target(weak.into());
+        reset.register_target(stream::EventInput::new(Rc::new(on_reset)));
+        this
+    }
+
+    fn reset(&self, stack: CallStack) {
+        self.state.replace(self.init.clone());
+        self.emit_event(stack, &self.init);
+    }
+}
+
+impl<T1, T2, S, F> stream::EventConsumer<Output<T1>> for OwnedScan<T1, T2, S, F>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+    S: Data,
+    F: 'static + Fn(&S, &Output<T1>) -> S,
+{
+    fn on_event(&self, stack: CallStack, value: &Output<T1>) {
+        let state = (self.function)(&self.state.borrow(), value);
+        self.state.replace(state.clone());
+        self.emit_event(stack, &state);
+    }
+}
+
+impl<T1, T2, S, F> stream::WeakEventConsumer<Output<T2>> for ScanReset<T1, T2, S, F>
+where
+    T1: EventOutput,
+    T2: EventOutput,
+    S: Data,
+    F: 'static + Fn(&S, &Output<T1>) -> S,
+{
+    fn is_dropped(&self) -> bool {
+        self.scan.is_dropped()
+    }
+
+    fn consumer_id(&self) -> Id {
+        self.scan.id()
+    }
+
+    fn on_event_if_exists(&self, stack: CallStack, _: &Output<T2>) -> bool {
+        if let Some(scan) = self.scan.upgrade() {
+            scan.reset(stack);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<T1, T2, S, F> Debug for ScanData<T1, T2, S, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ScanData")
+    }
+}
+
+
+
 // ==============
 // === AllMut ===
 // ==============
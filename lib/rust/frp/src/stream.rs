@@ -5,6 +5,7 @@ use crate::node::*;
 use crate::prelude::*;
 
 use crate::data::watch;
+use crate::introspection;
 
 
 
@@ -92,6 +93,27 @@ pub trait HasOutputTypeLabel {
     fn output_type_label(&self) -> Label;
 }
 
+/// Kind of this FRP node, like `Map` or `Gate`. Used mainly for debugging purposes.
+pub trait HasNodeKind {
+    /// Node kind label of this object.
+    fn node_kind(&self) -> Label;
+}
+
+
+
+// ==================
+// === HasTargets ===
+// ==================
+
+/// Returns ids of all nodes receiving events from this node. For introspection purposes only.
+#[allow(missing_docs)]
+pub trait HasTargets {
+    fn target_ids(&self) -> Vec<Id>;
+}
+
+/// All the information about a node used by the network introspection.
+pub trait Introspectable = HasOutputTypeLabel + HasNodeKind + HasTargets + InputBehaviors;
+
 
 
 // ======================
@@ -148,6 +170,9 @@ pub trait WeakEventConsumer<T> {
     /// Returns true is the consumer is already dropped.
     fn is_dropped(&self) -> bool;
 
+    /// Id of the node consuming the events. For introspection purposes only.
+    fn consumer_id(&self) -> Id;
+
     /// Callback for a new incoming event. Returns true if the event was consumed or false if it was
     /// not. Not consuming an event means that the event receiver was already dropped.
     fn on_event_if_exists(&self, stack: CallStack, value: &T) -> bool;
@@ -289,6 +314,9 @@ impl<Out: Data> EventEmitter for NodeData<Out> {
             });
             warn!("{}", backtrace())
         } else {
+            if introspection::is_counting_emissions() {
+                introspection::record_emission(self.id());
+            }
             self.ongoing_evaluations.set(self.ongoing_evaluations.get() + 1);
             if self.use_caching() {
                 *self.value_cache.borrow_mut() = value.clone();
@@ -329,6 +357,27 @@ impl<Out: Data> EventEmitter for NodeData<Out> {
     }
 }
 
+impl<Out> HasId for NodeData<Out> {
+    fn id(&self) -> Id {
+        let ptr: *const Self = self;
+        (ptr as *const () as usize).into()
+    }
+}
+
+impl<Out> HasTargets for NodeData<Out> {
+    fn target_ids(&self) -> Vec<Id> {
+        let consumer_ids = |targets: &[EventInput<Out>]| {
+            let existing = targets.iter().filter(|target| !target.data.is_dropped());
+            existing.map(|target| target.data.consumer_id()).collect_vec()
+        };
+        // The targets are borrowed mutably only for a moment after an event is emitted.
+        let targets = self.targets.try_borrow();
+        let mut ids = targets.map(|targets| consumer_ids(&targets)).unwrap_or_default();
+        ids.extend(consumer_ids(&self.new_targets.borrow()));
+        ids
+    }
+}
+
 impl<Out: Data> ValueProvider for NodeData<Out> {
     fn value(&self) -> Out {
         self.with(|t| t.clone()).unwrap_or_default()
@@ -623,6 +672,10 @@ where
         self.definition.strong_count() == 0
     }
 
+    fn consumer_id(&self) -> Id {
+        self.id()
+    }
+
     fn on_event_if_exists(&self, stack: CallStack, value: &T) -> bool {
         self.upgrade()
             .map(|node| {
@@ -724,7 +777,15 @@ impl<Def> HasOutputTypeLabel for Node<Def>
 where Def: HasOutputStatic + InputBehaviors
 {
     fn output_type_label(&self) -> Label {
-        type_name_to_output_label(type_name::<Def>())
+        type_name::<Output<Def>>()
+    }
+}
+
+impl<Def> HasNodeKind for Node<Def>
+where Def: HasOutputStatic + InputBehaviors
+{
+    fn node_kind(&self) -> Label {
+        type_name_to_node_kind(type_name::<Def>())
     }
 }
 
@@ -732,7 +793,7 @@ where Def: HasOutputStatic + InputBehaviors
 // only once for all node types. This has a noticeable impact on compilation time.
 // For more details see https://github.com/enso-org/enso/pull/3848
 #[inline(never)]
-fn type_name_to_output_label(typename: &'static str) -> Label {
+fn type_name_to_node_kind(typename: &'static str) -> Label {
     let label = typename.split('<').next().unwrap_or(typename);
     let label = label.rsplit(':').next().unwrap_or(label);
     let label = label.strip_suffix("Data").unwrap_or(label);
@@ -746,8 +807,7 @@ impl<Def: HasOutputStatic> InputBehaviors for Node<Def>
 where Def: InputBehaviors
 {
     fn input_behaviors(&self) -> Vec<Link> {
-        vec![] // FIXME
-               //        self.data.input_behaviors()
+        InputBehaviors::input_behaviors(&*self.definition)
     }
 }
 
@@ -755,7 +815,16 @@ impl<Def: HasOutputStatic> InputBehaviors for WeakNode<Def>
 where Def: InputBehaviors
 {
     fn input_behaviors(&self) -> Vec<Link> {
-        self.stream.input_behaviors()
+        self.upgrade().map(|node| node.input_behaviors()).unwrap_or_default()
+    }
+}
+
+
+// === HasTargets ===
+
+impl<Def: HasOutputStatic> HasTargets for Node<Def> {
+    fn target_ids(&self) -> Vec<Id> {
+        self.stream.data.target_ids()
     }
 }
 
@@ -0,0 +1,149 @@
+//! Benchmarks of searching 50 000 names, with and without the [`fuzzly::Index`] prefiltering
+//! candidates.
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use fuzzly::Index;
+use std::time::Duration;
+
+
+
+// =================
+// === Utilities ===
+// =================
+
+/// The number of names in the searched collection.
+const NAME_COUNT: usize = 50_000;
+
+/// The number of best matches returned by each search.
+const LIMIT: usize = 100;
+
+/// The patterns searched for, from a single letter to a full word.
+const PATTERNS: &[&str] = &["f", "tf", "fil", "tbfr", "filter_rows"];
+
+/// The modules of the generated names.
+const MODULES: &str = "Base.Data.Vector Base.Data.Text Base.Data.Numbers Base.Runtime \
+    Table.Data.Table Table.Data.Column Database.Connection Geo.Shapes Image.Codecs Visualization";
+
+/// The words the generated method names are made of.
+const WORDS: &str = "filter map rows column table sort join read write to from text vector first \
+    last index value count distinct group by aggregate parse format select remove set get at zip \
+    fold take";
+
+/// The base configuration for the benchmarks.
+fn bench_config() -> Criterion {
+    Criterion::default()
+        .measurement_time(Duration::from_secs(10))
+        .warm_up_time(Duration::from_secs(3))
+        .sample_size(25)
+}
+
+/// Generate `count` distinct qualified method names, like `Table.Data.Column.group_by_3`. The
+/// names are generated with a fixed seed, so they are the same in each run.
+fn gen_names(count: usize) -> Vec<String> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = move |bound: usize| {
+        // The xorshift generator.
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize % bound
+    };
+    let modules: Vec<_> = MODULES.split_whitespace().collect();
+    let words: Vec<_> = WORDS.split_whitespace().collect();
+    (0..count)
+        .map(|index| {
+            let module = modules[next(modules.len())];
+            let word_count = 1 + next(3);
+            let name: Vec<_> = (0..word_count).map(|_| words[next(words.len())]).collect();
+            format!("{module}.{}_{index}", name.join("_"))
+        })
+        .collect()
+}
+
+fn gen_index(names: &[String]) -> Index<usize> {
+    let mut index = Index::new();
+    for (key, name) in names.iter().enumerate() {
+        index.insert(key, name.as_str());
+    }
+    index
+}
+
+
+
+// ==================
+// === Benchmarks ===
+// ==================
+
+// === Search ===
+
+/// Search the names using the index.
+fn index_search(c: &mut Criterion) {
+    let index = gen_index(&gen_names(NAME_COUNT));
+    let mut group = c.benchmark_group("Index Search");
+    for pattern in PATTERNS {
+        group.bench_with_input(BenchmarkId::from_parameter(pattern), pattern, |b, pattern| {
+            b.iter(|| index.search(black_box(*pattern), fuzzly::metric::default(), LIMIT))
+        });
+    }
+    group.finish();
+}
+
+/// Score every name, for comparison with [`index_search`].
+fn full_scan(c: &mut Criterion) {
+    let names = gen_names(NAME_COUNT);
+    let mut group = c.benchmark_group("Full Scan");
+    for pattern in PATTERNS {
+        group.bench_with_input(BenchmarkId::from_parameter(pattern), pattern, |b, pattern| {
+            b.iter(|| fuzzly::rank(&names, black_box(*pattern), fuzzly::metric::default(), LIMIT))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name    = search_benchmarks;
+    config  = bench_config();
+    targets = index_search,full_scan
+}
+
+
+// === Update ===
+
+/// Build the index of all the names.
+fn build(c: &mut Criterion) {
+    let names = gen_names(NAME_COUNT);
+    c.bench_function("Index Build", |b| b.iter(|| gen_index(black_box(&names))));
+}
+
+/// Replace a single name in the index, as done when an entry changes.
+fn replace(c: &mut Criterion) {
+    let names = gen_names(NAME_COUNT);
+    let mut index = gen_index(&names);
+    let key = NAME_COUNT / 2;
+    let mut flip = false;
+    c.bench_function("Index Replace", |b| {
+        b.iter(|| {
+            flip = !flip;
+            let name = if flip { "Table.Data.Table.filter_rows_new" } else { names[key].as_str() };
+            index.insert(black_box(key), name);
+        })
+    });
+}
+
+criterion_group! {
+    name    = update_benchmarks;
+    config  = bench_config();
+    targets = build,replace
+}
+
+
+
+// ==============
+// === Runner ===
+// ==============
+
+criterion_main!(search_benchmarks, update_benchmarks);
@@ -0,0 +1,215 @@
+//! An index of texts for searching large collections, like all the entries of loaded libraries.
+//!
+//! Building the `SubsequenceGraph` of every text on each search does not scale to tens of thousands
+//! of texts. The [`Index`] keeps, for every character, the set of texts containing it, so a search
+//! only scores the texts containing all the characters of the pattern, which is a small fraction of
+//! the collection as soon as the pattern has a few characters.
+//!
+//! Note that n-gram indices, like a trigram index, don't apply here: the pattern is matched as a
+//! subsequence, so its adjacent characters may be far apart in the matching text.
+
+use crate::prelude::*;
+
+use crate::metric::Metric;
+use crate::rank;
+use crate::score::Subsequence;
+
+
+
+// ==============
+// === Folded ===
+// ==============
+
+/// The form of a character used as a key of the index. It must agree with the case-insensitive
+/// comparison of [`crate::matches`].
+fn folded(ch: char) -> char {
+    ch.to_ascii_lowercase()
+}
+
+/// The number of occurrences of each folded character of the text.
+fn char_counts(text: &str) -> HashMap<char, usize> {
+    let mut counts = HashMap::<char, usize>::new();
+    for ch in text.chars() {
+        *counts.entry(folded(ch)).or_default() += 1;
+    }
+    counts
+}
+
+
+
+// =============
+// === Match ===
+// =============
+
+/// An indexed text matching the pattern, as returned by [`Index::search`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Match<Key> {
+    /// The key the text was inserted with.
+    pub key:         Key,
+    /// The best subsequence of the text matching the pattern.
+    pub subsequence: Subsequence,
+}
+
+
+
+// =============
+// === Index ===
+// =============
+
+#[derive(Clone, Debug)]
+struct Entry {
+    text:        String,
+    char_counts: HashMap<char, usize>,
+}
+
+/// An incrementally updatable index of texts, identified by keys. See the module docs for details.
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct Index<Key> {
+    entries:  HashMap<Key, Entry>,
+    /// For each folded character, the keys of the texts containing it.
+    postings: HashMap<char, HashSet<Key>>,
+}
+
+impl<Key: Copy + Ord + Hash> Index<Key> {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The number of indexed texts.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the index contains no texts.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the text indexed with the given key.
+    pub fn get(&self, key: Key) -> Option<&str> {
+        self.entries.get(&key).map(|entry| entry.text.as_str())
+    }
+
+    /// Add the text to the index. If the key was already present, its previous text is replaced.
+    pub fn insert(&mut self, key: Key, text: impl Str) {
+        self.remove(key);
+        let text = text.into();
+        let char_counts = char_counts(&text);
+        for ch in char_counts.keys() {
+            self.postings.entry(*ch).or_default().insert(key);
+        }
+        self.entries.insert(key, Entry { text, char_counts });
+    }
+
+    /// Remove the text with the given key from the index, and return it.
+    pub fn remove(&mut self, key: Key) -> Option<String> {
+        let entry = self.entries.remove(&key)?;
+        for ch in entry.char_counts.keys() {
+            if let Some(keys) = self.postings.get_mut(ch) {
+                keys.remove(&key);
+                if keys.is_empty() {
+                    self.postings.remove(ch);
+                }
+            }
+        }
+        Some(entry.text)
+    }
+
+    /// The keys and texts of entries which may match the pattern: the texts containing every
+    /// character of the pattern at least as many times as the pattern does. The returned texts
+    /// still need to be checked with [`crate::matches`], as the characters may be in a different
+    /// order. The entries are ordered by their keys.
+    pub fn candidates(&self, pattern: &str) -> Vec<(Key, &str)> {
+        let pattern_counts = char_counts(pattern);
+        let mut keys = if pattern_counts.is_empty() {
+            self.entries.keys().copied().collect_vec()
+        } else {
+            let postings = pattern_counts.keys().map(|ch| self.postings.get(ch));
+            let Some(mut postings) = postings.collect::<Option<Vec<_>>>() else { return vec![] };
+            postings.sort_by_key(|keys| keys.len());
+            let (rarest, others) = postings.split_first().expect("The pattern is not empty.");
+            let in_all = |key: &&Key| others.iter().all(|keys| keys.contains(*key));
+            rarest.iter().filter(in_all).copied().collect_vec()
+        };
+        keys.sort_unstable();
+        let entries = keys.into_iter().map(|key| (key, &self.entries[&key]));
+        let has_all_chars = |(_, entry): &(Key, &Entry)| {
+            pattern_counts.iter().all(|(ch, count)| entry.char_counts.get(ch) >= Some(count))
+        };
+        entries.filter(has_all_chars).map(|(key, entry)| (key, entry.text.as_str())).collect()
+    }
+
+    /// Find the texts matching the pattern, and return at most `limit` best matches, ordered from
+    /// the best one. Matches with equal scores are ordered by their keys. Only the
+    /// [`candidates`](Self::candidates) are scored.
+    pub fn search(&self, pattern: &str, metric: impl Metric, limit: usize) -> Vec<Match<Key>> {
+        let candidates = self.candidates(pattern);
+        let texts = candidates.iter().map(|(_, text)| text);
+        let ranked = rank::rank(texts, pattern, metric, limit).into_iter();
+        ranked.map(|m| Match { key: candidates[m.index].0, subsequence: m.subsequence }).collect()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metric;
+
+    fn index(texts: &[&str]) -> Index<usize> {
+        let mut index = Index::new();
+        for (key, text) in texts.iter().enumerate() {
+            index.insert(key, *text);
+        }
+        index
+    }
+
+    fn candidate_keys(index: &Index<usize>, pattern: &str) -> Vec<usize> {
+        index.candidates(pattern).into_iter().map(|(key, _)| key).collect()
+    }
+
+    #[test]
+    fn prefiltering_candidates() {
+        let index = index(&["filter", "Table.filter_rows", "first", "map", "flit", "fill"]);
+        assert_eq!(candidate_keys(&index, "fil"), vec![0, 1, 4, 5]);
+        assert_eq!(candidate_keys(&index, "FLI"), vec![0, 1, 4, 5]);
+        assert_eq!(candidate_keys(&index, "ll"), vec![1, 5]);
+        assert_eq!(candidate_keys(&index, "fz"), Vec::<usize>::new());
+        assert_eq!(candidate_keys(&index, ""), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn searching() {
+        let texts = ["flit", "Table.filter_rows", "map", "filter"];
+        let index = index(&texts);
+        let found = index.search("fil", metric::default(), 10);
+        let keys = found.iter().map(|m| m.key).collect_vec();
+        assert_eq!(keys, vec![3, 1]);
+        let ranked = rank::rank(texts, "fil", metric::default(), 10).into_iter();
+        let expected = ranked.map(|m| Match { key: m.index, subsequence: m.subsequence });
+        assert_eq!(found, expected.collect_vec());
+    }
+
+    #[test]
+    fn updating() {
+        let mut index = index(&["filter", "map"]);
+        index.insert(2, "fold");
+        assert_eq!(candidate_keys(&index, "f"), vec![0, 2]);
+        index.insert(0, "zip");
+        assert_eq!(candidate_keys(&index, "f"), vec![2]);
+        assert_eq!(index.remove(2), Some("fold".to_owned()));
+        assert_eq!(index.remove(2), None);
+        assert_eq!(candidate_keys(&index, "f"), Vec::<usize>::new());
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(0), Some("zip"));
+        assert!(index.postings.values().all(|keys| !keys.is_empty()));
+        assert!(!index.postings.contains_key(&'f'));
+    }
+}
@@ -7,6 +7,14 @@
 //!
 //! The metrics used for scoring may be adjusted by implementing `Metric` trait, or by customizing
 //! parameters of metrics defined in `metric` module.
+//!
+//! To score a whole list of names at once, and keep only the best matches, use the `rank` function.
+//! The returned `Subsequence`s contain the indices of the matched characters, which may be used to
+//! highlight them. For large collections of names updated over time, use the `Index`, which scores
+//! only the names which may match the pattern.
+//!
+//! By default, the pattern must be a subsequence of the matched text. The `typo` module provides
+//! the opt-in tolerant mode, where patterns with a few mistyped letters match as well.
 
 // === Features ===
 #![feature(option_result_contains)]
@@ -29,12 +37,19 @@
 // === Export ===
 // ==============
 
+pub mod index;
 pub mod metric;
+pub mod rank;
 pub mod score;
 pub mod subsequence_graph;
+pub mod typo;
+pub mod word;
 
 pub use enso_prelude as prelude;
+pub use index::Index;
 pub use metric::Metric;
+pub use rank::rank;
+pub use rank::Match;
 pub use score::find_best_subsequence;
 pub use score::matches;
 pub use score::Subsequence;
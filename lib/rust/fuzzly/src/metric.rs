@@ -3,6 +3,7 @@
 use crate::prelude::*;
 
 use crate::subsequence_graph;
+use crate::word;
 
 
 
@@ -22,6 +23,13 @@ pub trait Metric {
     /// How good is the edge on the path on the Subsequence Graph.
     fn measure_edge(&self, edge: subsequence_graph::Edge, text: &str, pattern: &str) -> f32;
 
+    /// How good is a match found only after correcting the given number of typos in the pattern.
+    /// See the [`crate::typo`] module. This should be a penalty, i.e. a non-positive value; the
+    /// default implementation ignores the typos.
+    fn measure_typos(&self, _typos: usize, _text: &str, _pattern: &str) -> f32 {
+        0.0
+    }
+
     /// Return a new metric being a sum of this and `rhs`.
     fn sum<Rhs: Metric>(self, rhs: Rhs) -> Sum<Self, Rhs>
     where Self: Sized {
@@ -29,6 +37,20 @@ pub trait Metric {
     }
 }
 
+impl<M: Metric> Metric for &M {
+    fn measure_vertex(&self, vertex: subsequence_graph::Vertex, text: &str, pattern: &str) -> f32 {
+        (*self).measure_vertex(vertex, text, pattern)
+    }
+
+    fn measure_edge(&self, edge: subsequence_graph::Edge, text: &str, pattern: &str) -> f32 {
+        (*self).measure_edge(edge, text, pattern)
+    }
+
+    fn measure_typos(&self, typos: usize, text: &str, pattern: &str) -> f32 {
+        (*self).measure_typos(typos, text, pattern)
+    }
+}
+
 
 
 // ==========================
@@ -37,7 +59,10 @@ pub trait Metric {
 
 /// The default metric, recommended by this library
 pub fn default() -> impl Metric {
-    SubsequentLettersBonus::default().sum(CaseMatchBonus::default())
+    SubsequentLettersBonus::default()
+        .sum(CaseMatchBonus::default())
+        .sum(WordStartBonus::default())
+        .sum(TypoPenalty::default())
 }
 
 
@@ -66,6 +91,13 @@ impl<M1: Metric, M2: Metric> Metric for Sum<M1, M2> {
         let right = right.measure_edge(edge, text, pattern);
         left + right
     }
+
+    fn measure_typos(&self, typos: usize, text: &str, pattern: &str) -> f32 {
+        let Self(left, right) = self;
+        let left = left.measure_typos(typos, text, pattern);
+        let right = right.measure_typos(typos, text, pattern);
+        left + right
+    }
 }
 
 
@@ -96,14 +128,16 @@ impl Default for SubsequentLettersBonus {
 impl Metric for SubsequentLettersBonus {
     fn measure_vertex(&self, vertex: subsequence_graph::Vertex, text: &str, pattern: &str) -> f32 {
         let is_first_pattern_char = vertex.layer == 0;
-        let is_last_pattern_char = pattern.len().checked_sub(1).contains(&vertex.layer);
+        let pattern_length = pattern.chars().count();
+        let is_last_pattern_char = pattern_length.checked_sub(1).contains(&vertex.layer);
         let first_char_bonus = if is_first_pattern_char {
             self.base_weight / (vertex.position_in_text as f32 + 1.0) * self.beginning_weight
         } else {
             0.0
         };
         let last_char_bonus = if is_last_pattern_char {
-            self.base_weight / (text.len() - vertex.position_in_text) as f32 * self.ending_weight
+            let text_length = text.chars().count();
+            self.base_weight / (text_length - vertex.position_in_text) as f32 * self.ending_weight
         } else {
             0.0
         };
@@ -145,3 +179,70 @@ impl Metric for CaseMatchBonus {
         0.0
     }
 }
+
+
+// === WordStartBonus ===
+
+/// A metric which scores the matched chars beginning a word of the text, so that patterns made of
+/// word initials, like `fr` for `filter_rows`, are ranked high. See the [`word`] module for the
+/// rules of splitting text into words.
+#[derive(Copy, Clone, Debug)]
+pub struct WordStartBonus {
+    /// A score added for each matched char beginning a word.
+    pub bonus_per_char: f32,
+}
+
+impl Default for WordStartBonus {
+    fn default() -> Self {
+        WordStartBonus { bonus_per_char: 0.3 }
+    }
+}
+
+impl Metric for WordStartBonus {
+    fn measure_vertex(&self, vertex: subsequence_graph::Vertex, text: &str, _: &str) -> f32 {
+        if word::is_word_start_at(text, vertex.position_in_text) {
+            self.bonus_per_char
+        } else {
+            0.0
+        }
+    }
+
+    fn measure_edge(&self, _: subsequence_graph::Edge, _: &str, _: &str) -> f32 {
+        0.0
+    }
+}
+
+
+// === TypoPenalty ===
+
+/// A metric which lowers the score of the matches found only after correcting typos in the
+/// pattern. The penalty grows with the pattern length, so that it outweighs the bonuses given by
+/// the other default metrics, and a match with typos ranks below any exact match.
+#[derive(Copy, Clone, Debug)]
+pub struct TypoPenalty {
+    /// The penalty for each typo.
+    pub base_penalty:     f32,
+    /// The additional penalty for each typo and each char of the pattern.
+    pub penalty_per_char: f32,
+}
+
+impl Default for TypoPenalty {
+    fn default() -> Self {
+        TypoPenalty { base_penalty: 1.0, penalty_per_char: 1.5 }
+    }
+}
+
+impl Metric for TypoPenalty {
+    fn measure_vertex(&self, _: subsequence_graph::Vertex, _: &str, _: &str) -> f32 {
+        0.0
+    }
+
+    fn measure_edge(&self, _: subsequence_graph::Edge, _: &str, _: &str) -> f32 {
+        0.0
+    }
+
+    fn measure_typos(&self, typos: usize, _text: &str, pattern: &str) -> f32 {
+        let pattern_length = pattern.chars().count() as f32;
+        -(self.base_penalty + self.penalty_per_char * pattern_length) * typos as f32
+    }
+}
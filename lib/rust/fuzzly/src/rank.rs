@@ -0,0 +1,115 @@
+//! Ranking a list of candidates by how well they match a pattern.
+
+use crate::prelude::*;
+
+use crate::metric::Metric;
+use crate::score;
+use crate::score::Subsequence;
+
+
+
+// =============
+// === Match ===
+// =============
+
+/// A candidate matching the pattern, as returned by [`rank`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Match {
+    /// The index of the candidate in the ranked list.
+    pub index:       usize,
+    /// The best subsequence of the candidate matching the pattern.
+    pub subsequence: Subsequence,
+}
+
+impl Match {
+    /// Check if this match should be ranked before `other`: it has a greater score, or an equal
+    /// score and an earlier position in the list.
+    fn is_better_than(&self, other: &Match) -> bool {
+        let by_score = self.subsequence.score.total_cmp(&other.subsequence.score);
+        by_score.then_with(|| other.index.cmp(&self.index)).is_gt()
+    }
+}
+
+
+
+// ============
+// === Rank ===
+// ============
+
+/// Score each of the `candidates` against the `pattern` and return at most `limit` best matches,
+/// ordered from the best one. Matches with equal scores keep the order of the candidates.
+///
+/// The candidates are cut off as early as possible: the ones which don't match the pattern at all
+/// are rejected by [`score::matches`] without building their subsequence graph, and once `limit`
+/// matches are collected, the scored candidates not better than the worst of them are dropped
+/// right away, so the memory used does not depend on the number of candidates.
+pub fn rank<Text: AsRef<str>>(
+    candidates: impl IntoIterator<Item = Text>,
+    pattern: impl Str,
+    metric: impl Metric,
+    limit: usize,
+) -> Vec<Match> {
+    let pattern = pattern.as_ref();
+    let mut best: Vec<Match> = Vec::with_capacity(limit.min(64));
+    if limit == 0 {
+        return best;
+    }
+    for (index, candidate) in candidates.into_iter().enumerate() {
+        let text = candidate.as_ref();
+        if !score::matches(text, pattern) {
+            continue;
+        }
+        let Some(subsequence) = score::find_best_subsequence(text, pattern, &metric) else {
+            continue;
+        };
+        let candidate = Match { index, subsequence };
+        let is_full = best.len() == limit;
+        if is_full && best.last().map_or(false, |worst| !candidate.is_better_than(worst)) {
+            continue;
+        }
+        if is_full {
+            best.pop();
+        }
+        let position = best.partition_point(|kept| kept.is_better_than(&candidate));
+        best.insert(position, candidate);
+    }
+    best
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metric;
+
+    fn ranked_indices(candidates: &[&str], pattern: &str, limit: usize) -> Vec<usize> {
+        let matches = rank(candidates.iter(), pattern, metric::default(), limit);
+        matches.into_iter().map(|m| m.index).collect()
+    }
+
+    #[test]
+    fn ranking_candidates() {
+        let candidates = ["Text.from", "filter", "Table.filter_rows", "first", "map"];
+        assert_eq!(ranked_indices(&candidates, "fil", 10), vec![1, 2]);
+        assert_eq!(ranked_indices(&candidates, "fil", 1), vec![1]);
+        assert_eq!(ranked_indices(&candidates, "fil", 0), Vec::<usize>::new());
+        assert_eq!(ranked_indices(&candidates, "xyz", 10), Vec::<usize>::new());
+        assert_eq!(ranked_indices(&candidates, "", 3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn ranking_keeps_best_matches() {
+        let candidates = ["a_b_c_d", "abcd", "a__bcd", "abc_d", "xabcd"];
+        let all = rank(candidates.iter(), "abcd", metric::default(), candidates.len());
+        assert!(all.windows(2).all(|pair| pair[0].subsequence.score >= pair[1].subsequence.score));
+        for limit in 1..candidates.len() {
+            let limited = rank(candidates.iter(), "abcd", metric::default(), limit);
+            assert_eq!(limited, all[..limit]);
+        }
+    }
+}
@@ -155,6 +155,41 @@ pub struct Subsequence {
     pub indices: Vec<usize>,
 }
 
+impl Subsequence {
+    /// The ranges of char indices of `text` which belong to the subsequence. Consecutive matched
+    /// chars are merged into a single range.
+    pub fn char_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = default();
+        for &index in &self.indices {
+            match ranges.last_mut() {
+                Some(range) if range.end == index => range.end += 1,
+                _ => ranges.push(index..index + 1),
+            }
+        }
+        ranges
+    }
+
+    /// The ranges of byte offsets of `text` which belong to the subsequence, for highlighting the
+    /// matched chars. Consecutive matched chars are merged into a single range. The `text` must be
+    /// the one the subsequence was found in.
+    pub fn byte_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let mut char_offsets = text.char_indices().map(|(offset, _)| offset).enumerate();
+        let mut byte_offset = |char_index: usize| {
+            let found = char_offsets.find(|(index, _)| *index == char_index);
+            found.map_or(text.len(), |(_, offset)| offset)
+        };
+        // The offsets are looked up in increasing order, so a single pass over `text` is enough.
+        self.char_ranges()
+            .into_iter()
+            .map(|range| {
+                let start = byte_offset(range.start);
+                let end = byte_offset(range.end);
+                start..end
+            })
+            .collect()
+    }
+}
+
 
 /// Find best subsequence in `text` which case-insensitively equals to `pattern` in terms of given
 /// `metric`.
@@ -315,6 +350,25 @@ mod test {
         assert_eq!(find_best_subsequence(text, pattern, mock_metric::Sum::default()), None);
     }
 
+    #[test]
+    fn matched_ranges() {
+        let subsequence = Subsequence { score: 0.0, indices: vec![0, 1, 2, 5, 7, 8] };
+        assert_eq!(subsequence.char_ranges(), vec![0..3, 5..6, 7..9]);
+        assert_eq!(subsequence.byte_ranges("abcdefghi"), vec![0..3, 5..6, 7..9]);
+        assert_eq!(subsequence.byte_ranges("żółw i ćma"), vec![0..6, 8..9, 10..13]);
+        assert_eq!(Subsequence::default().byte_ranges("any"), vec![]);
+    }
+
+    #[test]
+    fn word_starts_are_preferred() {
+        let text = "filterRows";
+        let best = find_best_subsequence(text, "fr", metric::default()).unwrap();
+        assert_eq!(best.indices, vec![0, 6]);
+        let text = "Table.first_row";
+        let best = find_best_subsequence(text, "tr", metric::default()).unwrap();
+        assert_eq!(best.indices, vec![0, 12]);
+    }
+
     #[test]
     fn correct_scoring_of_trailing_pattern() {
         let pattern = "list";
@@ -0,0 +1,220 @@
+//! Typo-tolerant matching. A pattern with a mistyped letter, like `flitr` for `filter`, is not a
+//! subsequence of the text it was meant to match, so it is rejected by [`score::matches`]. In the
+//! tolerant mode, the pattern is also matched after correcting a bounded number of typos:
+//! - a **transposition** swaps two adjacent chars of the pattern, so `flitr` matches `filter`;
+//! - a **deletion** removes a char from the pattern, which covers both substituted and extra chars:
+//!   as the pattern is matched as a subsequence, the text may contain anything in the place of the
+//!   removed char.
+//!
+//! The number of corrections is the edit distance between the typed pattern and the matched one.
+//! The matches requiring the fewest corrections are preferred, and their score is lowered by
+//! [`Metric::measure_typos`].
+
+use crate::prelude::*;
+
+use crate::metric::Metric;
+use crate::score;
+use crate::score::Subsequence;
+
+
+
+// =================
+// === Tolerance ===
+// =================
+
+/// How many typos may be corrected in a pattern.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tolerance {
+    /// The maximum number of typos corrected in any pattern.
+    pub max_typos:      usize,
+    /// The number of pattern chars needed for each allowed typo. Short patterns can't have typos
+    /// corrected, because after removing a char from them they would match almost anything.
+    pub chars_per_typo: usize,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance { max_typos: 2, chars_per_typo: 4 }
+    }
+}
+
+impl Tolerance {
+    /// The tolerance of the strict mode, where no typos are corrected.
+    pub fn none() -> Self {
+        Tolerance { max_typos: 0, ..default() }
+    }
+
+    /// The number of typos which may be corrected in a pattern of the given char count.
+    pub fn allowed_typos(&self, pattern_length: usize) -> usize {
+        let by_length = pattern_length.checked_div(self.chars_per_typo).unwrap_or(0);
+        self.max_typos.min(by_length)
+    }
+}
+
+
+
+// ===================
+// === Corrections ===
+// ===================
+
+/// All the patterns obtained by correcting exactly `typos` typos in `pattern`, and no fewer.
+///
+/// The result is empty if `typos` is 0. Corrections which would empty the pattern are not made.
+pub fn corrections(pattern: &str, typos: usize) -> Vec<String> {
+    let pattern = pattern.chars().collect_vec();
+    let mut seen: HashSet<Vec<char>> = HashSet::from([pattern.clone()]);
+    let mut current = vec![pattern];
+    for _ in 0..typos {
+        let mut next = vec![];
+        for pattern in &current {
+            for corrected in single_corrections(pattern) {
+                if !corrected.is_empty() && seen.insert(corrected.clone()) {
+                    next.push(corrected);
+                }
+            }
+        }
+        current = next;
+    }
+    if typos == 0 {
+        return vec![];
+    }
+    current.into_iter().map(|chars| chars.into_iter().collect()).collect()
+}
+
+/// All the patterns obtained by a single deletion or transposition.
+fn single_corrections(pattern: &[char]) -> impl Iterator<Item = Vec<char>> + '_ {
+    let deletions = (0..pattern.len()).map(|index| {
+        let mut corrected = pattern.to_vec();
+        corrected.remove(index);
+        corrected
+    });
+    let transpositions = (1..pattern.len()).filter(move |i| pattern[i - 1] != pattern[*i]);
+    let transpositions = transpositions.map(|index| {
+        let mut corrected = pattern.to_vec();
+        corrected.swap(index - 1, index);
+        corrected
+    });
+    deletions.chain(transpositions)
+}
+
+
+
+// ================
+// === Matching ===
+// ================
+
+/// Check if the pattern matches the text, after correcting at most as many typos as the tolerance
+/// allows. The tolerant counterpart of [`score::matches`].
+pub fn matches(text: impl Str, pattern: impl Str, tolerance: Tolerance) -> bool {
+    let text = text.as_ref();
+    let pattern = pattern.as_ref();
+    score::matches(text, pattern) || {
+        let allowed_typos = tolerance.allowed_typos(pattern.chars().count());
+        (1..=allowed_typos)
+            .any(|typos| corrections(pattern, typos).iter().any(|c| score::matches(text, c)))
+    }
+}
+
+/// Find the best subsequence of `text` matching `pattern`, after correcting at most as many typos
+/// as the tolerance allows. The tolerant counterpart of [`score::find_best_subsequence`].
+///
+/// If the pattern matches without corrections, the result is the same as of
+/// [`score::find_best_subsequence`]. Otherwise, the fewest typos possible are corrected, the best
+/// of the corrected patterns is picked, and its score is lowered by [`Metric::measure_typos`]. The
+/// `indices` of the result are the text chars matching the corrected pattern.
+pub fn find_best_subsequence(
+    text: impl Str,
+    pattern: impl Str,
+    metric: impl Metric,
+    tolerance: Tolerance,
+) -> Option<Subsequence> {
+    let text = text.as_ref();
+    let pattern = pattern.as_ref();
+    if score::matches(text, pattern) {
+        return score::find_best_subsequence(text, pattern, metric);
+    }
+    let allowed_typos = tolerance.allowed_typos(pattern.chars().count());
+    (1..=allowed_typos).find_map(|typos| {
+        let corrections = corrections(pattern, typos).into_iter();
+        let matching = corrections.filter(|corrected| score::matches(text, corrected));
+        let found = matching.filter_map(|c| score::find_best_subsequence(text, c, &metric));
+        let best = found.max_by(|a, b| a.score.total_cmp(&b.score))?;
+        let score = best.score + metric.measure_typos(typos, text, pattern);
+        Some(Subsequence { score, ..best })
+    })
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metric;
+
+    #[test]
+    fn allowed_typos() {
+        let tolerance = Tolerance::default();
+        assert_eq!(tolerance.allowed_typos(3), 0);
+        assert_eq!(tolerance.allowed_typos(5), 1);
+        assert_eq!(tolerance.allowed_typos(20), 2);
+        assert_eq!(Tolerance::none().allowed_typos(20), 0);
+        assert_eq!(Tolerance { max_typos: 1, chars_per_typo: 0 }.allowed_typos(20), 0);
+    }
+
+    #[test]
+    fn correcting_patterns() {
+        let mut single = corrections("abc", 1);
+        single.sort();
+        assert_eq!(single, vec!["ab", "ac", "acb", "bac", "bc"]);
+        let double = corrections("abc", 2);
+        assert!(double.contains(&"a".to_owned()));
+        assert!(double.contains(&"bca".to_owned()));
+        assert!(!double.iter().any(|pattern| single.contains(pattern) || pattern == "abc"));
+        assert_eq!(corrections("aa", 1), vec!["a"]);
+        assert_eq!(corrections("a", 1), Vec::<String>::new());
+        assert_eq!(corrections("abc", 0), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tolerant_matching() {
+        let tolerance = Tolerance::default();
+        assert!(!score::matches("filter", "flitr"));
+        assert!(matches("filter", "flitr", tolerance));
+        assert!(matches("filter", "fikter", tolerance));
+        assert!(matches("filter", "filterx", tolerance));
+        assert!(!matches("filter", "flitr", Tolerance::none()));
+        assert!(!matches("filter", "map", tolerance));
+        assert!(!matches("filter", "lif", tolerance));
+        assert!(!matches("filter", "rtlif", tolerance));
+    }
+
+    #[test]
+    fn typo_matches_rank_below_exact_ones() {
+        let tolerance = Tolerance::default();
+        let metric = metric::default();
+        let exact =
+            find_best_subsequence("file_list_transform", "flitr", &metric, tolerance).unwrap();
+        let typo = find_best_subsequence("filter", "flitr", &metric, tolerance).unwrap();
+        assert!(exact.score > typo.score, "{exact:?} {typo:?}");
+        assert_eq!(typo.indices, vec![0, 1, 2, 3, 5]);
+        let strict = score::find_best_subsequence("filter", "filter", &metric);
+        assert_eq!(find_best_subsequence("filter", "filter", &metric, tolerance), strict);
+        assert_eq!(find_best_subsequence("filter", "flitr", &metric, Tolerance::none()), None);
+    }
+
+    #[test]
+    fn typos_are_penalized() {
+        let tolerance = Tolerance::default();
+        let metric = metric::default();
+        assert!(metric.measure_typos(1, "filter", "flitr") < 0.0);
+        assert_eq!(metric.measure_typos(0, "filter", "filter"), 0.0);
+        let typo = find_best_subsequence("filter", "flitr", &metric, tolerance).unwrap();
+        let corrected = score::find_best_subsequence("filter", "filtr", &metric).unwrap();
+        assert_eq!(typo.indices, corrected.indices);
+        assert!(typo.score < corrected.score, "{typo:?} {corrected:?}");
+    }
+}
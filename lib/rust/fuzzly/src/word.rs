@@ -0,0 +1,139 @@
+//! Detecting the beginnings of words in identifiers, like `camelCase`, `snake_case` or
+//! `Module.method` names. The words are found using the Unicode categories of characters, so the
+//! detection works for any script, not only for ASCII text.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === CharClass ===
+// =================
+
+/// The category of a character, as far as splitting text into words is concerned.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CharClass {
+    /// An uppercase or titlecase letter.
+    Upper,
+    /// A lowercase letter.
+    Lower,
+    /// A letter of a script without case, like CJK ideographs.
+    Uncased,
+    /// A digit, or other numeric character.
+    Digit,
+    /// Any other character, like `_`, `.` or whitespace. Separators are never part of a word.
+    Separator,
+}
+
+impl CharClass {
+    /// Get the class of the given character.
+    pub fn of(ch: char) -> Self {
+        if ch.is_lowercase() {
+            Self::Lower
+        } else if ch.is_uppercase() {
+            Self::Upper
+        } else if ch.is_alphabetic() {
+            // Titlecase letters, like `ǅ`, are neither lowercase nor uppercase, but start a word
+            // the same way an uppercase letter does.
+            if ch.to_lowercase().eq(std::iter::once(ch)) {
+                Self::Uncased
+            } else {
+                Self::Upper
+            }
+        } else if ch.is_numeric() {
+            Self::Digit
+        } else {
+            Self::Separator
+        }
+    }
+}
+
+
+
+// ==================
+// === Word Start ===
+// ==================
+
+/// Check if a character of the given class begins a word, given the classes of the characters
+/// directly before and after it.
+///
+/// A word begins:
+/// - at the first non-separator character of the text, or after a separator (`snake_case`,
+///   `Module.method`),
+/// - at an uppercase letter following a lowercase letter (`camelCase`),
+/// - at the last uppercase letter of an acronym followed by a lowercase one (`HTTPServer`),
+/// - wherever the class changes between letters, digits and uncased letters (`vec3`, `表Table`).
+pub fn is_word_start(prev: Option<CharClass>, current: CharClass, next: Option<CharClass>) -> bool {
+    use CharClass::*;
+    match (prev, current) {
+        (_, Separator) => false,
+        (None | Some(Separator), _) => true,
+        (Some(Upper), Upper) => next == Some(Lower),
+        (Some(Upper | Lower), Lower) => false,
+        (Some(prev), current) => prev != current,
+    }
+}
+
+/// Check if the character at the given char index of `text` begins a word. Returns `false` if the
+/// index is out of bounds.
+pub fn is_word_start_at(text: &str, char_index: usize) -> bool {
+    let mut chars = text.chars().skip(char_index.saturating_sub(1)).map(CharClass::of);
+    let prev = if char_index > 0 { chars.next() } else { None };
+    match chars.next() {
+        Some(current) => is_word_start(prev, current, chars.next()),
+        None => false,
+    }
+}
+
+/// The char indices of all characters in `text` which begin a word. See [`is_word_start`] for the
+/// rules.
+pub fn word_starts(text: &str) -> Vec<usize> {
+    let classes = text.chars().map(CharClass::of).collect_vec();
+    let word_starts = classes.iter().enumerate().filter(|(index, current)| {
+        let prev = index.checked_sub(1).map(|prev| classes[prev]);
+        let next = classes.get(index + 1).copied();
+        is_word_start(prev, **current, next)
+    });
+    word_starts.map(|(index, _)| index).collect()
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn word_starts_in_identifiers() {
+        let cases: &[(&str, &[usize])] = &[
+            ("", &[]),
+            ("camelCaseName", &[0, 5, 9]),
+            ("snake_case_name", &[0, 6, 11]),
+            ("Table.filter_rows", &[0, 6, 13]),
+            ("HTTPServer", &[0, 4]),
+            ("parseHTTP", &[0, 5]),
+            ("vec3Add", &[0, 3, 4]),
+            ("_private", &[1]),
+            ("  two words", &[2, 6]),
+            ("ZażółćGęślą", &[0, 6]),
+            ("données.écrire", &[0, 8]),
+            ("表Table表", &[0, 1, 6]),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(&word_starts(text), expected, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn word_start_at_index() {
+        let text = "Table.filterRows";
+        let expected = word_starts(text);
+        for index in 0..=text.chars().count() {
+            assert_eq!(is_word_start_at(text, index), expected.contains(&index), "{index}");
+        }
+    }
+}
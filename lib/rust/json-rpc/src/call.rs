@@ -0,0 +1,252 @@
+//! Module providing `CallOptions`, controlling a single call made to the peer, and the types used
+//! by them: `CancellationToken` and `ProgressToken`.
+
+use crate::prelude::*;
+
+use crate::ensogl::Duration;
+use crate::messages::Id;
+
+use futures::channel::mpsc::unbounded;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
+use futures::channel::oneshot;
+use serde::Deserialize;
+use serde::Serialize;
+use std::future::Future;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The method of the notification sent to the peer when a call is cancelled.
+pub const CANCEL_REQUEST_METHOD: &str = "$/cancelRequest";
+
+/// The method of the notifications reporting the progress of a call.
+pub const PROGRESS_METHOD: &str = "$/progress";
+
+
+
+// =========================
+// === CancellationToken ===
+// =========================
+
+#[derive(Debug, Default)]
+struct CancellationTokenData {
+    cancelled: bool,
+    waiting:   Vec<oneshot::Sender<()>>,
+}
+
+/// A handle allowing to cancel calls. All clones of the token share the same state, and a single
+/// token may be used to cancel many calls.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct CancellationToken {
+    data: Rc<RefCell<CancellationTokenData>>,
+}
+
+impl CancellationToken {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Cancel all the calls using this token, including the ones made with it later.
+    pub fn cancel(&self) {
+        let waiting = with(self.data.borrow_mut(), |mut data| {
+            data.cancelled = true;
+            mem::take(&mut data.waiting)
+        });
+        for sender in waiting {
+            // The waiting call may have already finished.
+            sender.send(()).ok();
+        }
+    }
+
+    /// Check if the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.data.borrow().cancelled
+    }
+
+    /// Returns a `Future` which completes once the token is cancelled.
+    pub fn cancelled(&self) -> impl Future<Output = ()> + 'static {
+        let receiver = with(self.data.borrow_mut(), |mut data| {
+            (!data.cancelled).as_some_from(|| {
+                let (sender, receiver) = oneshot::channel();
+                // Forget the futures which were dropped before the token was cancelled, so that
+                // a long-lived token doesn't accumulate them.
+                data.waiting.retain(|sender| !sender.is_canceled());
+                data.waiting.push(sender);
+                receiver
+            })
+        });
+        // The future keeps the token alive, so the sender is never dropped without being used.
+        let token = self.clone_ref();
+        async move {
+            if let Some(receiver) = receiver {
+                receiver.await.ok();
+            }
+            drop(token);
+        }
+    }
+}
+
+
+
+// =====================
+// === ProgressToken ===
+// =====================
+
+/// The token identifying the progress notifications of a call. It is passed to the peer as a part
+/// of the call params, and sent back by the peer in each `$/progress` notification.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum ProgressToken {
+    Number(i64),
+    String(String),
+}
+
+impl From<i64> for ProgressToken {
+    fn from(number: i64) -> Self {
+        ProgressToken::Number(number)
+    }
+}
+
+impl From<String> for ProgressToken {
+    fn from(string: String) -> Self {
+        ProgressToken::String(string)
+    }
+}
+
+impl From<&str> for ProgressToken {
+    fn from(string: &str) -> Self {
+        ProgressToken::String(string.to_owned())
+    }
+}
+
+/// The params of a `$/progress` notification.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProgressParams {
+    /// The token of the call the progress is reported for.
+    pub token: ProgressToken,
+    /// The progress data, in JSON form.
+    pub value: serde_json::Value,
+}
+
+/// The params of a `$/cancelRequest` notification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancelParams {
+    /// The id of the cancelled request.
+    pub id: Id,
+}
+
+
+
+// ===================
+// === CallOptions ===
+// ===================
+
+/// Options of a single call made to the peer, passed to `Handler::open_request_with_options`.
+///
+/// A call is finished when the reply is received, the call times out, its cancellation token is
+/// cancelled, or its `Future` is dropped. In the last three cases the call is forgotten by the
+/// `Handler`, and, if `notify_cancel` is set, the peer is sent a `$/cancelRequest` notification.
+#[derive(Debug, Default)]
+pub struct CallOptions {
+    /// The timeout of the call. If not set, the `Handler`'s timeout is used.
+    pub timeout:       Option<Duration>,
+    /// The token cancelling the call.
+    pub cancellation:  Option<CancellationToken>,
+    /// Whether the peer should be notified when the call is abandoned before receiving the reply.
+    pub notify_cancel: bool,
+    /// The token and the sink of the progress notifications of the call.
+    pub progress:      Option<(ProgressToken, UnboundedSender<serde_json::Value>)>,
+}
+
+impl CallOptions {
+    /// Constructor. The created options are the ones used by `Handler::open_request`.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Set the timeout of the call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the token cancelling the call.
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.cancellation = Some(token.clone_ref());
+        self
+    }
+
+    /// Notify the peer with `$/cancelRequest` when the call is abandoned.
+    pub fn with_cancel_notification(mut self) -> Self {
+        self.notify_cancel = true;
+        self
+    }
+
+    /// Route the `$/progress` notifications with the given token to the returned stream. The stream
+    /// ends when the call is finished.
+    ///
+    /// The token should also be passed to the peer in the call params, in the way required by the
+    /// called method.
+    pub fn report_progress(
+        &mut self,
+        token: impl Into<ProgressToken>,
+    ) -> UnboundedReceiver<serde_json::Value> {
+        let (sender, receiver) = unbounded();
+        self.progress = Some((token.into(), sender));
+        receiver
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::FutureExt;
+
+    #[test]
+    fn cancelling_token() {
+        let token = CancellationToken::new();
+        let mut before = token.cancelled().boxed_local();
+        before.expect_pending();
+        assert!(!token.is_cancelled());
+        token.clone_ref().cancel();
+        assert!(token.is_cancelled());
+        before.expect_ready();
+        token.cancelled().boxed_local().expect_ready();
+    }
+
+    #[test]
+    fn forgetting_dropped_waiters() {
+        let token = CancellationToken::new();
+        for _ in 0..10 {
+            token.cancelled().boxed_local().expect_pending();
+        }
+        let mut waiting = token.cancelled().boxed_local();
+        assert_eq!(token.data.borrow().waiting.len(), 1);
+        waiting.expect_pending();
+        token.cancel();
+        waiting.expect_ready();
+    }
+
+    #[test]
+    fn serializing_progress_params() {
+        let params = ProgressParams { token: 5.into(), value: serde_json::json!({ "done": 1 }) };
+        let json = serde_json::json!({ "token": 5, "value": { "done": 1 } });
+        assert_eq!(serde_json::to_value(&params).unwrap(), json);
+        let json = serde_json::json!({ "token": "a", "value": null });
+        let params = serde_json::from_value::<ProgressParams>(json).unwrap();
+        assert_eq!(params.token, ProgressToken::from("a"));
+    }
+}
@@ -0,0 +1,117 @@
+//! Module providing `Dispatcher`, answering the calls initiated by the peer.
+
+use crate::prelude::*;
+
+use crate::api::RemoteMethodCall;
+use crate::messages;
+use crate::messages::error_code;
+use crate::messages::IncomingRequest;
+use crate::messages::Message;
+use crate::messages::ResponseMessage;
+
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::future::Future;
+
+
+
+// =============
+// === Reply ===
+// =============
+
+/// The outcome of handling a call initiated by the peer: either the value returned to the peer, or
+/// the error reported to it.
+pub type Reply<T> = Result<T, messages::Error>;
+
+/// A `Future` yielding the response to a call initiated by the peer.
+pub type ResponseFuture = LocalBoxFuture<'static, ResponseMessage<serde_json::Value>>;
+
+/// A registered method, taking the call params and returning the reply in JSON form.
+type Method = Rc<dyn Fn(serde_json::Value) -> LocalBoxFuture<'static, Reply<serde_json::Value>>>;
+
+
+
+// ==================
+// === Dispatcher ===
+// ==================
+
+/// Routes the requests initiated by the peer to the handlers registered for their methods, and
+/// builds the response messages from the handlers' replies.
+///
+/// The handlers are typed: each handles a single method described by a type implementing
+/// `RemoteMethodCall`, the same way as the calls made by this side are described. The request
+/// params are deserialized into that type, and the value returned by the handler is serialized as
+/// the call result. A request to an unknown method, or with params not matching the type, is
+/// answered with the relevant error defined by the JSON-RPC specification.
+#[derive(Clone, Default)]
+pub struct Dispatcher {
+    methods: HashMap<String, Method>,
+}
+
+impl Debug for Dispatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dispatcher").field("methods", &self.methods.keys()).finish()
+    }
+}
+
+impl Dispatcher {
+    /// Create a dispatcher with no methods registered.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Register a handler for calls of the method `In::NAME`. If the method already had a
+    /// handler, it is replaced.
+    pub fn register<In, F, Fut>(&mut self, handler: F)
+    where
+        In: RemoteMethodCall + DeserializeOwned + 'static,
+        In::Returned: Serialize,
+        F: Fn(In) -> Fut + 'static,
+        Fut: Future<Output = Reply<In::Returned>> + 'static, {
+        let method: Method = Rc::new(move |params| match serde_json::from_value::<In>(params) {
+            Ok(input) =>
+                handler(input).map(|reply| reply.and_then(serialize_returned)).boxed_local(),
+            Err(error) => {
+                let error = messages::Error::new(error_code::INVALID_PARAMS, error.to_string());
+                futures::future::ready(Err(error)).boxed_local()
+            }
+        });
+        self.methods.insert(In::NAME.to_owned(), method);
+    }
+
+    /// Check if there is a handler registered for the given method.
+    pub fn is_registered(&self, method: &str) -> bool {
+        self.methods.contains_key(method)
+    }
+
+    /// Handle a request initiated by the peer. Returns a `Future` yielding the response message,
+    /// which should be sent back to the peer.
+    pub fn dispatch(&self, request: IncomingRequest) -> ResponseFuture {
+        let id = request.id;
+        let method = request.call.method;
+        let reply = match self.methods.get(&method) {
+            Some(handler) => handler(request.call.params),
+            None => {
+                let message = format!("Method not found: {method}.");
+                let error = messages::Error::new(error_code::METHOD_NOT_FOUND, message);
+                futures::future::ready(Err(error)).boxed_local()
+            }
+        };
+        reply
+            .map(move |reply| match reply {
+                Ok(result) => Message::new_success(id, result),
+                Err(messages::Error { code, message, data }) =>
+                    Message::new_error(id, code, message, data),
+            })
+            .boxed_local()
+    }
+}
+
+fn serialize_returned<Returned: Serialize>(returned: Returned) -> Reply<serde_json::Value> {
+    serde_json::to_value(returned).map_err(|error| {
+        let message = format!("Failed to serialize the result: {error}.");
+        messages::Error::new(error_code::INTERNAL_ERROR, message)
+    })
+}
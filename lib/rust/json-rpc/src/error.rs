@@ -40,6 +40,10 @@ pub enum RpcError<Payload: Debug + Send + Sync + 'static = serde_json::Value> {
     #[allow(missing_docs)]
     #[fail(display = "Response timed out after {} ms.", millis)]
     TimeoutError { millis: u128 },
+
+    /// The call was cancelled with its `CancellationToken`.
+    #[fail(display = "The call was cancelled.")]
+    Cancelled,
 }
 
 impl RpcError {
@@ -4,12 +4,24 @@ use crate::prelude::*;
 
 use crate::api;
 use crate::api::Result;
+use crate::call::CallOptions;
+use crate::call::CancelParams;
+use crate::call::ProgressParams;
+use crate::call::ProgressToken;
+use crate::call::CANCEL_REQUEST_METHOD;
+use crate::call::PROGRESS_METHOD;
+use crate::dispatcher::Dispatcher;
+use crate::dispatcher::Reply;
+use crate::dispatcher::ResponseFuture;
 use crate::ensogl::sleep;
 use crate::ensogl::Duration;
 use crate::error::HandlingError;
 use crate::error::RpcError;
 use crate::messages;
+use crate::messages::BatchResponse;
 use crate::messages::Id;
+use crate::messages::IncomingRequest;
+use crate::messages::InvalidRequest;
 use crate::transport::Transport;
 use crate::transport::TransportEvent;
 
@@ -17,10 +29,13 @@ use futures::channel::mpsc::unbounded;
 use futures::channel::mpsc::UnboundedSender;
 use futures::channel::oneshot;
 use futures::future;
+use futures::future::LocalBoxFuture;
 use futures::FutureExt;
 use futures::Stream;
 use futures::StreamExt;
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
 use std::future::Future;
 
 
@@ -94,6 +109,11 @@ impl Default for IdGenerator {
 pub enum Event<N> {
     /// Transport has been closed.
     Closed,
+    /// Transport is trying to reestablish the lost connection. The attempts are numbered from 1.
+    #[allow(missing_docs)]
+    Reconnecting { attempt: usize },
+    /// Transport has reestablished the lost connection.
+    Reconnected,
     /// Error occurred.
     Error(HandlingError),
     /// Notification received.
@@ -111,6 +131,17 @@ pub enum Event<N> {
 /// from this container.
 pub type OngoingCalls = HashMap<Id, oneshot::Sender<ReplyMessage>>;
 
+/// A task sending back the response to a call initiated by the peer. The tasks are run by the
+/// `Handler`'s runner.
+pub type ReplyTask = LocalBoxFuture<'static, ()>;
+
+/// Handle to send the progress reported for a call to its stream.
+pub type ProgressSink = UnboundedSender<serde_json::Value>;
+
+/// Container that stores the sinks of the progress notifications, for the ongoing calls reporting
+/// their progress.
+pub type ProgressSinks = HashMap<ProgressToken, ProgressSink>;
+
 
 
 // ===============
@@ -125,6 +156,9 @@ pub type OngoingCalls = HashMap<Id, oneshot::Sender<ReplyMessage>>;
 ///
 /// Notifications and internal messages are emitted using the `events` stream.
 ///
+/// The calls initiated by the peer are answered by the handlers registered with
+/// `register_method`. See the `Dispatcher` docs for details.
+///
 /// `Notification` is a type for notifications. It should implement
 /// `DeserializeOwned` and deserialize from JSON maps with `method` and `params`
 /// fields.
@@ -145,6 +179,12 @@ pub struct HandlerData<Notification> {
     id_generator    : IdGenerator,
     /// Transports text messages between this handler and the peer.
     transport       : Box<dyn Transport>,
+    /// Handlers of the calls initiated by the peer.
+    dispatcher      : Dispatcher,
+    /// Handle to send the tasks replying to the peer's calls to the runner.
+    reply_tasks     : Option<UnboundedSender<ReplyTask>>,
+    /// Sinks of the progress notifications of the ongoing calls.
+    progress_sinks  : ProgressSinks,
 }
 
 
@@ -167,7 +207,41 @@ impl<Notification> {
     /// Removes all the ongoing requests. This will be recognized by the `Future`s
     /// as losing connection error.
     pub fn clear_ongoing_requests(&mut self) {
-        self.ongoing_calls.clear()
+        self.ongoing_calls.clear();
+        self.progress_sinks.clear();
+    }
+
+    /// Removes the request which will not await the reply anymore, because it was cancelled, timed
+    /// out, or its `Future` was dropped. If the request was still ongoing and `notify_peer` is set,
+    /// the peer is sent a `$/cancelRequest` notification.
+    pub fn abandon_request(&mut self, id:Id, notify_peer:bool) {
+        let was_ongoing = self.ongoing_calls.remove(&id).is_some();
+        self.transport.forget_request(id);
+        if was_ongoing && notify_peer {
+            let params  = CancelParams{id};
+            let message = messages::Message::new_notification(CANCEL_REQUEST_METHOD,params);
+            let serialized_message = serde_json::to_string(&message).unwrap();
+            // If the transport is closed, the peer will not process the request anyway.
+            self.transport.send_text(&serialized_message).ok();
+        }
+    }
+
+    /// Routes the progress notifications with the given token to the sink.
+    pub fn insert_progress_sink(&mut self, token:ProgressToken, sink:ProgressSink) {
+        self.progress_sinks.insert(token,sink);
+    }
+
+    /// Stops routing the progress notifications with the given token. Finishes the stream of the
+    /// reported progress.
+    pub fn remove_progress_sink(&mut self, token:ProgressToken) {
+        self.progress_sinks.remove(&token);
+    }
+
+    /// Sends the reported progress to the sink registered for its token. Returns `false` if there
+    /// is no such sink.
+    pub fn report_progress(&self, params:ProgressParams) -> bool {
+        let sink = self.progress_sinks.get(&params.token);
+        sink.map(|sink| channel::emit(sink,params.value)).is_some()
     }
 
     /// Obtains an id for a new request to be made.
@@ -206,6 +280,23 @@ impl<Notification> {
     pub fn set_timeout(&mut self, timeout:Duration) {
         self.timeout = timeout;
     }
+
+    /// Replace the handlers of the calls initiated by the peer.
+    pub fn set_dispatcher(&mut self, dispatcher:Dispatcher) {
+        self.dispatcher = dispatcher;
+    }
+
+    /// Start handling a call initiated by the peer. Returns a `Future` yielding the response.
+    pub fn dispatch(&self, request:IncomingRequest) -> ResponseFuture {
+        self.dispatcher.dispatch(request)
+    }
+
+    /// Sends a task replying to the peer's call to the runner.
+    pub fn spawn_reply_task(&self, task:ReplyTask) {
+        if let Some(reply_tasks) = self.reply_tasks.as_ref() {
+            channel::emit(reply_tasks,task)
+        }
+    }
 }
 } // shared!
 
@@ -238,6 +329,9 @@ impl<Notification> Handler<Notification> {
             id_generator:    IdGenerator::new(),
             transport:       Box::new(transport),
             outgoing_events: None,
+            dispatcher:      default(),
+            reply_tasks:     None,
+            progress_sinks:  default(),
         };
         Handler { rc: Rc::new(RefCell::new(data)) }
     }
@@ -251,7 +345,20 @@ impl<Notification> Handler<Notification> {
         let id = self.generate_new_id();
         let message = api::into_request_message(input, id);
         let serialized_message = serde_json::to_string(&message).unwrap();
-        self.open_request_with_message(id, &serialized_message)
+        self.open_request_with_message(id, &serialized_message, default())
+    }
+
+    /// Sends a request to the peer, like `open_request`, with the call controlled by the given
+    /// options. See the `CallOptions` docs for details.
+    pub fn open_request_with_options<In: api::RemoteMethodCall>(
+        &self,
+        input: In,
+        options: CallOptions,
+    ) -> impl Future<Output = Result<In::Returned>> {
+        let id = self.generate_new_id();
+        let message = api::into_request_message(input, id);
+        let serialized_message = serde_json::to_string(&message).unwrap();
+        self.open_request_with_message(id, &serialized_message, options)
     }
 
     /// Sends a request to the peer and returns a `Future` that shall yield a reply message.
@@ -273,7 +380,7 @@ impl<Notification> Handler<Notification> {
         let id = self.generate_new_id();
         let message = crate::messages::Message::new_request(id, method_name, input);
         let serialized_message = serde_json::to_string(&message).unwrap();
-        self.open_request_with_message(id, &serialized_message)
+        self.open_request_with_message(id, &serialized_message, default())
     }
 
     /// Sends a request to the peer and returns a `Future` that shall yield a reply message.
@@ -284,26 +391,91 @@ impl<Notification> Handler<Notification> {
         &self,
         id: Id,
         message_json: &str,
+        options: CallOptions,
     ) -> impl Future<Output = Result<Returned>> {
-        let (sender, receiver) = oneshot::channel::<ReplyMessage>();
-        let ret = receiver.map(|result_or_cancel| {
-            let result = result_or_cancel?;
-            decode_result(result)
-        });
-
-        self.insert_ongoing_request(id, sender);
+        let ret = self.expect_reply(id, options);
         if self.send_text_message(message_json).is_err() {
             // If message cannot be send, future ret must be cancelled.
             self.remove_ongoing_request(id);
         }
+        ret
+    }
 
-        let millis = self.timeout().as_millis();
-        future::select(ret, sleep(self.timeout()).boxed_local()).map(move |either| match either {
-            future::Either::Left((x, _)) => x,
-            future::Either::Right((_, _)) => Err(RpcError::TimeoutError { millis }),
+    /// Sends a batch of requests to the peer in a single message, and returns a `Future` for each
+    /// of them, in the same order. Each `Future` shall yield the reply to its request, decoded into
+    /// the expected type.
+    pub fn open_batch_request<In: api::RemoteMethodCall>(
+        &self,
+        inputs: impl IntoIterator<Item = In>,
+    ) -> Vec<impl Future<Output = Result<In::Returned>>> {
+        let messages = inputs
+            .into_iter()
+            .map(|input| api::into_request_message(input, self.generate_new_id()))
+            .collect_vec();
+        if messages.is_empty() {
+            // An empty batch is not a valid JSON-RPC message.
+            return vec![];
+        }
+        let ids = messages.iter().map(|message| message.id).collect_vec();
+        let serialized_message = serde_json::to_string(&messages).unwrap();
+        let ret = ids.iter().map(|id| self.expect_reply(*id, default())).collect_vec();
+        if self.send_text_message(&serialized_message).is_err() {
+            for id in ids {
+                self.remove_ongoing_request(id);
+            }
+        }
+        ret
+    }
+
+    /// Registers an ongoing request with the given id, and returns a `Future` that shall yield the
+    /// reply to it, or an error if the call times out or is cancelled. The request is abandoned
+    /// when the `Future` is dropped before receiving the reply.
+    fn expect_reply<Returned: DeserializeOwned>(
+        &self,
+        id: Id,
+        options: CallOptions,
+    ) -> impl Future<Output = Result<Returned>> {
+        let (sender, receiver) = oneshot::channel::<ReplyMessage>();
+        let reply = receiver.map(|result_or_cancel| {
+            let result = result_or_cancel?;
+            decode_result(result)
+        });
+        self.insert_ongoing_request(id, sender);
+        let progress = options.progress.map(|(token, sink)| {
+            self.insert_progress_sink(token.clone(), sink);
+            token
+        });
+        let notify_cancel = options.notify_cancel;
+        let call = OngoingCall { data: Rc::downgrade(&self.rc), id, progress, notify_cancel };
+
+        let timeout = options.timeout.unwrap_or_else(|| self.timeout());
+        let millis = timeout.as_millis();
+        let timeout = sleep(timeout).map(move |()| Err(RpcError::TimeoutError { millis }));
+        let cancelled = match options.cancellation {
+            Some(token) => token.cancelled().boxed_local(),
+            None => future::pending().boxed_local(),
+        };
+        let cancelled = cancelled.map(|()| Err(RpcError::Cancelled));
+        let interrupted = future::select(timeout.boxed_local(), cancelled);
+        let interrupted = interrupted.map(|either| either.factor_first().0);
+        future::select(reply, interrupted).map(move |either| {
+            // Finishes the call, removing it from the ongoing ones if no reply was received.
+            drop(call);
+            either.factor_first().0
         })
     }
 
+    /// Registers a handler for the calls of the method `In::NAME` initiated by the peer. See the
+    /// `Dispatcher` docs for details.
+    pub fn register_method<In, F, Fut>(&self, handler: F)
+    where
+        In: api::RemoteMethodCall + DeserializeOwned + 'static,
+        In::Returned: Serialize,
+        F: Fn(In) -> Fut + 'static,
+        Fut: Future<Output = Reply<In::Returned>> + 'static, {
+        self.rc.borrow_mut().dispatcher.register(handler)
+    }
+
     /// Deal with `Response` message from the peer.
     ///
     /// It shall be either matched with an open request or yield an error.
@@ -321,8 +493,14 @@ impl<Notification> Handler<Notification> {
     ///
     /// If possible, emits a message with notification. In case of failure,
     /// emits relevant error.
+    ///
+    /// The `$/progress` notifications reporting the progress of an ongoing call are routed to the
+    /// stream of that call instead.
     pub fn process_notification(&self, message: messages::Notification<serde_json::Value>)
     where Notification: DeserializeOwned {
+        if self.route_progress(&message.0) {
+            return;
+        }
         match serde_json::from_value(message.0) {
             Ok(notification) => {
                 let event = Event::Notification(notification);
@@ -335,20 +513,95 @@ impl<Notification> Handler<Notification> {
         }
     }
 
+    /// If the notification reports the progress of an ongoing call, sends it to the call's
+    /// progress stream and returns `true`.
+    fn route_progress(&self, notification: &serde_json::Value) -> bool {
+        let method = notification.get("method").and_then(|method| method.as_str());
+        let params = notification.get("params").filter(|_| method == Some(PROGRESS_METHOD));
+        let params = params.and_then(|params| ProgressParams::deserialize(params).ok());
+        params.map_or(false, |params| self.report_progress(params))
+    }
+
+    /// Deal with `Request` message from the peer.
+    ///
+    /// The request is passed to the registered handler, and the response is sent back once the
+    /// handler's reply is ready.
+    pub fn process_request(&self, message: IncomingRequest)
+    where Notification: 'static {
+        let response = self.dispatch(message);
+        self.reply_with(response);
+    }
+
     /// Deal with incoming text message from the peer.
     ///
-    /// The message must conform either to the `Response` or to the
-    /// `Notification` JSON-serialized format. Otherwise, an error is raised.
+    /// The message must conform either to the `Response`, `Request` or
+    /// `Notification` JSON-serialized format, or be a batch of such messages.
+    /// Otherwise, an error is raised. The requests of a batch are answered with
+    /// a single batch of responses. The malformed messages of a batch, and an
+    /// empty batch, are answered with the `INVALID_REQUEST` error.
     pub fn process_incoming_message(&self, message: String)
-    where Notification: DeserializeOwned {
-        match messages::decode_incoming_message(&message) {
-            Ok(messages::IncomingMessage::Response(response)) => self.process_response(response),
-            Ok(messages::IncomingMessage::Notification(notification)) =>
+    where Notification: DeserializeOwned + 'static {
+        match messages::decode_incoming_batch(&message) {
+            Ok(messages::Batch::Single(Ok(message))) => self.process_incoming_payload(message),
+            Ok(messages::Batch::Single(Err(err))) | Err(err) =>
+                self.error_occurred(HandlingError::InvalidMessage(err)),
+            Ok(messages::Batch::Batch(messages)) if messages.is_empty() => {
+                let response = messages::Message::new(InvalidRequest::new("empty batch"));
+                self.reply_with(future::ready(response));
+            }
+            Ok(messages::Batch::Batch(messages)) => {
+                let mut responses = vec![];
+                for message in messages {
+                    match message {
+                        Ok(messages::IncomingMessage::Request(request)) => {
+                            let response = self.dispatch(request).map(BatchResponse::Response);
+                            responses.push(response.boxed_local());
+                        }
+                        Ok(other) => self.process_incoming_payload(other),
+                        Err(err) => {
+                            let response = messages::Message::new(InvalidRequest::new(err));
+                            let response = BatchResponse::InvalidRequest(response);
+                            responses.push(future::ready(response).boxed_local());
+                        }
+                    }
+                }
+                if !responses.is_empty() {
+                    self.reply_with(future::join_all(responses));
+                }
+            }
+        }
+    }
+
+    fn process_incoming_payload(&self, message: messages::IncomingMessage)
+    where Notification: DeserializeOwned + 'static {
+        match message {
+            messages::IncomingMessage::Response(response) => self.process_response(response),
+            messages::IncomingMessage::Request(request) => self.process_request(request),
+            messages::IncomingMessage::Notification(notification) =>
                 self.process_notification(notification),
-            Err(err) => self.error_occurred(HandlingError::InvalidMessage(err)),
         }
     }
 
+    /// Spawns a task sending the response yielded by the given `Future` to the peer. The task
+    /// holds only a weak handle to the data, so it does not keep the `Handler` alive.
+    fn reply_with<Response: Serialize + 'static>(
+        &self,
+        response: impl Future<Output = Response> + 'static,
+    ) where
+        Notification: 'static,
+    {
+        let weak_data = Rc::downgrade(&self.rc);
+        let task = response.map(move |response| {
+            if let Some(rc) = weak_data.upgrade() {
+                let handler = Handler { rc };
+                let serialized_message = serde_json::to_string(&response).unwrap();
+                // If the transport is closed, the peer will not expect the response anyway.
+                handler.send_text_message(&serialized_message).ok();
+            }
+        });
+        self.spawn_reply_task(task.boxed_local());
+    }
+
     /// With with a handling error. Uses `on_error` callback to notify the
     /// owner.
     pub fn error_occurred(&self, error: HandlingError) {
@@ -359,7 +612,7 @@ impl<Notification> Handler<Notification> {
     ///
     /// Each event either completes a requests or is translated into `Event`.
     pub fn process_event(&self, event: TransportEvent)
-    where Notification: DeserializeOwned {
+    where Notification: DeserializeOwned + 'static {
         match event {
             TransportEvent::TextMessage(msg) => self.process_incoming_message(msg),
             TransportEvent::BinaryMessage(data) =>
@@ -370,6 +623,15 @@ impl<Notification> Handler<Notification> {
                 self.clear_ongoing_requests();
                 self.emit_event(Event::Closed);
             }
+            TransportEvent::Reconnecting { attempt } =>
+                self.emit_event(Event::Reconnecting { attempt }),
+            TransportEvent::Reconnected { dropped_requests } => {
+                // The dropped requests will not get a reply, so their futures are cancelled.
+                for id in dropped_requests {
+                    self.remove_ongoing_request(id);
+                }
+                self.emit_event(Event::Reconnected);
+            }
         }
     }
 
@@ -382,12 +644,16 @@ impl<Notification> Handler<Notification> {
     /// dropping the `Transport` itself.
     ///
     /// It is expected that upon setting up the `Handler`, this future shall be
-    /// passed to the main executor.
+    /// passed to the main executor. The future also runs the tasks replying to
+    /// the calls initiated by the peer.
     pub fn runner(&mut self) -> impl Future<Output = ()>
     where Notification: DeserializeOwned + 'static {
         let event_receiver = self.transport_event_stream();
+        let (reply_task_transmitter, reply_task_receiver) = unbounded();
+        self.rc.borrow_mut().reply_tasks = Some(reply_task_transmitter);
+        let reply_tasks = reply_task_receiver.for_each_concurrent(None, |task: ReplyTask| task);
         let weak_data = Rc::downgrade(&self.rc);
-        event_receiver.for_each(move |event: TransportEvent| {
+        let events = event_receiver.for_each(move |event: TransportEvent| {
             let data_opt = weak_data.clone().upgrade();
             let handler_opt = data_opt.map(|rc| Handler { rc });
             if let Some(handler) = handler_opt {
@@ -396,6 +662,35 @@ impl<Notification> Handler<Notification> {
                 // If the data is inaccessible, it is ok to just drop the event here.
             }
             futures::future::ready(())
-        })
+        });
+        future::select(events.boxed_local(), reply_tasks.boxed_local()).map(|_| ())
+    }
+}
+
+
+
+// ===================
+// === OngoingCall ===
+// ===================
+
+/// A guard of a call awaiting the reply, finishing the call when dropped. If the reply was not
+/// received, the call is abandoned, as described in the `CallOptions` docs.
+#[derive(Debug)]
+struct OngoingCall<Notification> {
+    data:          Weak<RefCell<HandlerData<Notification>>>,
+    id:            Id,
+    progress:      Option<ProgressToken>,
+    notify_cancel: bool,
+}
+
+impl<Notification> Drop for OngoingCall<Notification> {
+    fn drop(&mut self) {
+        if let Some(rc) = self.data.upgrade() {
+            let handler = Handler { rc };
+            if let Some(token) = self.progress.take() {
+                handler.remove_progress_sink(token);
+            }
+            handler.abandon_request(self.id, self.notify_cancel);
+        }
     }
 }
@@ -1,5 +1,8 @@
 //! This is a library aimed to facilitate implementing JSON-RPC protocol
-//! clients. The main type is `Handler` that a client should build upon.
+//! clients. The main type is `Handler` that a client should build upon. The
+//! calls initiated by the peer are answered by handlers registered in the
+//! `Dispatcher`. A single call can be controlled with `CallOptions`, allowing
+//! to cancel it or to receive the progress reported by the peer.
 
 // === Features ===
 #![feature(trait_alias)]
@@ -16,6 +19,7 @@
 #![warn(unused_qualifications)]
 #![warn(missing_copy_implementations)]
 #![warn(missing_debug_implementations)]
+#![recursion_limit = "256"]
 
 
 // ==============
@@ -23,6 +27,8 @@
 // ==============
 
 pub mod api;
+pub mod call;
+pub mod dispatcher;
 pub mod error;
 pub mod handler;
 pub mod log;
@@ -33,6 +39,9 @@ pub mod transport;
 
 pub use api::RemoteMethodCall;
 pub use api::Result;
+pub use call::CallOptions;
+pub use call::CancellationToken;
+pub use dispatcher::Dispatcher;
 pub use enso_prelude as prelude;
 pub use enso_profiler;
 pub use enso_profiler_data;
@@ -189,13 +189,53 @@ pub struct Error<Payload = serde_json::Value> {
     pub data:    Option<Payload>,
 }
 
-/// A message that can come from Server to Client — either a response or
-/// notification.
+impl Error {
+    /// Construct an error that bears no optional data.
+    pub fn new(code: i64, message: impl Into<String>) -> Error {
+        Error { code, message: message.into(), data: None }
+    }
+}
+
+/// Error codes defined by the JSON-RPC 2.0 specification. Codes from -32000 to -32099 are reserved
+/// for implementation-defined server errors, other codes may be used by the applications.
+#[allow(missing_docs)]
+pub mod error_code {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+}
+
+/// A response to a message which is not a valid request. As the id of such message cannot be
+/// determined, the response's id is always `null`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct InvalidRequest {
+    /// Always `null`.
+    pub id:    (),
+    /// The error with the `INVALID_REQUEST` code.
+    pub error: Error,
+}
+
+impl InvalidRequest {
+    /// Constructor. The `details` describe why the message is not a valid request.
+    pub fn new(details: impl Display) -> Self {
+        let message = format!("Invalid request: {details}");
+        InvalidRequest { id: (), error: Error::new(error_code::INVALID_REQUEST, message) }
+    }
+}
+
+/// A request initiated by the peer, with params in JSON form.
+pub type IncomingRequest = Request<MethodCall<serde_json::Value>>;
+
+/// A message that can come from the peer — a response, a request or a notification.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum IncomingMessage {
     /// A response to a call made by client.
     Response(Response<serde_json::Value>),
+    /// A call initiated by the peer, that expects a response.
+    Request(IncomingRequest),
     /// A notification call (initiated by the server).
     Notification(Notification<serde_json::Value>),
 }
@@ -203,13 +243,33 @@ pub enum IncomingMessage {
 /// Partially decodes incoming message.
 ///
 /// This checks if has `jsonrpc` version string, and whether it is a
-/// response or a notification.
+/// response, a request or a notification.
 pub fn decode_incoming_message(message: &str) -> serde_json::Result<IncomingMessage> {
     use serde_json::from_str;
-    use serde_json::from_value;
-    use serde_json::Value;
-    let message = from_str::<Message<Value>>(message)?;
-    from_value::<IncomingMessage>(message.payload)
+    let message = from_str::<Message<serde_json::Value>>(message)?;
+    decode_incoming_payload(message)
+}
+
+/// Partially decodes incoming message, that may be a batch of messages.
+///
+/// Like `decode_incoming_message`, but accepts also a JSON array of messages. The messages of a
+/// batch are decoded separately, so a malformed one does not prevent handling the others.
+pub fn decode_incoming_batch(
+    message: &str,
+) -> serde_json::Result<Batch<serde_json::Result<IncomingMessage>>> {
+    let batch = serde_json::from_str::<Batch<serde_json::Value>>(message)?;
+    Ok(batch.map(|message| decode_incoming_payload(serde_json::from_value(message)?)))
+}
+
+fn decode_incoming_payload(
+    message: Message<serde_json::Value>,
+) -> serde_json::Result<IncomingMessage> {
+    let message = serde_json::from_value::<IncomingMessage>(message.payload)?;
+    match &message {
+        IncomingMessage::Notification(Notification(call)) if !call["method"].is_string() =>
+            Err(serde::de::Error::missing_field("method")),
+        _ => Ok(message),
+    }
 }
 
 /// Message from server to client.
@@ -227,6 +287,67 @@ pub struct MethodCall<In> {
 
 
 
+// =============
+// === Batch ===
+// =============
+
+/// A single message, or a batch of messages sent together as a JSON array.
+///
+/// The peer processes the messages of a batch in any order, and replies to a batch of requests
+/// with a batch of responses. The notifications of a batch get no responses.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Batch<T> {
+    /// A batch of messages.
+    Batch(Vec<T>),
+    /// A single message.
+    Single(T),
+}
+
+impl<T> Batch<T> {
+    /// All the messages, in the order they were sent.
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            Batch::Batch(messages) => messages,
+            Batch::Single(message) => vec![message],
+        }
+    }
+
+    /// Convert each of the messages.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Batch<U> {
+        match self {
+            Batch::Batch(messages) => Batch::Batch(messages.into_iter().map(f).collect()),
+            Batch::Single(message) => Batch::Single(f(message)),
+        }
+    }
+
+    /// Convert each of the messages, failing if any conversion fails.
+    pub fn try_map<U, E>(
+        self,
+        mut f: impl FnMut(T) -> std::result::Result<U, E>,
+    ) -> std::result::Result<Batch<U>, E> {
+        match self {
+            Batch::Batch(messages) => {
+                let converted = messages.into_iter().map(f);
+                converted.collect::<std::result::Result<_, _>>().map(Batch::Batch)
+            }
+            Batch::Single(message) => f(message).map(Batch::Single),
+        }
+    }
+}
+
+/// A response to a message of a batch sent by the peer: either the reply to a request, or the error
+/// reported for a message which is not a valid request.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum BatchResponse {
+    Response(ResponseMessage<serde_json::Value>),
+    InvalidRequest(Message<InvalidRequest>),
+}
+
+
+
 // =============
 // === Tests ===
 // =============
@@ -363,4 +484,40 @@ mod tests {
             _ => panic!("Invalid decoding result of {text}: {decoding_result:?}"),
         }
     }
+
+    #[test]
+    fn decode_incoming_request() {
+        let text = r#"{"jsonrpc":"2.0","id":3,"method":"pow","params":{"i":2}}"#;
+        let expected = Request::new(Id(3), MethodCall {
+            method: "pow".into(),
+            params: serde_json::json!({ "i": 2 }),
+        });
+        assert_eq!(decode_incoming_message(text).unwrap(), IncomingMessage::Request(expected));
+    }
+
+    #[test]
+    fn decode_incoming_batch_text() {
+        let text = r#"[
+            {"jsonrpc":"2.0","id":1,"result":null},
+            {"jsonrpc":"2.0","method":"meow","params":{}},
+            {"jsonrpc":"2.0","id":2,"method":"pow","params":[]}
+        ]"#;
+        let batch = decode_incoming_batch(text).unwrap();
+        let Batch::Batch(messages) = batch else { panic!("Expected a batch: {batch:?}") };
+        assert!(matches!(messages[0], Ok(IncomingMessage::Response(Response { id: Id(1), .. }))));
+        assert!(matches!(messages[1], Ok(IncomingMessage::Notification(_))));
+        assert!(matches!(messages[2], Ok(IncomingMessage::Request(Request { id: Id(2), .. }))));
+
+        let text = r#"{"jsonrpc":"2.0","method":"meow","params":{}}"#;
+        let batch = decode_incoming_batch(text).unwrap();
+        assert!(matches!(batch, Batch::Single(Ok(IncomingMessage::Notification(_)))));
+
+        // The malformed messages of a batch are decoded separately.
+        let text = r#"[{"jsonrpc":"2.0"},{"jsonrpc":"2.0","method":"meow","params":{}}]"#;
+        let batch = decode_incoming_batch(text).unwrap();
+        let Batch::Batch(messages) = batch else { panic!("Expected a batch: {batch:?}") };
+        assert!(messages[0].is_err());
+        assert!(matches!(messages[1], Ok(IncomingMessage::Notification(_))));
+        assert!(decode_incoming_batch("[").is_err());
+    }
 }
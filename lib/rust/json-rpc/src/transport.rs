@@ -2,12 +2,27 @@
 
 use crate::prelude::*;
 
+use crate::messages::Id;
+
 use failure::Error;
 use futures::channel::mpsc::unbounded;
 use futures::channel::mpsc::UnboundedReceiver;
 use futures::channel::mpsc::UnboundedSender;
 
 
+// ==============
+// === Export ===
+// ==============
+
+pub mod reconnecting;
+
+pub use reconnecting::ReconnectingTransport;
+
+
+
+// =================
+// === Transport ===
+// =================
 
 /// A transport that facilitate JSON-RPC protocol.
 ///
@@ -26,6 +41,11 @@ pub trait Transport: Debug {
     /// Set up a channel which shall be used to receive events from the `Transport`.
     fn set_event_transmitter(&mut self, transmitter: UnboundedSender<TransportEvent>);
 
+    /// Inform the transport that the request with the given id does not await the reply anymore,
+    /// e.g. because it was cancelled or timed out. The transports tracking the unanswered
+    /// requests, like the `ReconnectingTransport`, stop tracking it. Does nothing by default.
+    fn forget_request(&mut self, _id: Id) {}
+
     /// Sets up a stream's receiver yielding `TransportEvent`s.
     fn establish_event_stream(&mut self) -> UnboundedReceiver<TransportEvent> {
         let (event_transmitter, event_receiver) = unbounded();
@@ -46,4 +66,12 @@ pub enum TransportEvent {
     /// A socket has been closed by the peer.
     /// This event may be also emitted when reconnecting has failed.
     Closed,
+    /// The connection has been lost, and the transport is trying to reestablish it. Emitted
+    /// before each attempt, numbered from 1.
+    #[allow(missing_docs)]
+    Reconnecting { attempt: usize },
+    /// The connection has been reestablished. The unanswered requests listed in
+    /// `dropped_requests` were not resent, so they will not get a reply.
+    #[allow(missing_docs)]
+    Reconnected { dropped_requests: Vec<Id> },
 }
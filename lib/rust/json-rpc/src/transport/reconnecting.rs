@@ -0,0 +1,393 @@
+//! Module providing `ReconnectingTransport`, a `Transport` reestablishing the lost connection and
+//! resending the requests which were left unanswered.
+
+use crate::prelude::*;
+
+use crate::ensogl::sleep;
+use crate::ensogl::Duration;
+use crate::messages::Batch;
+use crate::messages::Id;
+use crate::transport::Transport;
+use crate::transport::TransportEvent;
+
+use failure::Error;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
+use futures::future::LocalBoxFuture;
+use futures::StreamExt;
+use std::future::Future;
+
+
+
+// ===============
+// === Backoff ===
+// ===============
+
+/// The exponential backoff of the reconnection attempts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Backoff {
+    /// The delay before the first attempt.
+    pub initial_delay: Duration,
+    /// The factor by which the delay grows after each failed attempt.
+    pub multiplier:    f64,
+    /// The limit of the delay between attempts.
+    pub max_delay:     Duration,
+    /// The number of failed attempts after which the transport gives up. If `None`, it never does.
+    pub max_attempts:  Option<usize>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        let initial_delay = Duration::from_millis(500);
+        let max_delay = Duration::from_secs(30);
+        Backoff { initial_delay, multiplier: 2.0, max_delay, max_attempts: None }
+    }
+}
+
+impl Backoff {
+    /// The delay before the given attempt, numbered from 1. Returns `None` if the transport should
+    /// give up before this attempt.
+    pub fn delay(&self, attempt: usize) -> Option<Duration> {
+        let exceeded = self.max_attempts.map_or(false, |max| attempt > max);
+        (attempt > 0 && !exceeded).as_some_from(|| {
+            let exponent = i32::try_from(attempt - 1).unwrap_or(i32::MAX);
+            let secs = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+            Duration::from_secs_f64(secs.min(self.max_delay.as_secs_f64()).max(0.0))
+        })
+    }
+}
+
+
+
+// ======================
+// === PendingRequest ===
+// ======================
+
+/// A request sent through the `ReconnectingTransport`, which has not been answered yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingRequest {
+    /// The id of the request.
+    pub id:      Id,
+    /// The name of the called method.
+    pub method:  String,
+    /// The serialized request, sent again if the request is resent.
+    pub message: String,
+}
+
+impl PendingRequest {
+    /// Get the request from an outgoing message, if it is one.
+    fn from_json(json: serde_json::Value) -> Option<Self> {
+        let id = serde_json::from_value(json.get("id")?.clone()).ok()?;
+        let method = json.get("method")?.as_str()?.to_owned();
+        let message = json.to_string();
+        Some(PendingRequest { id, method, message })
+    }
+}
+
+/// Get the id of the request answered by the incoming message, if it is a response.
+fn response_id(json: &serde_json::Value) -> Option<Id> {
+    let is_request = json.get("method").is_some();
+    let id = json.get("id").filter(|_| !is_request)?;
+    serde_json::from_value(id.clone()).ok()
+}
+
+/// Decode a text message as a batch of JSON values. Returns an empty batch if the message is not
+/// valid JSON, as such messages are neither tracked nor needed to be.
+fn decode_batch(message: &str) -> Vec<serde_json::Value> {
+    let batch = serde_json::from_str::<Batch<serde_json::Value>>(message);
+    batch.map(Batch::into_vec).unwrap_or_default()
+}
+
+
+
+// ====================
+// === ResendPolicy ===
+// ====================
+
+/// Decides whether the unanswered request should be resent after reconnecting. The requests which
+/// are not resent are reported in the `TransportEvent::Reconnected` event.
+pub trait ResendPolicy = Fn(&PendingRequest) -> bool + 'static;
+
+/// The policy resending all the unanswered requests. Note that a lost request might have been
+/// handled by the peer before the connection was lost, so it is safe only if no request changes
+/// the peer's state.
+pub fn resend_all(_request: &PendingRequest) -> bool {
+    true
+}
+
+/// The policy resending none of the unanswered requests. This is the default policy.
+pub fn resend_none(_request: &PendingRequest) -> bool {
+    false
+}
+
+/// The policy resending only the requests of the given methods. These should be the methods which
+/// are safe to call again, i.e. which do not change the peer's state.
+pub fn resend_methods<S: Into<String>>(methods: impl IntoIterator<Item = S>) -> impl ResendPolicy {
+    let methods: HashSet<String> = methods.into_iter().map(Into::into).collect();
+    move |request: &PendingRequest| methods.contains(&request.method)
+}
+
+
+
+// =================
+// === Connector ===
+// =================
+
+/// A `Future` yielding a new connection.
+pub type ConnectionFuture = LocalBoxFuture<'static, Result<Box<dyn Transport>, Error>>;
+
+/// Establishes a new connection to the peer, replacing the lost one.
+pub trait Connector = Fn() -> ConnectionFuture + 'static;
+
+
+
+// =============
+// === Model ===
+// =============
+
+struct Model {
+    /// The current connection. `None` while reconnecting.
+    connection:        Option<Box<dyn Transport>>,
+    connector:         Rc<dyn Fn() -> ConnectionFuture>,
+    backoff:           Backoff,
+    resend_policy:     Box<dyn Fn(&PendingRequest) -> bool>,
+    /// The unanswered requests, in the order of sending.
+    pending:           Vec<PendingRequest>,
+    event_transmitter: Option<UnboundedSender<TransportEvent>>,
+    /// Whether the transport has given up reconnecting.
+    closed:            bool,
+}
+
+impl Debug for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Model")
+            .field("connection", &self.connection)
+            .field("backoff", &self.backoff)
+            .field("pending", &self.pending)
+            .field("closed", &self.closed)
+            .finish()
+    }
+}
+
+impl Model {
+    fn emit(&self, event: TransportEvent) {
+        if let Some(transmitter) = self.event_transmitter.as_ref() {
+            channel::emit(transmitter, event)
+        }
+    }
+
+    /// Forwards the event of the current connection, noting the answered requests.
+    fn forward(&mut self, event: TransportEvent) {
+        if let TransportEvent::TextMessage(message) = &event {
+            let answered = decode_batch(message).iter().filter_map(response_id).collect_vec();
+            self.pending.retain(|request| !answered.contains(&request.id));
+        }
+        self.emit(event)
+    }
+
+    /// Sets up the stream of the current connection's events.
+    fn connection_events(&mut self) -> Option<UnboundedReceiver<TransportEvent>> {
+        self.connection.as_mut().map(|connection| connection.establish_event_stream())
+    }
+
+    /// Replaces the lost connection with the new one, and resends the unanswered requests chosen
+    /// by the policy. The other requests are forgotten.
+    fn reconnected(&mut self, mut connection: Box<dyn Transport>) {
+        let pending = mem::take(&mut self.pending);
+        let (resent, dropped): (Vec<_>, Vec<_>) =
+            pending.into_iter().partition(|request| (self.resend_policy)(request));
+        let dropped_requests = dropped.into_iter().map(|request| request.id).collect();
+        self.emit(TransportEvent::Reconnected { dropped_requests });
+        for request in &resent {
+            // If the new connection is lost too, the requests stay pending until the next one.
+            connection.send_text(&request.message).ok();
+        }
+        self.pending = resent;
+        self.connection = Some(connection);
+    }
+
+    /// Gives up reconnecting. All the unanswered requests are forgotten.
+    fn give_up(&mut self) {
+        self.closed = true;
+        self.pending.clear();
+        self.emit(TransportEvent::Closed);
+    }
+}
+
+
+
+// =============================
+// === ReconnectingTransport ===
+// =============================
+
+/// A `Transport` wrapping a connection to the peer, which reestablishes the connection when it is
+/// lost, instead of closing.
+///
+/// When the wrapped connection is closed, new connections are made by the `Connector`, with
+/// delays defined by the `Backoff`. Before each attempt, the `TransportEvent::Reconnecting` event
+/// is emitted, and after a successful one, `TransportEvent::Reconnected`. If the transport gives
+/// up, `TransportEvent::Closed` is emitted.
+///
+/// The transport tracks the requests sent through it until they are answered, or until the
+/// `Handler` forgets them because they were cancelled or timed out. After reconnecting, the
+/// requests chosen by the `ResendPolicy` are sent again, and the other ones are reported as
+/// dropped, which makes the `Handler` fail their calls. The requests sent while reconnecting are
+/// held until the connection is reestablished, while sending any other messages fails. Note that
+/// as the transport only sees the serialized messages, it decodes them to track the requests.
+///
+/// The reconnection is driven by the `runner` future, which should be passed to the executor.
+#[derive(Clone, CloneRef, Debug)]
+pub struct ReconnectingTransport {
+    model: Rc<RefCell<Model>>,
+}
+
+impl ReconnectingTransport {
+    /// Constructor. The `connection` must be functional, and the `connector` shall establish the
+    /// new connections replacing it. By default no unanswered request is resent after
+    /// reconnecting, as the transport cannot tell which requests are safe to send again; see
+    /// [`Self::set_resend_policy`].
+    pub fn new(connection: impl Transport + 'static, connector: impl Connector) -> Self {
+        let model = Model {
+            connection:        Some(Box::new(connection)),
+            connector:         Rc::new(connector),
+            backoff:           default(),
+            resend_policy:     Box::new(resend_none),
+            pending:           default(),
+            event_transmitter: None,
+            closed:            false,
+        };
+        ReconnectingTransport { model: Rc::new(RefCell::new(model)) }
+    }
+
+    /// Set the backoff of the reconnection attempts.
+    pub fn set_backoff(&self, backoff: Backoff) {
+        self.model.borrow_mut().backoff = backoff;
+    }
+
+    /// Set the policy deciding which unanswered requests are resent after reconnecting.
+    pub fn set_resend_policy(&self, policy: impl ResendPolicy) {
+        self.model.borrow_mut().resend_policy = Box::new(policy);
+    }
+
+    /// The requests sent through this transport which have not been answered yet.
+    pub fn pending_requests(&self) -> Vec<PendingRequest> {
+        self.model.borrow().pending.clone()
+    }
+
+    /// Check if the transport is currently reconnecting.
+    pub fn is_reconnecting(&self) -> bool {
+        self.model.borrow().connection.is_none()
+    }
+
+    /// Returns a `Future` forwarding the events of the current connection, and reconnecting when
+    /// it is lost. It holds a weak handle to the transport, and finishes when the transport is
+    /// dropped or gives up reconnecting.
+    pub fn runner(&self) -> impl Future<Output = ()> {
+        let weak_model = Rc::downgrade(&self.model);
+        async move {
+            loop {
+                let events = weak_model.upgrade().and_then(|m| m.borrow_mut().connection_events());
+                let Some(mut events) = events else { return };
+                // The stream may also end without the `Closed` event, if the connection is dropped.
+                while let Some(event) = events.next().await {
+                    let Some(model) = weak_model.upgrade() else { return };
+                    match event {
+                        TransportEvent::Closed => break,
+                        event => model.borrow_mut().forward(event),
+                    }
+                }
+                if !Self::reconnect(&weak_model).await {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Makes the reconnection attempts until one succeeds or the backoff gives up. Returns `false`
+    /// in the latter case, or if the transport was dropped.
+    async fn reconnect(weak_model: &Weak<RefCell<Model>>) -> bool {
+        for attempt in 1.. {
+            let Some(model) = weak_model.upgrade() else { return false };
+            let delay = with(model.borrow_mut(), |mut model| {
+                model.connection = None;
+                let delay = model.backoff.delay(attempt);
+                match delay {
+                    Some(_) => model.emit(TransportEvent::Reconnecting { attempt }),
+                    None => model.give_up(),
+                }
+                delay
+            });
+            let Some(delay) = delay else { return false };
+            let connector = model.borrow().connector.clone();
+            drop(model);
+            sleep(delay).await;
+            match connector().await {
+                Ok(connection) => {
+                    let Some(model) = weak_model.upgrade() else { return false };
+                    model.borrow_mut().reconnected(connection);
+                    return true;
+                }
+                Err(error) => warn!("Reconnection attempt {attempt} failed: {error}"),
+            }
+        }
+        false
+    }
+}
+
+impl Transport for ReconnectingTransport {
+    fn send_text(&mut self, message: &str) -> Result<(), Error> {
+        let mut model = self.model.borrow_mut();
+        if model.closed {
+            return Err(SendError::Closed.into());
+        }
+        let requests = decode_batch(message).into_iter().filter_map(PendingRequest::from_json);
+        let pending_count = model.pending.len();
+        model.pending.extend(requests);
+        let has_requests = model.pending.len() > pending_count;
+        let sent = match model.connection.as_mut() {
+            Some(connection) => connection.send_text(message),
+            None => Err(SendError::Reconnecting.into()),
+        };
+        // The requests which could not be sent are resent once the connection is reestablished.
+        if has_requests {
+            Ok(())
+        } else {
+            sent
+        }
+    }
+
+    fn send_binary(&mut self, message: &[u8]) -> Result<(), Error> {
+        let mut model = self.model.borrow_mut();
+        let closed = model.closed;
+        match model.connection.as_mut() {
+            Some(connection) => connection.send_binary(message),
+            None if closed => Err(SendError::Closed.into()),
+            None => Err(SendError::Reconnecting.into()),
+        }
+    }
+
+    fn set_event_transmitter(&mut self, transmitter: UnboundedSender<TransportEvent>) {
+        self.model.borrow_mut().event_transmitter = Some(transmitter);
+    }
+
+    fn forget_request(&mut self, id: Id) {
+        self.model.borrow_mut().pending.retain(|request| request.id != id);
+    }
+}
+
+
+
+// =================
+// === SendError ===
+// =================
+
+/// Errors of sending messages through the `ReconnectingTransport`.
+#[derive(Clone, Copy, Debug, Fail)]
+pub enum SendError {
+    /// Only the requests can be sent while the connection is being reestablished.
+    #[fail(display = "Cannot send the message while reconnecting.")]
+    Reconnecting,
+    /// The transport has given up reconnecting.
+    #[fail(display = "The transport has been closed.")]
+    Closed,
+}
@@ -6,19 +6,28 @@ use json_rpc::prelude::*;
 use json_rpc::*;
 
 use enso_web::Duration;
+use futures::channel::oneshot;
 use futures::task::LocalSpawnExt;
 use futures::FutureExt;
 use futures::Stream;
 use json_rpc::api::RemoteMethodCall;
 use json_rpc::api::Result;
+use json_rpc::call;
 use json_rpc::error::HandlingError;
 use json_rpc::error::RpcError;
+use json_rpc::messages::error_code;
+use json_rpc::messages::BatchResponse;
 use json_rpc::messages::Id;
 use json_rpc::messages::Message;
 use json_rpc::messages::Version;
 use json_rpc::test_util::transport::mock::MockTransport;
+use json_rpc::transport::reconnecting::resend_all;
+use json_rpc::transport::reconnecting::resend_methods;
+use json_rpc::transport::reconnecting::Backoff;
+use json_rpc::transport::ReconnectingTransport;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::thread::sleep;
@@ -73,6 +82,8 @@ type MockRequestMessage = messages::RequestMessage<MockRequest>;
 
 type MockResponseMessage = messages::ResponseMessage<MockResponse>;
 
+type JsonResponseMessage = messages::ResponseMessage<serde_json::Value>;
+
 
 
 // ===================
@@ -304,3 +315,482 @@ fn test_handling_invalid_notification() {
         panic!("expected InvalidNotification error");
     }
 }
+
+
+
+// ============================
+// === Peer-Initiated Calls ===
+// ============================
+
+impl Fixture {
+    /// Register a handler answering the peer's `pow` calls immediately.
+    fn register_pow(&self) {
+        self.client.handler.register_method(|input: MockRequest| {
+            futures::future::ready(Ok(MockResponse { result: input.i * input.i }))
+        });
+    }
+
+    fn expect_error_response(&mut self, expected_code: i64) {
+        let response = self.transport.expect_json_message::<JsonResponseMessage>();
+        match &response.result {
+            messages::Result::Error { error } => assert_eq!(error.code, expected_code),
+            _ => panic!("Expected an error response, got {response:?}."),
+        }
+    }
+}
+
+#[test]
+fn test_answering_peer_call() {
+    let mut fixture = Fixture::new();
+    fixture.register_pow();
+    let request = Message::new_request(Id(7), MockRequest::NAME, MockRequest { i: 3 });
+    fixture.transport.mock_peer_json_message(request);
+    fixture.pool.run_until_stalled();
+
+    let response = fixture.transport.expect_json_message::<MockResponseMessage>();
+    assert_eq!(response, Message::new_success(Id(7), MockResponse { result: 9 }));
+}
+
+#[test]
+fn test_answering_peer_call_asynchronously() {
+    let mut fixture = Fixture::new();
+    let (sender, receiver) = oneshot::channel::<i64>();
+    let receiver = RefCell::new(Some(receiver));
+    fixture.client.handler.register_method(move |_: MockRequest| {
+        let receiver = receiver.borrow_mut().take().expect("pow called once");
+        receiver.map(|result| Ok(MockResponse { result: result.unwrap() }))
+    });
+    let request = Message::new_request(Id(1), MockRequest::NAME, MockRequest { i: 3 });
+    fixture.transport.mock_peer_json_message(request);
+    fixture.pool.run_until_stalled();
+
+    // The handler's reply is not ready, but other messages are still processed.
+    let mut fut = Box::pin(fixture.client.pow(2));
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    fixture.transport.mock_peer_json_message(pow_impl(request));
+    fixture.pool.run_until_stalled();
+    assert_eq!(fut.expect_ok(), 4);
+
+    sender.send(10).unwrap();
+    fixture.pool.run_until_stalled();
+    let response = fixture.transport.expect_json_message::<MockResponseMessage>();
+    assert_eq!(response, Message::new_success(Id(1), MockResponse { result: 10 }));
+}
+
+#[test]
+fn test_peer_call_errors() {
+    let mut fixture = Fixture::new();
+    fixture.register_pow();
+
+    let request = Message::new_request(Id(1), "unknown", serde_json::json!({ "i": 3 }));
+    fixture.transport.mock_peer_json_message(request);
+    fixture.pool.run_until_stalled();
+    fixture.expect_error_response(error_code::METHOD_NOT_FOUND);
+
+    let request = Message::new_request(Id(2), MockRequest::NAME, serde_json::json!({ "j": 3 }));
+    fixture.transport.mock_peer_json_message(request);
+    fixture.pool.run_until_stalled();
+    fixture.expect_error_response(error_code::INVALID_PARAMS);
+
+    fixture.client.handler.register_method(|_: MockRequest| {
+        let error = messages::Error::new(42, "Cannot compute.");
+        futures::future::ready(Err::<MockResponse, _>(error))
+    });
+    let request = Message::new_request(Id(3), MockRequest::NAME, MockRequest { i: 3 });
+    fixture.transport.mock_peer_json_message(request);
+    fixture.pool.run_until_stalled();
+    fixture.expect_error_response(42);
+}
+
+
+
+// ===============
+// === Batches ===
+// ===============
+
+#[test]
+fn test_answering_peer_batch() {
+    let mut fixture = Fixture::new();
+    fixture.register_pow();
+    let notification = MockNotification::Meow { text: "meow!".into() };
+    let batch = serde_json::json!([
+        Message::new_request(Id(1), MockRequest::NAME, MockRequest { i: 2 }),
+        Message::new(notification.clone()),
+        Message::new_request(Id(2), MockRequest::NAME, MockRequest { i: 3 }),
+    ]);
+    fixture.transport.mock_peer_json_message(batch);
+    fixture.pool.run_until_stalled();
+
+    assert_eq!(fixture.client.expect_notification(), notification);
+    let responses = fixture.transport.expect_json_message::<Vec<MockResponseMessage>>();
+    assert_eq!(responses, vec![
+        Message::new_success(Id(1), MockResponse { result: 4 }),
+        Message::new_success(Id(2), MockResponse { result: 9 }),
+    ]);
+}
+
+#[test]
+fn test_answering_peer_batch_with_invalid_request() {
+    let mut fixture = Fixture::new();
+    fixture.register_pow();
+    let batch = serde_json::json!([
+        Message::new_request(Id(1), MockRequest::NAME, MockRequest { i: 2 }),
+        { "jsonrpc": "2.0", "params": {} },
+        1,
+    ]);
+    fixture.transport.mock_peer_json_message(batch);
+    fixture.pool.run_until_stalled();
+
+    let responses = fixture.transport.expect_json_message::<Vec<BatchResponse>>();
+    assert_eq!(responses.len(), 3);
+    let expected_success = serde_json::to_value(MockResponse { result: 4 }).unwrap();
+    let expected_success = Message::new_success(Id(1), expected_success);
+    assert_eq!(responses[0], BatchResponse::Response(expected_success));
+    for response in &responses[1..] {
+        let BatchResponse::InvalidRequest(response) = response else {
+            panic!("Expected an invalid request error: {response:?}")
+        };
+        assert_eq!(response.error.code, error_code::INVALID_REQUEST);
+    }
+}
+
+#[test]
+fn test_answering_peer_empty_batch() {
+    let mut fixture = Fixture::new();
+    fixture.transport.mock_peer_text_message("[]");
+    fixture.pool.run_until_stalled();
+
+    let response = fixture.transport.expect_json_message::<serde_json::Value>();
+    assert_eq!(response["id"], serde_json::Value::Null);
+    assert_eq!(response["error"]["code"], error_code::INVALID_REQUEST);
+    fixture.client.expect_no_notification_yet();
+}
+
+#[test]
+fn test_batch_call() {
+    let mut fixture = Fixture::new();
+    let inputs = [MockRequest { i: 2 }, MockRequest { i: 3 }];
+    let futures = fixture.client.handler.open_batch_request(inputs);
+    let mut futures = futures.into_iter().map(Box::pin).collect::<Vec<_>>();
+
+    let requests = fixture.transport.expect_json_message::<Vec<MockRequestMessage>>();
+    assert_eq!(requests.len(), 2);
+    assert_ne!(requests[0].id, requests[1].id);
+    // The peer may reply in any order.
+    let responses = requests.into_iter().rev().map(pow_impl).collect::<Vec<_>>();
+    fixture.transport.mock_peer_json_message(responses);
+    fixture.pool.run_until_stalled();
+
+    assert_eq!(futures[0].expect_ok().result, 4);
+    assert_eq!(futures[1].expect_ok().result, 9);
+    assert!(fixture.client.handler.open_batch_request(Vec::<MockRequest>::new()).is_empty());
+}
+
+
+
+// ====================
+// === Call Options ===
+// ====================
+
+type JsonNotificationMessage = messages::NotificationMessage<serde_json::Value>;
+
+impl Fixture {
+    fn expect_no_message_sent(&mut self) {
+        let sent = self.transport.with_mut_data(|data| data.sent_text_msgs.clone());
+        assert!(sent.is_empty(), "Expected no messages sent, got {sent:?}.");
+    }
+
+    fn expect_cancel_notification(&mut self, id: Id) {
+        let notification = self.transport.expect_json_message::<JsonNotificationMessage>();
+        assert_eq!(notification.0.method, call::CANCEL_REQUEST_METHOD);
+        assert_eq!(notification.0.params, serde_json::json!({ "id": id }));
+    }
+}
+
+#[test]
+fn test_cancelling_call() {
+    let mut fixture = Fixture::new();
+    let token = CancellationToken::new();
+    let options = CallOptions::new().with_cancellation(&token).with_cancel_notification();
+    let mut fut =
+        Box::pin(fixture.client.handler.open_request_with_options(MockRequest { i: 2 }, options));
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    fut.expect_pending();
+
+    token.cancel();
+    assert!(matches!(fut.expect_err(), RpcError::Cancelled));
+    fixture.expect_cancel_notification(request.id);
+
+    // The reply to the cancelled call is not expected anymore.
+    fixture.transport.mock_peer_json_message(pow_impl(request));
+    fixture.pool.run_until_stalled();
+    assert!(matches!(fixture.client.expect_handling_error(), HandlingError::UnexpectedResponse(_)));
+}
+
+#[test]
+fn test_dropping_call() {
+    let mut fixture = Fixture::new();
+    let fut = fixture.client.pow(2);
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    drop(fut);
+    fixture.expect_no_message_sent();
+    fixture.transport.mock_peer_json_message(pow_impl(request));
+    fixture.pool.run_until_stalled();
+    assert!(matches!(fixture.client.expect_handling_error(), HandlingError::UnexpectedResponse(_)));
+
+    let options = CallOptions::new().with_cancel_notification();
+    let fut = fixture.client.handler.open_request_with_options(MockRequest { i: 2 }, options);
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    drop(fut);
+    fixture.expect_cancel_notification(request.id);
+}
+
+#[test]
+fn test_call_timeout() {
+    let mut fixture = Fixture::new();
+    let options =
+        CallOptions::new().with_timeout(Duration::from_millis(10)).with_cancel_notification();
+    let mut fut =
+        Box::pin(fixture.client.handler.open_request_with_options(MockRequest { i: 2 }, options));
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    fut.expect_pending();
+    sleep(Duration::from_millis(100));
+
+    assert!(matches!(fut.expect_err(), RpcError::TimeoutError { millis: 10 }));
+    fixture.expect_cancel_notification(request.id);
+}
+
+#[test]
+fn test_call_progress() {
+    let mut fixture = Fixture::new();
+    let mut options = CallOptions::new();
+    let mut progress = Box::pin(options.report_progress("pow-progress"));
+    let mut fut =
+        Box::pin(fixture.client.handler.open_request_with_options(MockRequest { i: 2 }, options));
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+
+    let report = |token: &str, done: i64| {
+        let params = call::ProgressParams { token: token.into(), value: serde_json::json!(done) };
+        Message::new_notification(call::PROGRESS_METHOD, params)
+    };
+    fixture.transport.mock_peer_json_message(report("pow-progress", 1));
+    fixture.transport.mock_peer_json_message(report("pow-progress", 2));
+    fixture.pool.run_until_stalled();
+    assert_eq!(progress.expect_next(), serde_json::json!(1));
+    assert_eq!(progress.expect_next(), serde_json::json!(2));
+    progress.expect_pending();
+    fixture.client.expect_no_notification_yet();
+
+    // The progress of other calls is not routed.
+    fixture.transport.mock_peer_json_message(report("other", 3));
+    fixture.pool.run_until_stalled();
+    progress.expect_pending();
+    assert!(matches!(
+        fixture.client.expect_handling_error(),
+        HandlingError::InvalidNotification(_)
+    ));
+
+    fixture.transport.mock_peer_json_message(pow_impl(request));
+    fixture.pool.run_until_stalled();
+    assert_eq!(fut.expect_ok().result, 4);
+    progress.expect_terminated();
+}
+
+
+
+// ==============================
+// === Reconnecting Transport ===
+// ==============================
+
+struct ReconnectingFixture {
+    /// The connections handed out by the connector, in order. When there are none left,
+    /// reconnecting fails.
+    connections:  Rc<RefCell<VecDeque<MockTransport>>>,
+    transport:    MockTransport,
+    reconnecting: ReconnectingTransport,
+    client:       Client,
+    pool:         futures::executor::LocalPool,
+}
+
+impl ReconnectingFixture {
+    fn new() -> Self {
+        let transport = MockTransport::new();
+        let connections = Rc::new(RefCell::new(VecDeque::<MockTransport>::new()));
+        let connector = f!([connections]() {
+            let connection = connections.borrow_mut().pop_front();
+            let connection: Option<Box<dyn Transport>> = connection.map(|c| Box::new(c) as _);
+            let result = connection.ok_or_else(|| failure::format_err!("Connection refused."));
+            futures::future::ready(result).boxed_local()
+        });
+        let reconnecting = ReconnectingTransport::new(transport.clone(), connector);
+        reconnecting.set_backoff(Backoff {
+            initial_delay: Duration::from_millis(1),
+            multiplier:    2.0,
+            max_delay:     Duration::from_millis(5),
+            max_attempts:  Some(3),
+        });
+        let mut client = Client::new(reconnecting.clone());
+        let pool = futures::executor::LocalPool::new();
+        pool.spawner().spawn_local(reconnecting.runner()).unwrap();
+        pool.spawner().spawn_local(client.events_processor()).unwrap();
+        let mut fixture =
+            ReconnectingFixture { connections, transport, reconnecting, client, pool };
+        fixture.pool.run_until_stalled();
+        fixture
+    }
+
+    /// Add a connection to be handed out by the connector, and return it.
+    fn add_connection(&self) -> MockTransport {
+        let connection = MockTransport::new();
+        self.connections.borrow_mut().push_back(connection.clone());
+        connection
+    }
+
+    /// Wait long enough for all the reconnection attempts to be made.
+    fn run_reconnection(&mut self) {
+        for _ in 0..10 {
+            sleep(Duration::from_millis(10));
+            self.pool.run_until_stalled();
+        }
+    }
+
+    fn expect_event(&mut self, expected: impl Fn(&MockEvent) -> bool) {
+        let event = self.client.events_stream.expect_next();
+        assert!(expected(&event), "Unexpected event: {event:?}.");
+    }
+}
+
+#[test]
+fn test_reconnecting_and_resending_requests() {
+    let mut fixture = ReconnectingFixture::new();
+    fixture.reconnecting.set_resend_policy(resend_methods([MockRequest::NAME]));
+    let mut first = Box::pin(fixture.client.pow(2));
+    let first_request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    let mut new_connection = fixture.add_connection();
+
+    fixture.transport.mock_connection_closed();
+    fixture.pool.run_until_stalled();
+    fixture.expect_event(|event| matches!(event, MockEvent::Reconnecting { attempt: 1 }));
+    assert!(fixture.reconnecting.is_reconnecting());
+    // The requests made while reconnecting are held until the connection is reestablished.
+    let mut second = Box::pin(fixture.client.pow(3));
+    second.expect_pending();
+
+    fixture.run_reconnection();
+    fixture.expect_event(|event| matches!(event, MockEvent::Reconnected));
+    assert!(!fixture.reconnecting.is_reconnecting());
+    let resent = new_connection.expect_json_message::<MockRequestMessage>();
+    assert_eq!(resent, first_request);
+    let second_request = new_connection.expect_json_message::<MockRequestMessage>();
+    assert_eq!(second_request.i, 3);
+    assert_eq!(fixture.reconnecting.pending_requests().len(), 2);
+
+    new_connection.mock_peer_json_message(pow_impl(resent));
+    new_connection.mock_peer_json_message(pow_impl(second_request));
+    fixture.pool.run_until_stalled();
+    assert_eq!(first.expect_ok(), 4);
+    assert_eq!(second.expect_ok(), 9);
+    assert!(fixture.reconnecting.pending_requests().is_empty());
+}
+
+#[test]
+fn test_not_resending_abandoned_requests() {
+    let mut fixture = ReconnectingFixture::new();
+    fixture.reconnecting.set_resend_policy(resend_all);
+    let token = CancellationToken::new();
+    let options = CallOptions::new().with_cancellation(&token);
+    let cancelled = fixture.client.handler.open_request_with_options(MockRequest { i: 2 }, options);
+    let mut cancelled = Box::pin(cancelled);
+    fixture.transport.expect_json_message::<MockRequestMessage>();
+    let options = CallOptions::new().with_timeout(Duration::from_millis(10));
+    let timed_out = fixture.client.handler.open_request_with_options(MockRequest { i: 3 }, options);
+    let mut timed_out = Box::pin(timed_out);
+    timed_out.expect_pending();
+    fixture.transport.expect_json_message::<MockRequestMessage>();
+    let mut answered_later = Box::pin(fixture.client.pow(4));
+    let request = fixture.transport.expect_json_message::<MockRequestMessage>();
+    assert_eq!(fixture.reconnecting.pending_requests().len(), 3);
+
+    token.cancel();
+    assert!(matches!(cancelled.expect_err(), RpcError::Cancelled));
+    sleep(Duration::from_millis(100));
+    assert!(matches!(timed_out.expect_err(), RpcError::TimeoutError { millis: 10 }));
+    let pending = fixture.reconnecting.pending_requests();
+    assert_eq!(pending.iter().map(|request| request.id).collect_vec(), vec![request.id]);
+
+    let mut new_connection = fixture.add_connection();
+    fixture.transport.mock_connection_closed();
+    fixture.run_reconnection();
+    let resent = new_connection.expect_json_message::<MockRequestMessage>();
+    assert_eq!(resent, request);
+    assert!(new_connection.with_mut_data(|data| data.sent_text_msgs.is_empty()));
+    new_connection.mock_peer_json_message(pow_impl(resent));
+    fixture.pool.run_until_stalled();
+    assert_eq!(answered_later.expect_ok(), 16);
+}
+
+#[test]
+fn test_not_resending_by_default() {
+    let mut fixture = ReconnectingFixture::new();
+    let mut fut = Box::pin(fixture.client.pow(2));
+    fixture.transport.expect_json_message::<MockRequestMessage>();
+    let mut new_connection = fixture.add_connection();
+
+    fixture.transport.mock_connection_closed();
+    fixture.run_reconnection();
+    fixture.expect_event(|event| matches!(event, MockEvent::Reconnecting { attempt: 1 }));
+    fixture.expect_event(|event| matches!(event, MockEvent::Reconnected));
+    assert!(matches!(fut.expect_err(), RpcError::LostConnection));
+    assert!(new_connection.with_mut_data(|data| data.sent_text_msgs.is_empty()));
+}
+
+#[test]
+fn test_resend_policy() {
+    let mut fixture = ReconnectingFixture::new();
+    fixture.reconnecting.set_resend_policy(resend_methods(["file/read"]));
+    let mut fut = Box::pin(fixture.client.pow(2));
+    fixture.transport.expect_json_message::<MockRequestMessage>();
+    let mut new_connection = fixture.add_connection();
+
+    fixture.transport.mock_connection_closed();
+    fixture.run_reconnection();
+    fixture.expect_event(|event| matches!(event, MockEvent::Reconnecting { attempt: 1 }));
+    fixture.expect_event(|event| matches!(event, MockEvent::Reconnected));
+    assert!(matches!(fut.expect_err(), RpcError::LostConnection));
+    assert!(new_connection.with_mut_data(|data| data.sent_text_msgs.is_empty()));
+}
+
+#[test]
+fn test_giving_up_reconnecting() {
+    let mut fixture = ReconnectingFixture::new();
+    let mut fut = Box::pin(fixture.client.pow(2));
+    fixture.transport.mock_connection_closed();
+    fixture.run_reconnection();
+    for n in 1..=3 {
+        fixture.expect_event(|e| matches!(e, MockEvent::Reconnecting { attempt } if *attempt == n));
+    }
+    fixture.expect_event(|event| matches!(event, MockEvent::Closed));
+    assert!(matches!(fut.expect_err(), RpcError::LostConnection));
+    fixture.client.pow(3).boxed_local().expect_err();
+}
+
+#[test]
+fn test_backoff_delays() {
+    let backoff = Backoff {
+        initial_delay: Duration::from_millis(100),
+        multiplier:    2.0,
+        max_delay:     Duration::from_millis(500),
+        max_attempts:  Some(5),
+    };
+    let delays = (0..=6).map(|attempt| backoff.delay(attempt)).collect::<Vec<_>>();
+    let millis = |millis| Some(Duration::from_millis(millis));
+    assert_eq!(delays, vec![
+        None,
+        millis(100),
+        millis(200),
+        millis(400),
+        millis(500),
+        millis(500),
+        None
+    ]);
+}
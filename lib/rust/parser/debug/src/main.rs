@@ -40,8 +40,8 @@ fn check_file(path: &str, mut code: &str) {
         code = code_;
     }
     let ast = enso_parser::Parser::new().run(code);
-    for (parsed, original) in ast.code().lines().zip(code.lines()) {
-        assert_eq!(parsed, original, "Bug: dropped tokens, while parsing: {path}");
+    if let Err(mismatch) = enso_parser::round_trip::check_tree(&ast, code) {
+        panic!("Bug: {mismatch} While parsing: {path}");
     }
     let s_expr = enso_parser_debug::to_s_expr(&ast, code);
     println!("{s_expr}");
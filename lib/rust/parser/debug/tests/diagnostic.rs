@@ -0,0 +1,164 @@
+//! Collect diagnostics from parsed code and compare them to expected values.
+
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(non_ascii_idents)]
+#![deny(unconditional_recursion)]
+#![warn(unsafe_code)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use enso_parser::diagnostic::Diagnostic;
+use enso_parser::diagnostic::Fix;
+use enso_parser::syntax::tree::ErrorKind;
+use enso_parser::syntax::Tree;
+use enso_parser::Parser;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[test]
+fn valid_code() {
+    assert_eq!(diagnostics("main =\n    x = 'text \\n'\n    x + 1"), []);
+}
+
+#[test]
+fn unmatched_delimiter() {
+    let found = diagnostics("x)");
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.kind, ErrorKind::UnmatchedDelimiter);
+    assert_eq!(diagnostic.code(), "E001");
+    assert_eq!(diagnostic.span, 1..2);
+    assert_eq!(apply(&diagnostic.fixes[0], "x)"), "x");
+    let found = diagnostics("(x");
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.span, 0..1);
+    assert_eq!(apply(&diagnostic.fixes[0], "(x"), "x");
+    assert_eq!(apply(&diagnostic.fixes[1], "(x"), "(x)");
+    let code = "foo = [1, 2";
+    let found = diagnostics(code);
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.span, 6..7);
+    assert_eq!(apply(&diagnostic.fixes[1], code), "foo = [1, 2]");
+}
+
+#[test]
+fn unterminated_text() {
+    let code = "x = 'abc";
+    let found = diagnostics(code);
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.kind, ErrorKind::UnterminatedText);
+    assert_eq!(diagnostic.code(), "E024");
+    assert_eq!(diagnostic.span, 4..8);
+    assert_eq!(apply(&diagnostic.fixes[0], code), "x = 'abc'");
+}
+
+#[test]
+fn operator_after_assignment() {
+    let code = "x = * 2";
+    let found = diagnostics(code);
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.kind, ErrorKind::MissingOperands { operator: "*".into() });
+    assert_eq!(diagnostic.span, 4..7);
+}
+
+#[test]
+fn multiple_operators() {
+    let code = "x + + y";
+    let found = diagnostics(code);
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.kind, ErrorKind::MultipleOperators);
+    assert_eq!(diagnostic.span, 2..5);
+    let secondary: Vec<_> = diagnostic.secondary_spans.iter().map(|s| s.span.clone()).collect();
+    assert_eq!(secondary, [2..3, 4..5]);
+    assert_eq!(diagnostic.fixes.len(), 2);
+    assert_eq!(apply(&diagnostic.fixes[1], code), "x +  y");
+}
+
+#[test]
+fn invalid_escape_sequence() {
+    let code = "x = 'a\\qb'";
+    let found = diagnostics(code);
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.kind, ErrorKind::InvalidEscapeSequence { escape: "\\q".into() });
+    assert_eq!(diagnostic.span, 6..8);
+    assert_eq!(apply(&diagnostic.fixes[0], code), "x = 'a\\\\qb'");
+}
+
+#[test]
+fn invalid_nodes() {
+    let found = diagnostics("foreign 4");
+    let [diagnostic] = &found[..] else { panic!() };
+    assert_eq!(diagnostic.kind, ErrorKind::ExpectedForeignLanguage);
+    assert_eq!(diagnostic.span, 0..9);
+    assert!(diagnostic.fixes.is_empty());
+}
+
+#[test]
+fn source_order() {
+    let code = "a = 'x\\q'\nb = x + + y\nc = 'y\\w'";
+    let kinds: Vec<_> = diagnostics(code).into_iter().map(|d| (d.code(), d.span)).collect();
+    assert_eq!(kinds, [("E009", 6..8), ("E007", 16..19), ("E009", 28..30)]);
+}
+
+#[test]
+fn serialized_kind() {
+    let code = "foreign 4\nx = 'a\\q'";
+    let tree = Parser::new().run(code);
+    let serialized = enso_parser::serialization::serialize_tree(&tree).unwrap();
+    let deserialized = enso_parser::serialization::deserialize_tree(&serialized).unwrap();
+    let kinds = |tree: &Tree| -> Vec<ErrorKind> {
+        enso_parser::diagnostic::collect(tree).into_iter().map(|d| d.kind).collect()
+    };
+    let escape = ErrorKind::InvalidEscapeSequence { escape: "\\q".into() };
+    let expected = [ErrorKind::ExpectedForeignLanguage, escape];
+    assert_eq!(kinds(&tree), expected);
+    assert_eq!(kinds(&deserialized), expected);
+}
+
+#[test]
+fn kind_from_code() {
+    let argument = || String::from("+");
+    let kinds = [
+        ErrorKind::UnmatchedDelimiter,
+        ErrorKind::MissingOperands { operator: argument() },
+        ErrorKind::UnexpectedToken { token: argument() },
+        ErrorKind::InvalidEscapeSequence { escape: argument() },
+        ErrorKind::UnterminatedText,
+        ErrorKind::InvalidMacroInvocation,
+        ErrorKind::Internal { message: argument() },
+        ErrorKind::Other { message: argument() },
+    ];
+    for kind in kinds {
+        let argument = kind.argument().unwrap_or_default().to_owned();
+        assert_eq!(ErrorKind::from_code(kind.code(), argument), Some(kind));
+    }
+    assert_eq!(ErrorKind::from_code("E000", argument()), None);
+}
+
+
+// ====================
+// === Test Support ===
+// ====================
+
+fn diagnostics(code: &str) -> Vec<Diagnostic> {
+    enso_parser::diagnostic::collect(&Parser::new().run(code))
+}
+
+/// Apply the fix to the code, and check that the result has no diagnostics.
+fn apply(fix: &Fix, code: &str) -> String {
+    let mut code = code.to_owned();
+    fix.edits.iter().for_each(|edit| edit.apply(&mut code));
+    assert_eq!(diagnostics(&code), [], "Applying {fix:?} to {code:?}");
+    code
+}
@@ -0,0 +1,165 @@
+//! Format code and compare the results to expected values.
+
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(non_ascii_idents)]
+#![deny(unconditional_recursion)]
+#![warn(unsafe_code)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use enso_parser::format::format;
+use enso_parser::Parser;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[test]
+fn empty_module() {
+    test_format("", "");
+    test_format("\n\n  \n", "");
+}
+
+#[test]
+fn spacing() {
+    test_format("x  =  a  +  b*c", "x = a + b*c\n");
+    test_format("main =\n    foo.bar   baz  (x  +  y)", "main =\n    foo.bar baz (x + y)\n");
+    test_format("f  (x)  =  x", "f (x) = x\n");
+}
+
+#[test]
+fn indentation() {
+    test_format("main =\n  x = 1\n  x + 2\n", "main =\n    x = 1\n    x + 2\n");
+    test_format(
+        "main =\n  foo =\n     1\n  foo\n",
+        "main =\n    foo =\n        1\n    foo\n",
+    );
+    test_format("type Maybe\n  Some value\n  None", "type Maybe\n    Some value\n    None\n");
+    test_format("main =\n foo\n   a\n   b", "main =\n    foo\n        a\n        b\n");
+    test_format("main =\n x = a\n   + b\n   * c", "main =\n    x = a\n        + b\n        * c\n");
+}
+
+#[test]
+fn case_expressions() {
+    test_format(
+        "main = case x of\n  Some v  ->  v\n  None   ->  0",
+        "main = case x of\n    Some v -> v\n    None -> 0\n",
+    );
+}
+
+#[test]
+fn empty_lines() {
+    test_format("main =\n    x = 1\n\n\n\n    x\n\n\n", "main =\n    x = 1\n\n    x\n");
+    test_format("\n\nimport Standard.Base\n\n\n", "import Standard.Base\n");
+}
+
+#[test]
+fn definitions_are_separated() {
+    test_format("foo = 1\nbar = 2\n", "foo = 1\n\nbar = 2\n");
+    test_format("foo : Integer\nfoo = 1\nbar = 2", "foo : Integer\nfoo = 1\n\nbar = 2\n");
+    test_format("import Standard.Base\nmain = 1", "import Standard.Base\n\nmain = 1\n");
+    test_format(
+        "type T\n    A\n    B\n    foo = 1\n    bar = 2",
+        "type T\n    A\n    B\n\n    foo = 1\n\n    bar = 2\n",
+    );
+    test_format("main =\n    f x = x\n    g x = x", "main =\n    f x = x\n    g x = x\n");
+}
+
+#[test]
+fn comments() {
+    test_format(
+        "main =  # Entry point.\n  x = 1   # Trailing.\n  # Own line.\n  x\n",
+        "main = # Entry point.\n    x = 1 # Trailing.\n    # Own line.\n    x\n",
+    );
+    test_format("# Comment.\n\n\nfoo = 1", "# Comment.\n\nfoo = 1\n");
+    test_format("# About foo.\nfoo = 1", "# About foo.\nfoo = 1\n");
+}
+
+#[test]
+fn documentation() {
+    test_format("## Doc.\nfoo = 1\n", "## Doc.\nfoo = 1\n");
+    test_format(
+        "type T\n  ## Doc\n     more.\n  foo = 1",
+        "type T\n    ## Doc\n       more.\n    foo = 1\n",
+    );
+    test_format("## Doc.\n\n\nfoo = 1\n", "## Doc.\nfoo = 1\n");
+}
+
+#[test]
+fn annotations() {
+    test_format("@a  x\nfoo = 1", "@a x\nfoo = 1\n");
+    test_format("main =\n  @Tail_Call  go  x", "main =\n    @Tail_Call go x\n");
+}
+
+#[test]
+fn text_literals() {
+    test_format("x = 'a  b'", "x = 'a  b'\n");
+    test_format(
+        "main =\n  x = \"\"\"\n     text\n       more\n  x",
+        "main =\n    x = \"\"\"\n       text\n         more\n    x\n",
+    );
+}
+
+#[test]
+fn idempotence() {
+    let code = [
+        "import Standard.Base.Data",
+        "from Standard.Base import all",
+        "",
+        "## A type.",
+        "type Maybe",
+        "  ## A value.",
+        "  Some value",
+        "  None",
+        "",
+        "  ## Return the value, or the default.",
+        "     More detail.",
+        "  get_or_else  self  default  =  case self of",
+        "     Some v -> v  # Present.",
+        "     None   -> default",
+        "",
+        "",
+        "main =",
+        "      # Compute things.",
+        "      x = [1,  2, 3].map  (+1)",
+        "      y = x",
+        "        . reverse",
+        "        . sort",
+        "      text = \"\"\"",
+        "         Multi-line text",
+        "           indented",
+        "      @Tail_Call  go  y",
+        "foo : Integer -> Integer",
+        "foo a = a*2",
+        "x = (a b",
+    ]
+    .join("\n");
+    let once = format(&Parser::new().run(&code));
+    let twice = format(&Parser::new().run(&once));
+    assert_eq!(once, twice);
+}
+
+
+
+// ====================
+// === Test Support ===
+// ====================
+
+/// Check that the code is formatted as expected, and that formatting is idempotent.
+fn test_format(code: &str, expected: &str) {
+    let parser = Parser::new();
+    let formatted = format(&parser.run(code));
+    assert_eq!(formatted, expected, "Formatting {code:?}");
+    let reformatted = format(&parser.run(&formatted));
+    assert_eq!(reformatted, formatted, "Reformatting {formatted:?}");
+}
@@ -0,0 +1,175 @@
+//! Check that incremental reparsing produces the same result as parsing the edited code from
+//! scratch.
+
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(non_ascii_idents)]
+#![deny(unconditional_recursion)]
+#![warn(unsafe_code)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use enso_parser::incremental::Edit;
+use enso_parser::Parser;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[test]
+fn edit_inside_line() {
+    test_edits("main =\n    x = 1\n    x + 2\nfoo = bar\n", &[(12..13, "23")]);
+    test_edits("a = 1\nb = 2\nc = 3", &[(6..7, "bb")]);
+}
+
+#[test]
+fn edit_joining_lines() {
+    test_edits("a = 1\nb = 2\nc = 3", &[(5..6, " ")]);
+    test_edits("a = 1\nb = 2\nc = 3", &[(6..6, "    ")]);
+    test_edits("main =\n\nfoo = bar\n", &[(8..8, "    ")]);
+}
+
+#[test]
+fn edit_splitting_lines() {
+    test_edits("main =\n    x = 1\n    x + 2\nfoo = bar\n", &[(17..21, "")]);
+    test_edits("a = 1 + 2\nb = 2", &[(6..6, "\n")]);
+}
+
+#[test]
+fn edit_creating_prefix() {
+    test_edits("a = 1\nb = 2\n## Doc.\nc = 3", &[(6..11, "@annotation x")]);
+    test_edits("a = 1\nb = 2\nc = 3", &[(0..5, "## Doc.")]);
+    test_edits("## Doc.\na = 1\nb = 2", &[(8..13, "")]);
+}
+
+#[test]
+fn edit_sequences() {
+    test_edits("a = 1\nb = 2\nc = 3\nd = 4", &[(18..18, "x"), (2..3, ":")]);
+    test_edits("a = 1\nb = 2\nc = 3\nd = 4", &[(0..0, "z\n"), (20..23, "")]);
+}
+
+#[test]
+fn no_edits() {
+    test_edits("a = 1\nb = 2\nc = 3", &[]);
+}
+
+#[test]
+fn random_edits() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    for _ in 0..500 {
+        let code = random_code(&mut rng);
+        let edit_count = rng.gen_range(1..4);
+        let mut edited = code.clone();
+        let mut edits = vec![];
+        for _ in 0..edit_count {
+            let start = random_boundary(&mut rng, &edited, 0..=edited.len());
+            let end = random_boundary(&mut rng, &edited, start..=(start + 8).min(edited.len()));
+            let text = FRAGMENTS.choose(&mut rng).unwrap();
+            edited.replace_range(start..end, text);
+            edits.push((start..end, *text));
+        }
+        test_edits(&code, &edits);
+    }
+}
+
+#[test]
+fn random_edit_chains() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    for _ in 0..100 {
+        let code = random_code(&mut rng);
+        let start = random_boundary(&mut rng, &code, 0..=code.len());
+        let text = FRAGMENTS.choose(&mut rng).unwrap();
+        let first = Edit::new(start..start, text);
+        let mut code1 = code.clone();
+        first.apply(&mut code1);
+        let start = random_boundary(&mut rng, &code1, 0..=code1.len());
+        let end = random_boundary(&mut rng, &code1, start..=code1.len());
+        let second = Edit::new(start..end, "");
+        let mut code2 = code1.clone();
+        second.apply(&mut code2);
+        let parser = Parser::new();
+        let tree = parser.run(&code);
+        let tree1 = parser.run_incremental(tree, &code, &[first], &code1);
+        assert_eq!(tree1, parser.run(&code1), "Reparsing {code:?} as {code1:?}");
+        let tree2 = parser.run_incremental(tree1, &code1, &[second], &code2);
+        assert_eq!(tree2, parser.run(&code2), "Reparsing {code1:?} as {code2:?}");
+        assert_eq!(tree2.code(), code2);
+    }
+}
+
+
+
+// ====================
+// === Test Support ===
+// ====================
+
+/// Lines the random test inputs are built from.
+const LINES: &[&str] = &[
+    "",
+    "main =",
+    "    x = 1",
+    "    x + 2",
+    "        nested block",
+    "foo a b = a + b",
+    "type Maybe",
+    "    Some value",
+    "    None",
+    "## A documentation comment.",
+    "@annotation value",
+    "# A comment.",
+    "bar = baz   # Trailing comment.",
+    "text = 'Hello, World!'",
+    "multiline = \"\"\"",
+    "    text literal",
+    "import Standard.Base.Data",
+    "x = (a b",
+    "value = operator1.sum",
+    "   ",
+    "ユニコード = 1",
+];
+
+/// Text inserted by the random edits.
+const FRAGMENTS: &[&str] =
+    &["", "\n", "    ", " ", "x", "## ", "@a ", "=", "(", ")", "'", "\"\"\"", "# ", "é", "foo = 1\n"];
+
+fn random_code(rng: &mut impl Rng) -> String {
+    let line_count = rng.gen_range(0..12);
+    let lines: Vec<_> = (0..line_count).map(|_| *LINES.choose(rng).unwrap()).collect();
+    lines.join("\n")
+}
+
+/// Return a random char boundary of the given text, within the given range.
+fn random_boundary(
+    rng: &mut impl Rng,
+    text: &str,
+    range: std::ops::RangeInclusive<usize>,
+) -> usize {
+    let position = rng.gen_range(range);
+    (position..=text.len()).find(|&i| text.is_char_boundary(i)).unwrap()
+}
+
+/// Check that applying the given edits to the code and reparsing incrementally produces the same
+/// tree as parsing the edited code from scratch.
+fn test_edits(code: &str, edits: &[(std::ops::Range<usize>, &str)]) {
+    let edits: Vec<_> = edits.iter().map(|(range, text)| Edit::new(range.clone(), text)).collect();
+    let mut edited = code.to_owned();
+    edits.iter().for_each(|edit| edit.apply(&mut edited));
+    let parser = Parser::new();
+    let old_tree = parser.run(code);
+    let reparsed = parser.run_incremental(old_tree, code, &edits, &edited);
+    let expected = parser.run(&edited);
+    assert_eq!(reparsed, expected, "Reparsing {code:?} as {edited:?}");
+    assert_eq!(reparsed.code(), edited);
+    enso_parser::serialization::serialize_tree(&reparsed).unwrap();
+}
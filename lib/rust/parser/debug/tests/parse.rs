@@ -60,7 +60,7 @@ fn application() {
 fn parentheses() {
     test("(a b)", block![(Group (App (Ident a) (Ident b)))]);
     test("x)", block![(App (Ident x) (Invalid))]);
-    test("(x", block![(App (Invalid) (Ident x))]);
+    test("(x", block![(Invalid)]);
     test("(a) (b)", block![(App (Group (Ident a)) (Group (Ident b)))]);
     #[rustfmt::skip]
     test("((a b) c)", block![
@@ -925,8 +925,8 @@ fn inline_text_literals() {
     test!(r#""I'm an inline raw text!""#, (TextLiteral #((Section "I'm an inline raw text!"))));
     test!(r#"zero_length = """#, (Assignment (Ident zero_length) "=" (TextLiteral #())));
     test!(r#""type""#, (TextLiteral #((Section "type"))));
-    test!(r#"unclosed = ""#, (Assignment (Ident unclosed) "=" (TextLiteral #())));
-    test!(r#"unclosed = "a"#, (Assignment (Ident unclosed) "=" (TextLiteral #((Section "a")))));
+    test!(r#"unclosed = ""#, (Assignment (Ident unclosed) "=" (Invalid)));
+    test!(r#"unclosed = "a"#, (Assignment (Ident unclosed) "=" (Invalid)));
     test!(r#"'Other quote type'"#, (TextLiteral #((Section "Other quote type"))));
     test!(r#""Non-escape: \n""#, (TextLiteral #((Section "Non-escape: \\n"))));
     test!(r#""Non-escape: \""#, (TextLiteral #((Section "Non-escape: \\"))));
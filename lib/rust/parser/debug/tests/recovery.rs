@@ -0,0 +1,211 @@
+//! Parse a corpus of broken programs, and check how well the parser recovers from their errors:
+//! each program is compared to its expected recovery tree, and the valid code around and within
+//! each error is checked to still be parsed into proper nodes, rather than being swallowed by an
+//! `Invalid` node.
+
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(non_ascii_idents)]
+#![deny(unconditional_recursion)]
+#![warn(unsafe_code)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use enso_parser::syntax::item;
+use enso_parser::syntax::tree;
+use enso_parser::syntax::tree::ItemVisitable;
+use enso_parser::syntax::tree::ItemVisitor;
+use enso_parser::syntax::tree::Visitor;
+use enso_parser::syntax::Tree;
+use enso_parser::Parser;
+use enso_parser_debug::to_s_expr;
+use std::mem;
+
+
+
+// ===========================
+// === Test support macros ===
+// ===========================
+
+/// Parses input as a sequence of S-expressions, and wraps it in a `BodyBlock`.
+macro_rules! block {
+    ( $($statements:tt)* ) => {
+        lexpr::sexp![(BodyBlock #( $( $statements )* ) )]
+    }
+}
+
+
+
+// ==============
+// === Corpus ===
+// ==============
+
+/// A broken program, and the expected result of recovering from its errors.
+#[derive(Debug)]
+struct Case {
+    code:      &'static str,
+    /// The expected tree, in the format of the `parse` tests.
+    expected:  lexpr::Value,
+    /// Valid fragments of the code, which are expected to be parsed into proper nodes.
+    fragments: &'static [&'static str],
+}
+
+#[rustfmt::skip]
+fn corpus() -> Vec<Case> {
+    vec![
+        // === Unclosed groups ===
+        Case {
+            code: "foo (a + b\nbar = 1",
+            expected: block![
+                (App (Ident foo) (Invalid))
+                (Assignment (Ident bar) "=" (Number () "1" ()))],
+            fragments: &["foo", "a + b", "bar = 1"],
+        },
+        Case {
+            code: "f (g [x\ny = 2",
+            expected: block![
+                (App (Ident f) (Invalid))
+                (Assignment (Ident y) "=" (Number () "2" ()))],
+            fragments: &["f", "g", "x", "y = 2"],
+        },
+        Case {
+            code: "xs = [1, 2\nys = xs",
+            expected: block![
+                (Assignment (Ident xs) "=" (Invalid))
+                (Assignment (Ident ys) "=" (Ident xs))],
+            fragments: &["1", "2", "ys = xs"],
+        },
+        Case {
+            code: "main =\n    x = (1 + 2\n    x * 3",
+            expected: block![
+                (Function (Ident main) #() "=" (BodyBlock #(
+                 (Assignment (Ident x) "=" (Invalid))
+                 (OprApp (Ident x) (Ok "*") (Number () "3" ())))))],
+            fragments: &["1 + 2", "x * 3"],
+        },
+        Case {
+            code: "foo x = (x\nbar = 2",
+            expected: block![
+                (Function (Ident foo) #((() (Ident x) () ())) "=" (Invalid))
+                (Assignment (Ident bar) "=" (Number () "2" ()))],
+            fragments: &["bar = 2"],
+        },
+        // === Dangling operators ===
+        Case {
+            code: "x = * 2\ny = x",
+            expected: block![
+                (Assignment (Ident x) "=" (Invalid))
+                (Assignment (Ident y) "=" (Ident x))],
+            fragments: &["2", "y = x"],
+        },
+        Case {
+            code: "x = 1 + * 2",
+            expected: block![
+                (Assignment (Ident x) "="
+                 (OprApp (Number () "1" ()) (Err (#("+" "*"))) (Number () "2" ())))],
+            fragments: &["1", "2"],
+        },
+        // === Unterminated text literals ===
+        Case {
+            code: "greeting = 'Hello\nmain = greeting",
+            expected: block![
+                (Assignment (Ident greeting) "=" (Invalid))
+                (Assignment (Ident main) "=" (Ident greeting))],
+            fragments: &["main = greeting"],
+        },
+        Case {
+            code: "main =\n    x = \"a\n    x",
+            expected: block![
+                (Function (Ident main) #() "=" (BodyBlock #(
+                 (Assignment (Ident x) "=" (Invalid))
+                 (Ident x))))],
+            fragments: &["x"],
+        },
+    ]
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[test]
+fn recovery_corpus() {
+    let parser = Parser::new();
+    let mut lost = vec![];
+    for case in corpus() {
+        let ast = parser.run(case.code);
+        let ast_s_expr = to_s_expr(&ast, case.code);
+        assert_eq!(ast_s_expr.to_string(), case.expected.to_string(), "{:?}", case.code);
+        assert_eq!(ast.code(), case.code, "{:?}", &ast);
+        let proper_nodes = proper_nodes(&ast);
+        for fragment in case.fragments {
+            if !proper_nodes.iter().any(|code| code == fragment) {
+                lost.push((case.code, *fragment));
+            }
+        }
+    }
+    assert_eq!(lost, [], "Fragments swallowed by `Invalid` nodes.");
+}
+
+#[test]
+fn errors_are_contained() {
+    // An error is reported in the line containing it, and doesn't affect the lines around it.
+    for case in corpus() {
+        let ast = Parser::new().run(case.code);
+        let tree::Variant::BodyBlock(block) = &*ast.variant else { panic!() };
+        for line in &block.statements {
+            if let Some(expression) = &line.expression {
+                assert!(!is_invalid(expression), "{:?}: {:?}", case.code, expression);
+            }
+        }
+    }
+}
+
+
+
+// ====================
+// === Test Support ===
+// ====================
+
+/// The code of every node of the tree that is not an `Invalid` node or the tree directly wrapped in
+/// one.
+fn proper_nodes(ast: &Tree) -> Vec<String> {
+    let mut collector = ProperNodes::default();
+    ast.visit_item(&mut collector);
+    collector.codes
+}
+
+#[derive(Debug, Default)]
+struct ProperNodes {
+    codes:          Vec<String>,
+    /// Whether the next visited tree is the content of an `Invalid` node.
+    invalid_parent: bool,
+}
+
+impl Visitor for ProperNodes {}
+impl<'s, 'a> ItemVisitor<'s, 'a> for ProperNodes {
+    fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+        if let item::Ref::Tree(ast) = item {
+            let wrapped = mem::take(&mut self.invalid_parent);
+            if is_invalid(ast) {
+                self.invalid_parent = true;
+            } else if !wrapped {
+                self.codes.push(ast.trimmed_code());
+            }
+        }
+        true
+    }
+}
+
+fn is_invalid(ast: &Tree) -> bool {
+    matches!(&*ast.variant, tree::Variant::Invalid(_))
+}
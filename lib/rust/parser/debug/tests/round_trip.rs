@@ -0,0 +1,114 @@
+//! Check that printing a parsed tree reproduces the parsed code exactly.
+
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(non_ascii_idents)]
+#![deny(unconditional_recursion)]
+#![warn(unsafe_code)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use enso_parser::round_trip;
+use enso_parser::round_trip::Mismatch;
+use enso_parser::Parser;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[test]
+fn line_endings() {
+    test_round_trip("main =\r\n    x = 1\r\n\r\n    x\r\n");
+    test_round_trip("a = 1\rb = 2\n\rc");
+    test_round_trip("text = '''\r\n    line\r\n    line\r\n");
+    test_round_trip("## Doc\r\n   more\r\ntype T\r\n    A\r\n");
+}
+
+#[test]
+fn tabs_and_spaces() {
+    test_round_trip("main =\n\tx = 1\n\tx");
+    test_round_trip("main =\n \t x = 1\n\t  x +\t1 \t");
+    test_round_trip("x\u{00A0}=\u{2003}1");
+    test_round_trip("\n\n  \t\n");
+}
+
+#[test]
+fn non_ascii() {
+    test_round_trip("変数 = 'ünïcødé' # コメント\nπ = 3.14");
+    test_round_trip("main =\n    🧑 = \"🧑\"\n    🧑.length");
+    test_round_trip("x = 'a\\u{1F9D1}b'");
+}
+
+#[test]
+fn invalid_code() {
+    test_round_trip("foo (a\n  ) ]] ' \"\n\t\t``");
+    test_round_trip("x = * 2\ny = 'unterminated\nz = [1, 2");
+    test_round_trip("type\nfrom import\nforeign 4\ncase of\n    ->");
+    test_round_trip("if then else\n@\n~\\\n`'`'");
+}
+
+#[test]
+fn operators_and_macros() {
+    test_round_trip("x= \n    ");
+    test_round_trip("a(  x=1)");
+    test_round_trip(",@\na,@ b\nx a-> , y");
+    test_round_trip("type(\ntype=@ x");
+    test_round_trip("case x of\n    a->##");
+}
+
+#[test]
+fn text_splices() {
+    test_round_trip("'''`  x");
+    test_round_trip("'`a`'  b");
+    test_round_trip("'`'''`'");
+}
+
+#[test]
+fn random_code() {
+    let fragments = [
+        "x", "Foo", "é", "🧑", "1", "0x1F", "_", " ", "  ", "\t", "\n", "\r\n", "\r", "    ", "(",
+        ")", "[", "]", "{", "}", "=", "+", "->", ".", ",", ":", "\\", "~", "@", "'", "\"", "'''",
+        "`", "#", "##", "if", "then", "else", "type", "case", "of", "import", "from", "foreign",
+    ];
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let parser = Parser::new();
+    for _ in 0..1_000 {
+        let length = rng.gen_range(0..40);
+        let code: String = (0..length).map(|_| *fragments.choose(&mut rng).unwrap()).collect();
+        if let Err(mismatch) = round_trip::check(&parser, &code) {
+            panic!("{mismatch} Code: {code:?}");
+        }
+    }
+}
+
+#[test]
+fn mismatch() {
+    assert_eq!(round_trip::compare("a = 1", "a = 1"), Ok(()));
+    let expected = "é = 1";
+    let mismatch = Mismatch { offset: 5, expected: "1".into(), found: "2".into() };
+    assert_eq!(round_trip::compare(expected, "é = 2"), Err(mismatch));
+    let mismatch = Mismatch { offset: 2, expected: " = 1".into(), found: "".into() };
+    assert_eq!(round_trip::compare(expected, "é"), Err(mismatch));
+}
+
+
+
+// ====================
+// === Test Support ===
+// ====================
+
+fn test_round_trip(code: &str) {
+    let tree = Parser::new().run(code);
+    assert_eq!(tree.code(), code, "{tree:?}");
+}
@@ -0,0 +1,21 @@
+//! Check that parsing any input is lossless: printing the tree reproduces the input exactly.
+//!
+//! Run with `cargo fuzz run round_trip` from the parser's directory.
+
+#![no_main]
+
+use enso_parser::round_trip;
+use enso_parser::Parser;
+use libfuzzer_sys::fuzz_target;
+
+thread_local! {
+    static PARSER: Parser = Parser::new();
+}
+
+fuzz_target!(|code: &str| {
+    PARSER.with(|parser| {
+        if let Err(mismatch) = round_trip::check(parser, code) {
+            panic!("{mismatch} Code: {code:?}");
+        }
+    });
+});
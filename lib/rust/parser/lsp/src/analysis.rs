@@ -0,0 +1,316 @@
+//! Computing the information the server provides from the code of a document and its syntax tree.
+//! Positions in the code are computed as byte offsets, and converted to the protocol's UTF-16
+//! based [`Position`]s at the end.
+
+use crate::protocol::*;
+
+use enso_parser::syntax::item;
+use enso_parser::syntax::token;
+use enso_parser::syntax::tree;
+use enso_parser::syntax::tree::ItemVisitable;
+use enso_parser::syntax::tree::ItemVisitor;
+use enso_parser::syntax::tree::Visitor;
+use enso_parser::syntax::Tree;
+use enso_text::unit::Byte;
+use enso_text::Rope;
+use std::mem;
+
+
+
+// =================
+// === Positions ===
+// =================
+
+/// Converts byte offsets in the code of a document to protocol [`Position`]s.
+#[derive(Debug)]
+pub struct Positions {
+    rope: Rope,
+}
+
+impl Positions {
+    /// Constructor.
+    pub fn new(code: &str) -> Self {
+        let rope = Rope::from(code);
+        Self { rope }
+    }
+
+    /// The position of the given byte offset. Offsets past the end of the code are snapped to its
+    /// end.
+    pub fn position(&self, offset: usize) -> Position {
+        let location = self.rope.offset_to_location_snapped(Byte(offset));
+        let location = self.rope.utf16_code_unit_location_of_location(location);
+        Position { line: location.line.value as u32, character: location.offset.value as u32 }
+    }
+
+    /// The range of the given byte range.
+    pub fn range(&self, range: std::ops::Range<usize>) -> Range {
+        Range { start: self.position(range.start), end: self.position(range.end) }
+    }
+}
+
+
+
+// ===================
+// === Diagnostics ===
+// ===================
+
+/// The name reported as the source of the diagnostics.
+pub const DIAGNOSTIC_SOURCE: &str = "enso-parser";
+
+/// The syntax errors in the given tree.
+pub fn diagnostics(ast: &Tree, positions: &Positions) -> Vec<Diagnostic> {
+    let diagnostics = enso_parser::diagnostic::collect(ast).into_iter();
+    let diagnostics = diagnostics.map(|diagnostic| Diagnostic {
+        range:    positions.range(diagnostic.span.clone()),
+        severity: DiagnosticSeverity::Error,
+        code:     diagnostic.code(),
+        source:   DIAGNOSTIC_SOURCE,
+        message:  diagnostic.message(),
+    });
+    diagnostics.collect()
+}
+
+
+
+// ===============
+// === Symbols ===
+// ===============
+
+/// The hierarchy of the functions, types and constructors defined in the given tree.
+pub fn document_symbols(ast: &Tree, positions: &Positions) -> Vec<DocumentSymbol> {
+    let mut collector = SymbolCollector { positions, offset: 0, open: vec![], symbols: vec![] };
+    ast.visit_item(&mut collector);
+    collector.close_symbols_ending_before(usize::MAX);
+    collector.symbols
+}
+
+/// Visitor building the symbol hierarchy. The visitor doesn't report leaving a node, so the symbols
+/// containing the current position are kept open, and each is closed when an item starting after
+/// its end is visited.
+#[derive(Debug)]
+struct SymbolCollector<'p> {
+    positions: &'p Positions,
+    offset:    usize,
+    /// The symbols containing the current position, with the offsets of their ends.
+    open:      Vec<(DocumentSymbol, usize)>,
+    symbols:   Vec<DocumentSymbol>,
+}
+
+impl<'p> Visitor for SymbolCollector<'p> {}
+impl<'s, 'a, 'p> ItemVisitor<'s, 'a> for SymbolCollector<'p> {
+    fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+        match item {
+            item::Ref::Token(token) => {
+                let start = self.offset + token.left_offset.code.repr.len();
+                self.close_symbols_ending_before(start);
+                self.offset = start + token.code.repr.len();
+            }
+            item::Ref::Tree(ast) => {
+                self.offset += ast.span.left_offset.code.repr.len();
+                let start = self.offset;
+                self.close_symbols_ending_before(start);
+                let end = start + ast.span.code_length.utf8_bytes();
+                if let Some((name, kind, name_span)) = self.symbol(ast, start) && start < end {
+                    let symbol = DocumentSymbol {
+                        name,
+                        kind,
+                        range: self.positions.range(start..end),
+                        selection_range: self.positions.range(name_span),
+                        children: vec![],
+                    };
+                    self.open.push((symbol, end));
+                }
+            }
+        }
+        true
+    }
+}
+
+impl<'p> SymbolCollector<'p> {
+    /// If the tree starting at the given offset defines a symbol, return its name, kind, and the
+    /// byte range of the name.
+    fn symbol(
+        &self,
+        ast: &Tree,
+        start: usize,
+    ) -> Option<(String, SymbolKind, std::ops::Range<usize>)> {
+        match &*ast.variant {
+            tree::Variant::Function(function) => {
+                let name = &function.name;
+                let name_start = start + name.span.left_offset.code.repr.len();
+                let name_end = name_start + name.span.code_length.utf8_bytes();
+                let parent_kind = self.open.last().map(|(parent, _)| parent.kind);
+                let in_type = parent_kind == Some(SymbolKind::Class);
+                let kind = if in_type { SymbolKind::Method } else { SymbolKind::Function };
+                Some((name.trimmed_code(), kind, name_start..name_end))
+            }
+            tree::Variant::TypeDef(type_def) => {
+                let keyword = &type_def.keyword;
+                let name = &type_def.name;
+                let keyword_end =
+                    start + keyword.left_offset.code.repr.len() + keyword.code.repr.len();
+                let name_start = keyword_end + name.left_offset.code.repr.len();
+                let name_span = name_start..name_start + name.code.repr.len();
+                Some((name.code.to_string(), SymbolKind::Class, name_span))
+            }
+            tree::Variant::ConstructorDefinition(constructor) => {
+                let name = &constructor.constructor;
+                let name_start = start + name.left_offset.code.repr.len();
+                let name_span = name_start..name_start + name.code.repr.len();
+                Some((name.code.to_string(), SymbolKind::Constructor, name_span))
+            }
+            _ => None,
+        }
+    }
+
+    fn close_symbols_ending_before(&mut self, offset: usize) {
+        while let Some((_, end)) = self.open.last() && *end <= offset {
+            let (symbol, _) = self.open.pop().unwrap();
+            match self.open.last_mut() {
+                Some((parent, _)) => parent.children.push(symbol),
+                None => self.symbols.push(symbol),
+            }
+        }
+    }
+}
+
+
+
+// ======================
+// === Folding Ranges ===
+// ======================
+
+/// The ranges of lines spanned by the blocks in the given tree. The module's top-level block is not
+/// reported.
+pub fn folding_ranges(ast: &Tree, positions: &Positions) -> Vec<FoldingRange> {
+    let mut collector = FoldingCollector { positions, offset: 0, is_root: true, ranges: vec![] };
+    ast.visit_item(&mut collector);
+    collector.ranges
+}
+
+#[derive(Debug)]
+struct FoldingCollector<'p> {
+    positions: &'p Positions,
+    offset:    usize,
+    is_root:   bool,
+    ranges:    Vec<FoldingRange>,
+}
+
+impl<'p> Visitor for FoldingCollector<'p> {}
+impl<'s, 'a, 'p> ItemVisitor<'s, 'a> for FoldingCollector<'p> {
+    fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+        match item {
+            item::Ref::Token(token) => {
+                let start = self.offset + token.left_offset.code.repr.len();
+                self.offset = start + token.code.repr.len();
+            }
+            item::Ref::Tree(ast) => {
+                self.offset += ast.span.left_offset.code.repr.len();
+                use tree::Variant::*;
+                let is_block = matches!(&*ast.variant, BodyBlock(_) | TypeDef(_));
+                if is_block && !mem::take(&mut self.is_root) {
+                    let end = self.offset + ast.span.code_length.utf8_bytes();
+                    let start_line = self.positions.position(self.offset).line;
+                    let end_line = self.positions.position(end).line;
+                    if start_line < end_line {
+                        self.ranges.push(FoldingRange { start_line, end_line });
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+
+
+// =======================
+// === Semantic Tokens ===
+// =======================
+
+/// The types of the semantic tokens, in the order of their indices in the encoded tokens.
+pub const TOKEN_TYPES: &[&str] = &["comment", "string", "number", "operator", "variable", "type"];
+
+/// The type of a semantic token, as an index into [`TOKEN_TYPES`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TokenType {
+    Comment,
+    String,
+    Number,
+    Operator,
+    Variable,
+    Type,
+}
+
+/// The semantic tokens of the given code, classified by the kinds of the tokens produced by the
+/// lexer. Tokens spanning multiple lines are split into a token for each line, as clients are not
+/// required to support multi-line tokens.
+pub fn semantic_tokens(code: &str, positions: &Positions) -> SemanticTokens {
+    let tokens = enso_parser::lexer::run(code).value;
+    let mut encoder = TokenEncoder::default();
+    let mut offset = 0;
+    let mut in_documentation = false;
+    for token in &tokens {
+        let start = offset + token.left_offset.code.repr.len();
+        offset = start + token.code.repr.len();
+        let code = &*token.code.repr;
+        let token_type = match &token.variant {
+            token::Variant::Newline(_) if code.starts_with('#') => Some(TokenType::Comment),
+            token::Variant::TextStart(_) => {
+                in_documentation = code.starts_with("##");
+                Some(if in_documentation { TokenType::Comment } else { TokenType::String })
+            }
+            token::Variant::TextEnd(_) => {
+                let was_documentation = in_documentation;
+                in_documentation = false;
+                Some(if was_documentation { TokenType::Comment } else { TokenType::String })
+            }
+            token::Variant::TextSection(_)
+            | token::Variant::TextEscape(_)
+            | token::Variant::TextInitialNewline(_)
+            | token::Variant::TextNewline(_) =>
+                Some(if in_documentation { TokenType::Comment } else { TokenType::String }),
+            token::Variant::Digits(_) | token::Variant::NumberBase(_) => Some(TokenType::Number),
+            token::Variant::Operator(_) | token::Variant::AutoScope(_) => Some(TokenType::Operator),
+            token::Variant::Ident(ident) if ident.is_operator_lexically =>
+                Some(TokenType::Operator),
+            token::Variant::Ident(ident) if ident.is_type => Some(TokenType::Type),
+            token::Variant::Ident(_) | token::Variant::Wildcard(_) => Some(TokenType::Variable),
+            _ => None,
+        };
+        if let Some(token_type) = token_type {
+            encoder.push(positions, start, code, token_type);
+        }
+    }
+    SemanticTokens { data: encoder.data }
+}
+
+/// Builds the relative encoding of the semantic tokens.
+#[derive(Debug, Default)]
+struct TokenEncoder {
+    previous: Position,
+    data:     Vec<u32>,
+}
+
+impl TokenEncoder {
+    fn push(&mut self, positions: &Positions, start: usize, code: &str, token_type: TokenType) {
+        let mut line_start = start;
+        for line in code.split('\n') {
+            let text = line.trim_end_matches('\r');
+            let length = text.encode_utf16().count() as u32;
+            if length > 0 {
+                let position = positions.position(line_start);
+                let line_delta = position.line - self.previous.line;
+                let character_delta = if line_delta == 0 {
+                    position.character - self.previous.character
+                } else {
+                    position.character
+                };
+                self.data.extend([line_delta, character_delta, length, token_type as u32, 0]);
+                self.previous = position;
+            }
+            line_start += line.len() + 1;
+        }
+    }
+}
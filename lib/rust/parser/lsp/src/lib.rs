@@ -0,0 +1,37 @@
+//! A [Language Server Protocol](https://microsoft.github.io/language-server-protocol/) front-end
+//! for the Enso parser.
+//!
+//! The server provides the features that can be computed from the syntax of a single file alone:
+//! document symbols, folding ranges, syntax diagnostics and semantic highlighting. It doesn't need
+//! a running engine, so editors other than the IDE can use it to understand Enso code offline.
+
+// === Features ===
+#![feature(let_chains)]
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(unconditional_recursion)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+
+// ==============
+// === Export ===
+// ==============
+
+pub mod analysis;
+pub mod protocol;
+pub mod server;
+
+pub use server::Server;
@@ -0,0 +1,37 @@
+//! Run the language server, communicating with the client over the standard input and output.
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(unconditional_recursion)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+
+
+/// Language server main function. As specified by the protocol, the process exits with an error
+/// code if the client didn't request a shutdown before the `exit` notification.
+pub fn main() {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let mut server = enso_parser_lsp::Server::new();
+    match server.run(stdin.lock(), stdout.lock()) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(error) => {
+            eprintln!("Communication with the client failed: {error}");
+            std::process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! The subset of the Language Server Protocol used by the server: the message framing, and the
+//! types of the messages' contents.
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde::Serializer;
+use serde_json::Value;
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+
+
+
+// ===============
+// === Framing ===
+// ===============
+
+const CONTENT_LENGTH: &str = "Content-Length:";
+
+/// Read a single message from the input. Return [`None`] if the input ended before the start of a
+/// message. Content which is not valid JSON is reported as an [`io::ErrorKind::InvalidData`] error.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    match read_content(input)? {
+        Some(content) => parse_content(&content).map(Some).map_err(invalid_data),
+        None => Ok(None),
+    }
+}
+
+/// Read the content of a single message from the input, without parsing it. Return [`None`] if the
+/// input ended before the start of a message.
+pub fn read_content(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix(CONTENT_LENGTH) {
+            let value =
+                value.trim().parse().map_err(|_| invalid_data("Invalid content length."))?;
+            length = Some(value);
+        }
+    }
+    let length = length.ok_or_else(|| invalid_data("Missing content length."))?;
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+    Ok(Some(content))
+}
+
+/// Parse the content of a message. Return the description of the error if it is not valid JSON.
+pub fn parse_content(content: &[u8]) -> Result<Value, String> {
+    serde_json::from_slice(content).map_err(|error| error.to_string())
+}
+
+/// Write a single message to the output.
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(output, "{CONTENT_LENGTH} {}\r\n\r\n{content}", content.len())?;
+    output.flush()
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+
+
+// ================
+// === Messages ===
+// ================
+
+/// A request, notification, or response received from the client.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Incoming {
+    /// The identifier of a request; not present in notifications.
+    #[serde(default)]
+    pub id:     Option<Value>,
+    /// The invoked method; not present in responses.
+    #[serde(default)]
+    pub method: Option<String>,
+    /// The parameters of the method.
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// Error codes defined by JSON-RPC and the Language Server Protocol.
+#[allow(missing_docs)]
+pub mod error_code {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const INVALID_REQUEST: i32 = -32600;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+}
+
+/// A successful response to the request with the given identifier.
+pub fn response(id: Value, result: impl Serialize) -> Value {
+    let result = serde_json::to_value(result).unwrap_or_default();
+    serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// A response reporting that the request with the given identifier failed.
+pub fn error_response(id: Value, code: i32, message: impl Into<String>) -> Value {
+    let error = serde_json::json!({ "code": code, "message": message.into() });
+    serde_json::json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+/// A notification sent to the client.
+pub fn notification(method: &str, params: impl Serialize) -> Value {
+    let params = serde_json::to_value(params).unwrap_or_default();
+    serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+
+
+// =================
+// === Documents ===
+// =================
+
+/// Identifies a text document.
+#[derive(Clone, Debug, Deserialize)]
+#[allow(missing_docs)]
+pub struct TextDocumentIdentifier {
+    pub uri: String,
+}
+
+/// A text document sent by the client when the document is opened.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TextDocumentItem {
+    /// The identifier of the document.
+    pub uri:  String,
+    /// The full content of the document.
+    pub text: String,
+}
+
+/// Parameters of the `textDocument/didOpen` notification.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct DidOpenTextDocumentParams {
+    pub text_document: TextDocumentItem,
+}
+
+/// Parameters of the `textDocument/didChange` notification. As the server requests full document
+/// synchronization, each change contains the full content of the document.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct DidChangeTextDocumentParams {
+    pub text_document:   TextDocumentIdentifier,
+    pub content_changes: Vec<TextDocumentContentChangeEvent>,
+}
+
+/// A change of a document's content.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TextDocumentContentChangeEvent {
+    /// The new content of the document.
+    pub text: String,
+}
+
+/// Parameters of the requests and notifications concerning a whole document.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct TextDocumentParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+
+
+// ================
+// === Location ===
+// ================
+
+/// A position in a document. Like all positions in the protocol, the character offset is measured
+/// in UTF-16 code units.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct Position {
+    pub line:      u32,
+    pub character: u32,
+}
+
+/// A range in a document.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct Range {
+    pub start: Position,
+    pub end:   Position,
+}
+
+
+
+// ===============
+// === Results ===
+// ===============
+
+/// A problem found in a document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// The code the problem is reported for.
+    pub range:    Range,
+    /// How serious the problem is.
+    pub severity: DiagnosticSeverity,
+    /// The stable identifier of the kind of the problem.
+    pub code:     &'static str,
+    /// The name of the tool reporting the problem.
+    pub source:   &'static str,
+    /// A human-readable description of the problem.
+    pub message:  String,
+}
+
+/// The severity of a [`Diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum DiagnosticSeverity {
+    Error       = 1,
+    Warning     = 2,
+    Information = 3,
+    Hint        = 4,
+}
+
+impl Serialize for DiagnosticSeverity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*self as u32)
+    }
+}
+
+/// Parameters of the `textDocument/publishDiagnostics` notification.
+#[derive(Clone, Debug, Serialize)]
+#[allow(missing_docs)]
+pub struct PublishDiagnosticsParams {
+    pub uri:         String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A named element of a document, with the elements defined within it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSymbol {
+    /// The name of the defined element.
+    pub name:            String,
+    /// The kind of the defined element.
+    pub kind:            SymbolKind,
+    /// The range of the whole definition.
+    pub range:           Range,
+    /// The range of the name in the definition.
+    pub selection_range: Range,
+    /// The symbols defined within this one.
+    pub children:        Vec<DocumentSymbol>,
+}
+
+/// The kind of a [`DocumentSymbol`]. Only the kinds reported by the server are listed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum SymbolKind {
+    Class       = 5,
+    Method      = 6,
+    Constructor = 9,
+    Function    = 12,
+}
+
+impl Serialize for SymbolKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(*self as u32)
+    }
+}
+
+/// A range of lines that can be folded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct FoldingRange {
+    pub start_line: u32,
+    pub end_line:   u32,
+}
+
+/// The semantic tokens of a document, encoded as described in the protocol: each token is
+/// represented by 5 numbers (line delta, start character delta, length, token type, token
+/// modifiers), where the deltas are relative to the previous token.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[allow(missing_docs)]
+pub struct SemanticTokens {
+    pub data: Vec<u32>,
+}
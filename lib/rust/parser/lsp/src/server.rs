@@ -0,0 +1,215 @@
+//! The server's state, and the handling of the messages received from the client.
+
+use crate::analysis;
+use crate::protocol;
+use crate::protocol::*;
+
+use enso_parser::Parser;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+
+
+// ==============
+// === Server ===
+// ==============
+
+/// A language server for Enso code. Documents are synchronized in full: each change sent by the
+/// client contains the whole new content of the document, which is then parsed from scratch.
+#[derive(Debug)]
+pub struct Server {
+    parser:             Parser,
+    /// The content of the open documents, by URI.
+    documents:          HashMap<String, String>,
+    shutdown_requested: bool,
+    exit_requested:     bool,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    /// Constructor.
+    pub fn new() -> Self {
+        let parser = Parser::new();
+        let documents = HashMap::new();
+        Self { parser, documents, shutdown_requested: false, exit_requested: false }
+    }
+
+    /// Handle the messages read from the input, writing the responses and notifications to the
+    /// output, until the client sends the `exit` notification or closes the input. Return whether
+    /// the client requested a shutdown before exiting, which determines the process's exit code.
+    ///
+    /// A message which is not valid JSON is answered with a parse error, and the following messages
+    /// are handled as usual.
+    pub fn run(&mut self, mut input: impl BufRead, mut output: impl Write) -> io::Result<bool> {
+        while !self.exit_requested && let Some(content) = protocol::read_content(&mut input)? {
+            let outgoing = match protocol::parse_content(&content) {
+                Ok(message) => self.handle(message),
+                Err(error) => vec![error_response(Value::Null, error_code::PARSE_ERROR, error)],
+            };
+            for outgoing in outgoing {
+                protocol::write_message(&mut output, &outgoing)?;
+            }
+        }
+        Ok(self.shutdown_requested)
+    }
+
+    /// Handle a single message received from the client, and return the messages to send in
+    /// response.
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let message: Incoming = match serde_json::from_value(message) {
+            Ok(message) => message,
+            Err(error) => {
+                let error = error.to_string();
+                return vec![error_response(Value::Null, error_code::INVALID_REQUEST, error)];
+            }
+        };
+        let Some(method) = message.method else { return vec![] };
+        match message.id {
+            Some(id) => vec![self.request(id, &method, message.params)],
+            None => self.notification(&method, message.params),
+        }
+    }
+
+    fn request(&mut self, id: Value, method: &str, params: Value) -> Value {
+        if self.shutdown_requested {
+            let message = format!("The server is shutting down; cannot handle {method}.");
+            return error_response(id, error_code::INVALID_REQUEST, message);
+        }
+        let result = match method {
+            "initialize" => Ok(capabilities()),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentSymbol" => self.with_document(params, |tree, positions, _| {
+                json!(analysis::document_symbols(tree, positions))
+            }),
+            "textDocument/foldingRange" => self.with_document(params, |tree, positions, _| {
+                json!(analysis::folding_ranges(tree, positions))
+            }),
+            "textDocument/semanticTokens/full" => self
+                .with_document(params, |_, positions, code| {
+                    json!(analysis::semantic_tokens(code, positions))
+                }),
+            _ => Err((error_code::METHOD_NOT_FOUND, format!("Unknown method: {method}."))),
+        };
+        match result {
+            Ok(result) => response(id, result),
+            Err((code, message)) => error_response(id, code, message),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: Value) -> Vec<Value> {
+        match method {
+            "exit" => {
+                self.exit_requested = true;
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let Ok(params) = parse_params::<DidOpenTextDocumentParams>(params) else {
+                    return vec![];
+                };
+                let uri = params.text_document.uri;
+                self.documents.insert(uri.clone(), params.text_document.text);
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didChange" => {
+                let Ok(params) = parse_params::<DidChangeTextDocumentParams>(params) else {
+                    return vec![];
+                };
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return vec![];
+                };
+                let uri = params.text_document.uri;
+                self.documents.insert(uri.clone(), change.text);
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didClose" => {
+                let Ok(params) = parse_params::<TextDocumentParams>(params) else {
+                    return vec![];
+                };
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                vec![self.publish_diagnostics(uri)]
+            }
+            // Other notifications, like `initialized`, require no action.
+            _ => vec![],
+        }
+    }
+
+    /// The notification reporting the syntax errors in the document. Closed documents are reported
+    /// to have no errors, which clears any previously reported ones.
+    fn publish_diagnostics(&self, uri: String) -> Value {
+        let diagnostics = self.documents.get(&uri).map(|text| {
+            let code = code(text);
+            let positions = analysis::Positions::new(code);
+            analysis::diagnostics(&self.parser.run(code), &positions)
+        });
+        let params = PublishDiagnosticsParams { uri, diagnostics: diagnostics.unwrap_or_default() };
+        notification("textDocument/publishDiagnostics", params)
+    }
+
+    /// Parse the document identified in the parameters of a request, and compute the request's
+    /// result from its syntax tree, position mapping, and code.
+    fn with_document(
+        &self,
+        params: Value,
+        f: impl FnOnce(&enso_parser::syntax::Tree, &analysis::Positions, &str) -> Value,
+    ) -> Result<Value, (i32, String)> {
+        let params = parse_params::<TextDocumentParams>(params)?;
+        let uri = params.text_document.uri;
+        let unknown = || (error_code::INVALID_PARAMS, format!("Unknown document: {uri}."));
+        let text = self.documents.get(&uri).ok_or_else(unknown)?;
+        let code = code(text);
+        let positions = analysis::Positions::new(code);
+        Ok(f(&self.parser.run(code), &positions, code))
+    }
+}
+
+
+
+// ====================
+// === Capabilities ===
+// ====================
+
+/// The capabilities reported to the client in response to the `initialize` request.
+pub fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            // Full document synchronization.
+            "textDocumentSync": 1,
+            "documentSymbolProvider": true,
+            "foldingRangeProvider": true,
+            "semanticTokensProvider": {
+                "legend": { "tokenTypes": analysis::TOKEN_TYPES, "tokenModifiers": [] },
+                "full": true,
+            },
+        },
+        "serverInfo": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+
+
+// ===============
+// === Helpers ===
+// ===============
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, (i32, String)> {
+    serde_json::from_value(params).map_err(|error| (error_code::INVALID_PARAMS, error.to_string()))
+}
+
+/// The code of a document, without the IDE metadata section.
+fn code(text: &str) -> &str {
+    enso_parser::metadata::extract(text).0
+}
@@ -0,0 +1,219 @@
+//! Check the language server's results for small documents, and its handling of a whole session.
+
+// === Non-Standard Linter Configuration ===
+#![allow(clippy::option_map_unit_fn)]
+#![allow(clippy::precedence)]
+#![allow(dead_code)]
+#![deny(non_ascii_idents)]
+#![deny(unconditional_recursion)]
+#![warn(unsafe_code)]
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use enso_parser::Parser;
+use enso_parser_lsp::analysis;
+use enso_parser_lsp::analysis::Positions;
+use enso_parser_lsp::analysis::TokenType;
+use enso_parser_lsp::protocol;
+use enso_parser_lsp::protocol::FoldingRange;
+use enso_parser_lsp::protocol::Position;
+use enso_parser_lsp::protocol::Range;
+use enso_parser_lsp::protocol::SymbolKind;
+use enso_parser_lsp::Server;
+use serde_json::json;
+use serde_json::Value;
+use std::io::Write;
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[test]
+fn utf16_positions() {
+    let positions = Positions::new("a\r\nü🧑b\nc");
+    assert_eq!(positions.position(0), Position { line: 0, character: 0 });
+    assert_eq!(positions.position(3), Position { line: 1, character: 0 });
+    assert_eq!(positions.position(9), Position { line: 1, character: 3 });
+    assert_eq!(positions.position(10), Position { line: 1, character: 4 });
+    assert_eq!(positions.position(12), Position { line: 2, character: 1 });
+    assert_eq!(positions.position(100), Position { line: 2, character: 1 });
+}
+
+#[test]
+fn document_symbols() {
+    let code = "type Maybe\n    Some value\n    None\n    get self = 1\nmain =\n    f x = x\n";
+    let tree = Parser::new().run(code);
+    let symbols = analysis::document_symbols(&tree, &Positions::new(code));
+    let outline: Vec<_> = symbols
+        .iter()
+        .map(|symbol| {
+            let children: Vec<_> =
+                symbol.children.iter().map(|child| (child.name.as_str(), child.kind)).collect();
+            (symbol.name.as_str(), symbol.kind, children)
+        })
+        .collect();
+    assert_eq!(outline, [
+        ("Maybe", SymbolKind::Class, vec![
+            ("Some", SymbolKind::Constructor),
+            ("None", SymbolKind::Constructor),
+            ("get", SymbolKind::Method),
+        ]),
+        ("main", SymbolKind::Function, vec![("f", SymbolKind::Function)]),
+    ]);
+    let name = Range { start: pos(0, 5), end: pos(0, 10) };
+    assert_eq!(symbols[0].selection_range, name);
+    assert_eq!(symbols[0].range.start, pos(0, 0));
+    assert_eq!(symbols[1].selection_range, Range { start: pos(4, 0), end: pos(4, 4) });
+}
+
+#[test]
+fn folding_ranges() {
+    let code = "main =\n    x = 1\n    x\nfoo = 2\ntype T\n    A\n";
+    let tree = Parser::new().run(code);
+    let ranges = analysis::folding_ranges(&tree, &Positions::new(code));
+    let main = FoldingRange { start_line: 0, end_line: 2 };
+    let type_def = FoldingRange { start_line: 4, end_line: 5 };
+    assert_eq!(ranges, [main, type_def]);
+}
+
+#[test]
+fn diagnostics() {
+    let code = "x = '🧑' )";
+    let tree = Parser::new().run(code);
+    let diagnostics = analysis::diagnostics(&tree, &Positions::new(code));
+    let [diagnostic] = &diagnostics[..] else { panic!("{diagnostics:?}") };
+    assert_eq!(diagnostic.code, "E001");
+    assert_eq!(diagnostic.range, Range { start: pos(0, 9), end: pos(0, 10) });
+}
+
+#[test]
+fn semantic_tokens() {
+    let code = "x = 'é' # c\n## Doc\nType.f 1";
+    let tokens = analysis::semantic_tokens(code, &Positions::new(code));
+    use TokenType::*;
+    let expected = [
+        (0, 0, 1, Variable),
+        (0, 2, 1, Operator),
+        (0, 2, 1, String),
+        (0, 1, 1, String),
+        (0, 1, 1, String),
+        (0, 2, 3, Comment),
+        (1, 0, 2, Comment),
+        (0, 2, 4, Comment),
+        (1, 0, 4, Type),
+        (0, 4, 1, Operator),
+        (0, 1, 1, Variable),
+        (0, 2, 1, Number),
+    ];
+    let expected: Vec<u32> = expected
+        .into_iter()
+        .flat_map(|(line, character, length, kind)| [line, character, length, kind as u32, 0])
+        .collect();
+    assert_eq!(tokens.data, expected);
+}
+
+#[test]
+fn session() {
+    let uri = "file:///main.enso";
+    let messages = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": { "uri": uri, "languageId": "enso", "version": 1, "text": "x)" }
+        }}),
+        json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+            "textDocument": { "uri": uri, "version": 2 },
+            "contentChanges": [{ "text": "main = 1" }]
+        }}),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/documentSymbol", "params": {
+            "textDocument": { "uri": uri }
+        }}),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "unknown", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ];
+    let mut input = vec![];
+    for message in &messages {
+        protocol::write_message(&mut input, message).unwrap();
+    }
+    let (shutdown, responses) = run_server(&input);
+    assert!(shutdown);
+    let [initialize, opened, changed, symbols, unknown, shutdown] = &responses[..] else {
+        panic!("{responses:#?}")
+    };
+    assert_eq!(initialize["result"]["capabilities"]["documentSymbolProvider"], true);
+    assert_eq!(opened["method"], "textDocument/publishDiagnostics");
+    assert_eq!(opened["params"]["diagnostics"][0]["code"], "E001");
+    assert_eq!(changed["params"]["diagnostics"], json!([]));
+    assert_eq!(symbols["id"], 2);
+    assert_eq!(symbols["result"][0]["name"], "main");
+    assert_eq!(symbols["result"][0]["kind"], 12);
+    assert_eq!(unknown["error"]["code"], protocol::error_code::METHOD_NOT_FOUND);
+    assert_eq!(shutdown["result"], Value::Null);
+}
+
+#[test]
+fn malformed_message() {
+    let mut input = vec![];
+    let malformed = "{ \"jsonrpc\": \"2.0\", \"id\": 1,";
+    write!(input, "Content-Length: {}\r\n\r\n{malformed}", malformed.len()).unwrap();
+    let initialize = json!({ "jsonrpc": "2.0", "id": 2, "method": "initialize", "params": {} });
+    protocol::write_message(&mut input, &initialize).unwrap();
+    let (shutdown, responses) = run_server(&input);
+    assert!(!shutdown);
+    let [parse_error, initialize] = &responses[..] else { panic!("{responses:#?}") };
+    assert_eq!(parse_error["id"], Value::Null);
+    assert_eq!(parse_error["error"]["code"], protocol::error_code::PARSE_ERROR);
+    assert_eq!(initialize["id"], 2);
+    assert_eq!(initialize["result"]["capabilities"]["documentSymbolProvider"], true);
+}
+
+#[test]
+fn requests_after_shutdown() {
+    let messages = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ];
+    let mut input = vec![];
+    for message in &messages {
+        protocol::write_message(&mut input, message).unwrap();
+    }
+    let (shutdown, responses) = run_server(&input);
+    assert!(shutdown);
+    let [_, shutdown, rejected] = &responses[..] else { panic!("{responses:#?}") };
+    assert_eq!(shutdown["result"], Value::Null);
+    assert_eq!(rejected["id"], 3);
+    assert_eq!(rejected["error"]["code"], protocol::error_code::INVALID_REQUEST);
+}
+
+
+
+// ====================
+// === Test Support ===
+// ====================
+
+/// Run a server on the given input. Return whether a shutdown was requested, and the messages
+/// written by the server.
+fn run_server(input: &[u8]) -> (bool, Vec<Value>) {
+    let mut output = vec![];
+    let shutdown = Server::new().run(input, &mut output).unwrap();
+    let mut output = &output[..];
+    let mut messages = vec![];
+    while let Some(message) = protocol::read_message(&mut output).unwrap() {
+        messages.push(message);
+    }
+    (shutdown, messages)
+}
+
+fn pos(line: u32, character: u32) -> Position {
+    Position { line, character }
+}
@@ -0,0 +1,223 @@
+//! Diagnostics reported for the syntax errors found in a parsed tree.
+//!
+//! Errors are represented in the tree in a few ways: most are [`Invalid`](tree::Variant::Invalid)
+//! nodes wrapping the erroneous code, but some are encoded in the structure of otherwise-valid
+//! nodes (such as [`tree::MultipleOperatorError`]s, and escape sequences that don't denote a
+//! character). [`collect`] finds all of them, and locates them in the source code.
+
+use crate::prelude::*;
+
+use crate::incremental::Edit;
+use crate::syntax::item;
+use crate::syntax::token;
+use crate::syntax::tree;
+use crate::syntax::tree::ErrorKind;
+use crate::syntax::tree::ItemVisitable;
+use crate::syntax::tree::ItemVisitor;
+use crate::syntax::tree::Visitor;
+use crate::syntax::Tree;
+
+
+
+// ==================
+// === Diagnostic ===
+// ==================
+
+/// A syntax error, located in the source code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What is wrong with the code.
+    pub kind:            ErrorKind,
+    /// The byte range of the code the error is reported for.
+    pub span:            Range<usize>,
+    /// Other parts of the code involved in the error.
+    pub secondary_spans: Vec<SecondarySpan>,
+    /// Changes to the code that would resolve the error.
+    pub fixes:           Vec<Fix>,
+}
+
+impl Diagnostic {
+    /// Constructor.
+    pub fn new(kind: ErrorKind, span: Range<usize>) -> Self {
+        Self { kind, span, secondary_spans: default(), fixes: default() }
+    }
+
+    /// The stable identifier of the kind of the error.
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// A human-readable description of the error.
+    pub fn message(&self) -> String {
+        self.kind.to_string()
+    }
+}
+
+/// A part of the code involved in an error, other than its primary location.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecondarySpan {
+    /// The byte range of the code.
+    pub span:  Range<usize>,
+    /// The role of the code in the error.
+    pub label: Cow<'static, str>,
+}
+
+/// A machine-applicable change that would resolve an error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    /// A short description of the change, suitable for a quick-fix menu.
+    pub title: String,
+    /// The edits making up the change. Like the edits passed to
+    /// [`Parser::run_incremental`](crate::Parser::run_incremental), each edit applies to the
+    /// result of the previous ones.
+    pub edits: Vec<Edit<'static>>,
+}
+
+impl Fix {
+    /// Constructor.
+    pub fn new(title: impl Into<String>, edits: Vec<Edit<'static>>) -> Self {
+        let title = title.into();
+        Self { title, edits }
+    }
+}
+
+
+
+// ===============
+// === Collect ===
+// ===============
+
+/// Return the diagnostics for all syntax errors in the tree, in the order of their locations in
+/// the source code. The tree is expected to be the root of a parsed module, as returned by
+/// [`crate::Parser::run`].
+pub fn collect(tree: &Tree) -> Vec<Diagnostic> {
+    let mut collector = Collector::default();
+    tree.visit_item(&mut collector);
+    // Diagnostics are found when the traversal enters the nodes they are reported for, which is
+    // usually, but not always, in order of their locations.
+    collector.diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    collector.diagnostics
+}
+
+/// Visitor tracking the position of each visited item in the source code.
+#[derive(Debug, Default)]
+struct Collector {
+    offset:      usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Visitor for Collector {}
+impl<'s, 'a> ItemVisitor<'s, 'a> for Collector {
+    fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+        match item {
+            item::Ref::Token(token) => {
+                let start = self.offset + token.left_offset.code.repr.len();
+                let end = start + token.code.repr.len();
+                self.token(token, start..end);
+                self.offset = end;
+            }
+            item::Ref::Tree(tree) => {
+                self.offset += tree.span.left_offset.code.repr.len();
+                let span = self.offset..self.offset + tree.span.code_length.utf8_bytes();
+                self.tree(tree, span);
+            }
+        }
+        true
+    }
+}
+
+impl Collector {
+    fn token(&mut self, token: token::Ref, span: Range<usize>) {
+        if let token::Variant::TextEscape(escape) = token.data && escape.value.is_none() {
+            let escape = token.code.to_string();
+            let mut diagnostic = Diagnostic::new(ErrorKind::InvalidEscapeSequence { escape }, span);
+            let backslash = Edit::new(diagnostic.span.start..diagnostic.span.start, "\\");
+            diagnostic.fixes.push(Fix::new("Escape the backslash", vec![backslash]));
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn tree(&mut self, ast: &Tree, span: Range<usize>) {
+        match &*ast.variant {
+            tree::Variant::Invalid(invalid) => self.invalid(invalid, span),
+            tree::Variant::OprApp(tree::OprApp { lhs, opr: Err(error), .. }) => {
+                let mut offset = span.start;
+                if let Some(lhs) = lhs {
+                    offset += lhs.span.left_offset.code.repr.len();
+                    offset += lhs.span.code_length.utf8_bytes();
+                }
+                let mut operators = vec![];
+                for operator in error.operators.iter() {
+                    let start = offset + operator.left_offset.code.repr.len();
+                    offset = start + operator.code.repr.len();
+                    operators.push((start..offset, operator.code.to_string()));
+                }
+                let first = operators.first().map_or(span.start, |(span, _)| span.start);
+                let last = operators.last().map_or(span.end, |(span, _)| span.end);
+                let mut diagnostic = Diagnostic::new(ErrorKind::MultipleOperators, first..last);
+                for (span, code) in operators {
+                    let label = "operator".into();
+                    diagnostic.secondary_spans.push(SecondarySpan { span: span.clone(), label });
+                    let title = format!("Remove `{code}`");
+                    diagnostic.fixes.push(Fix::new(title, vec![Edit::new(span, "")]));
+                }
+                self.diagnostics.push(diagnostic);
+            }
+            _ => {}
+        }
+    }
+
+    fn invalid(&mut self, invalid: &tree::Invalid, span: Range<usize>) {
+        let mut diagnostic = Diagnostic::new(invalid.error.kind.clone(), span.clone());
+        match &invalid.error.kind {
+            ErrorKind::UnmatchedDelimiter => {
+                let code = invalid.ast.trimmed_code();
+                match closing_delimiter(&code) {
+                    // A group that was not closed: the problem is reported at its opening
+                    // delimiter, which can be removed, or matched at the end of the group.
+                    Some(close) => {
+                        let open = span.start..span.start + 1;
+                        diagnostic.span = open.clone();
+                        let title = format!("Remove unmatched `{}`", &code[..1]);
+                        diagnostic.fixes.push(Fix::new(title, vec![Edit::new(open, "")]));
+                        let title = format!("Insert `{close}`");
+                        let insert = Edit::new(span.end..span.end, close);
+                        diagnostic.fixes.push(Fix::new(title, vec![insert]));
+                    }
+                    None => {
+                        let title = format!("Remove unmatched `{code}`");
+                        diagnostic.fixes.push(Fix::new(title, vec![Edit::new(span, "")]));
+                    }
+                }
+            }
+            ErrorKind::UnterminatedText => {
+                let quote = if invalid.ast.trimmed_code().starts_with('\'') { "'" } else { "\"" };
+                let title = "Insert the closing quote";
+                diagnostic.fixes.push(Fix::new(title, vec![Edit::new(span.end..span.end, quote)]));
+            }
+            ErrorKind::UnexpectedMacroTokens => {
+                let title = "Remove unexpected tokens";
+                diagnostic.fixes.push(Fix::new(title, vec![Edit::new(span, "")]));
+            }
+            ErrorKind::UnaryOperatorRequiresSpace => {
+                let title = "Insert a space before the operator";
+                diagnostic
+                    .fixes
+                    .push(Fix::new(title, vec![Edit::new(span.start..span.start, " ")]));
+            }
+            _ => {}
+        }
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+/// If the code starts with an opening delimiter, return the delimiter closing it.
+fn closing_delimiter(code: &str) -> Option<&'static str> {
+    match code.chars().next()? {
+        '(' => Some(")"),
+        '[' => Some("]"),
+        '{' => Some("}"),
+        '`' => Some("`"),
+        _ => None,
+    }
+}
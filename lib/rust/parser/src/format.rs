@@ -0,0 +1,461 @@
+//! Canonical source code formatting of syntax trees.
+//!
+//! The formatter prints a [`Tree`] in a canonical layout:
+//! - Every line of a block is indented by [`INDENT`] spaces per block nesting level.
+//! - Whitespace between the tokens of a line is collapsed to a single space. Because spacing
+//!   affects operator precedence in Enso, tokens written without whitespace between them (like
+//!   `a.b` or `x+1`) are kept together.
+//! - Runs of empty lines are collapsed to a single empty line. Empty lines at the beginning and end
+//!   of a block are removed.
+//! - Definitions at the top level of a module or a type are separated from their neighbours by an
+//!   empty line. A type signature stays attached to the definition following it.
+//! - Comments and documentation are preserved. The content of text literals and documentation
+//!   comments is printed verbatim; only the indentation of their lines is adjusted.
+//!
+//! Formatting is idempotent: the formatter leaves its own output unchanged.
+
+use crate::prelude::*;
+
+use crate::syntax::item;
+use crate::syntax::token;
+use crate::syntax::tree::block;
+use crate::syntax::tree::ArgumentDefinition;
+use crate::syntax::tree::Case;
+use crate::syntax::tree::CodePrinterVisitor;
+use crate::syntax::tree::DocComment;
+use crate::syntax::tree::ItemVisitable;
+use crate::syntax::tree::ItemVisitor;
+use crate::syntax::tree::Variant;
+use crate::syntax::tree::Visitor;
+use crate::syntax::Tree;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Number of spaces each block nesting level is indented by.
+pub const INDENT: usize = 4;
+
+
+
+// ==============
+// === Format ===
+// ==============
+
+/// Print the given tree in the canonical layout. The tree is expected to be the root of a parsed
+/// module, as returned by [`crate::Parser::run`].
+pub fn format(tree: &Tree) -> String {
+    let mut formatter = Formatter::default();
+    match &*tree.variant {
+        Variant::BodyBlock(block) => formatter.lines(block.statements.iter().map(line), true),
+        _ => formatter.tree(tree),
+    }
+    if !formatter.out.is_empty() {
+        formatter.out.push('\n');
+    }
+    formatter.out
+}
+
+
+
+// =================
+// === Formatter ===
+// =================
+
+#[derive(Debug, Default)]
+struct Formatter {
+    out:           String,
+    /// Block nesting level of the current line.
+    depth:         usize,
+    /// Indentation of the current line in the input.
+    source_indent: usize,
+    /// Whether the next written code is separated from the preceding code by whitespace.
+    space:         bool,
+    /// Whether no code has been written on the current line yet.
+    at_line_start: bool,
+}
+
+
+// === Output ===
+
+impl Formatter {
+    /// Start a new line; if `count` is greater than one, insert empty lines before it.
+    fn line_break(&mut self, count: usize) {
+        if !self.out.is_empty() {
+            self.out.extend(iter::repeat('\n').take(count));
+            self.out.extend(iter::repeat(' ').take(self.depth * INDENT));
+        }
+        self.at_line_start = true;
+        self.space = false;
+    }
+
+    fn write(&mut self, code: &str, spaced: bool) {
+        if code.is_empty() {
+            self.space |= spaced;
+            return;
+        }
+        if (self.space || spaced) && !self.at_line_start {
+            self.out.push(' ');
+        }
+        self.out.push_str(code);
+        self.at_line_start = false;
+        self.space = false;
+    }
+
+    fn comment(&mut self, comment: &str) {
+        self.write(comment.trim_end(), true);
+    }
+
+    /// Write code printed verbatim from the input, adjusting the indentation of its lines.
+    fn verbatim(&mut self, code: &str) {
+        let code = reindent(code, self.source_indent, self.depth * INDENT);
+        self.write(&code, false);
+    }
+
+    /// Print the content of a block, one level deeper than the current line.
+    fn block(&mut self, f: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+    }
+}
+
+
+// === Items ===
+
+impl Formatter {
+    fn items<'s: 'a, 'a>(&mut self, value: &'a impl ItemVisitable<'s, 'a>) {
+        for item in children(value) {
+            match item {
+                item::Ref::Token(token) => self.token(token),
+                item::Ref::Tree(tree) => self.subtree(tree),
+            }
+        }
+    }
+
+    fn token(&mut self, item: token::Ref) {
+        let code = &item.code.repr;
+        match item.data {
+            // Line breaks are handled by the printers of the trees containing them.
+            token::Variant::Newline(_) if is_comment(code) => self.comment(code),
+            token::Variant::Newline(_) => self.line_break(1),
+            _ => self.write(code, item.left_offset.exists()),
+        }
+    }
+
+    fn subtree(&mut self, tree: &Tree) {
+        if !starts_with_block(tree) {
+            self.space |= tree.span.left_offset.exists();
+        }
+        self.tree(tree);
+    }
+
+    fn tree(&mut self, tree: &Tree) {
+        match &*tree.variant {
+            Variant::BodyBlock(block) =>
+                self.block(|f| f.lines(block.statements.iter().map(line), false)),
+            Variant::ArgumentBlockApplication(app) => {
+                self.items(&app.lhs);
+                self.block(|f| f.lines(app.arguments.iter().map(line), false));
+            }
+            Variant::OperatorBlockApplication(app) => {
+                self.items(&app.lhs);
+                let operator_lines = app.expressions.iter().map(|line| {
+                    (Some(&line.newline), line.expression.as_ref().map(LineContent::Operator))
+                });
+                let lines = operator_lines.chain(app.excess.iter().map(line));
+                self.block(|f| f.lines(lines, false));
+            }
+            Variant::TypeDef(def) => {
+                self.items(&def.keyword);
+                self.items(&def.name);
+                self.items(&def.params);
+                self.block(|f| f.lines(def.body.iter().map(line), true));
+            }
+            Variant::CaseOf(case_of) => {
+                self.items(&case_of.case);
+                self.items(&case_of.expression);
+                self.items(&case_of.of);
+                let lines = case_of
+                    .cases
+                    .iter()
+                    .map(|line| (line.newline.as_ref(), line.case.as_ref().map(LineContent::Case)));
+                self.block(|f| f.lines(lines, false));
+            }
+            Variant::ConstructorDefinition(def) => {
+                self.items(&def.constructor);
+                self.items(&def.arguments);
+                let lines = def.block.iter().map(|line| {
+                    (Some(&line.newline), line.argument.as_ref().map(LineContent::Argument))
+                });
+                self.block(|f| f.lines(lines, false));
+            }
+            Variant::TextLiteral(_) => self.verbatim(&tree.trimmed_code()),
+            Variant::Documented(documented) => {
+                self.doc_comment(&documented.documentation);
+                if let Some(expression) = &documented.expression {
+                    self.line_break(1);
+                    self.subtree(expression);
+                }
+            }
+            Variant::Annotated(annotated) => {
+                self.items(&annotated.token);
+                self.items(&annotated.annotation);
+                self.items(&annotated.argument);
+                self.annotated_expression(&annotated.newlines, &annotated.expression);
+            }
+            Variant::AnnotatedBuiltin(annotated) => {
+                self.items(&annotated.token);
+                self.items(&annotated.annotation);
+                self.annotated_expression(&annotated.newlines, &annotated.expression);
+            }
+            _ => self.items(&tree.variant),
+        }
+    }
+
+    fn doc_comment(&mut self, doc: &DocComment) {
+        let mut printer = CodePrinterVisitor::default();
+        printer.code.push_str(&doc.open.code.repr);
+        doc.elements.visit_item(&mut printer);
+        self.verbatim(&printer.code);
+        self.comments(&doc.newlines);
+    }
+
+    fn annotated_expression(&mut self, newlines: &[token::Newline], expression: &Option<Tree>) {
+        self.comments(newlines);
+        if let Some(expression) = expression {
+            if !newlines.is_empty() {
+                self.line_break(1);
+            }
+            self.subtree(expression);
+        }
+    }
+
+    /// Print the comments found in a sequence of line breaks between a prefix (like an annotation
+    /// or documentation) and the item it applies to. Empty lines are dropped.
+    fn comments(&mut self, newlines: &[token::Newline]) {
+        let mut line_ended = false;
+        for newline in newlines {
+            if is_comment(&newline.code.repr) {
+                if line_ended {
+                    self.line_break(1);
+                    line_ended = false;
+                }
+                self.comment(&newline.code.repr);
+            } else {
+                line_ended = true;
+            }
+        }
+    }
+
+    fn case(&mut self, case: &Case) {
+        if let Some(doc) = &case.documentation {
+            self.doc_comment(doc);
+            self.line_break(1);
+        }
+        self.items(&case.pattern);
+        self.items(&case.arrow);
+        self.items(&case.expression);
+    }
+}
+
+
+// === Lines ===
+
+/// The content of a line of a block.
+#[derive(Debug, Clone, Copy)]
+enum LineContent<'s, 'a> {
+    Tree(&'a Tree<'s>),
+    Operator(&'a block::OperatorBlockExpression<'s>),
+    Case(&'a Case<'s>),
+    Argument(&'a ArgumentDefinition<'s>),
+}
+
+impl<'s, 'a> LineContent<'s, 'a> {
+    /// The indentation of the line in the input.
+    fn source_indent(self) -> usize {
+        let first = match self {
+            LineContent::Tree(tree) => children(tree).into_iter().next(),
+            LineContent::Operator(expression) => children(expression).into_iter().next(),
+            LineContent::Case(case) => children(case).into_iter().next(),
+            LineContent::Argument(argument) => children(argument).into_iter().next(),
+        };
+        first.map_or(0, |item| match item {
+            item::Ref::Token(token) => token.left_offset.visible.width_in_spaces,
+            item::Ref::Tree(tree) => tree.span.left_offset.visible.width_in_spaces,
+        })
+    }
+
+    fn is_definition(self) -> bool {
+        matches!(self, LineContent::Tree(tree) if is_definition(tree))
+    }
+
+    fn is_type_signature(self) -> bool {
+        let signature = |tree: &Tree| matches!(&*tree.variant, Variant::TypeSignature(_));
+        matches!(self, LineContent::Tree(tree) if signature(tree))
+    }
+}
+
+/// A line of a block, as found in the tree: the token beginning the line, and the line's content.
+type RawLine<'s, 'a> = (Option<&'a token::Newline<'s>>, Option<LineContent<'s, 'a>>);
+
+fn line<'s, 'a>(line: &'a block::Line<'s>) -> RawLine<'s, 'a> {
+    (Some(&line.newline), line.expression.as_ref().map(LineContent::Tree))
+}
+
+/// A line of a block, as it appears in the source code. A comment is lexed as a line break, so it
+/// begins a new line in the tree; here it is attached to the line it is written on.
+#[derive(Debug, Clone, Copy)]
+struct SourceLine<'s, 'a> {
+    content: Option<LineContent<'s, 'a>>,
+    comment: Option<&'a str>,
+}
+
+impl<'s, 'a> SourceLine<'s, 'a> {
+    fn is_empty(self) -> bool {
+        self.content.is_none() && self.comment.is_none()
+    }
+
+    /// Whether an empty line should be inserted between the two lines, when separating
+    /// definitions.
+    fn is_separated_from(self, previous: Self) -> bool {
+        let follows_definition = previous.content.map_or(false, LineContent::is_definition);
+        let is_definition = self.content.map_or(false, LineContent::is_definition);
+        let attached = previous.content.map_or(true, LineContent::is_type_signature);
+        follows_definition || (is_definition && !attached)
+    }
+}
+
+impl Formatter {
+    fn lines<'s: 'a, 'a>(
+        &mut self,
+        lines: impl IntoIterator<Item = RawLine<'s, 'a>>,
+        separate_definitions: bool,
+    ) {
+        let mut header_comment = None;
+        let mut source_lines: Vec<SourceLine> = default();
+        for (newline, content) in lines {
+            match newline.map(|newline| &*newline.code.repr) {
+                Some(comment) if is_comment(comment) => {
+                    match source_lines.last_mut() {
+                        Some(line) => line.comment = Some(comment),
+                        None => header_comment = Some(comment),
+                    }
+                    if content.is_some() {
+                        source_lines.push(SourceLine { content, comment: None });
+                    }
+                }
+                _ => source_lines.push(SourceLine { content, comment: None }),
+            }
+        }
+        if let Some(comment) = header_comment {
+            self.comment(comment);
+        }
+        let mut previous: Option<SourceLine> = None;
+        let mut empty_line = false;
+        for line in source_lines {
+            if line.is_empty() {
+                empty_line |= previous.is_some();
+                continue;
+            }
+            let separated = previous.map_or(false, |previous| {
+                empty_line || (separate_definitions && line.is_separated_from(previous))
+            });
+            self.line_break(if separated { 2 } else { 1 });
+            if let Some(content) = line.content {
+                self.line_content(content);
+            }
+            if let Some(comment) = line.comment {
+                self.comment(comment);
+            }
+            previous = Some(line);
+            empty_line = false;
+        }
+    }
+
+    fn line_content(&mut self, content: LineContent) {
+        let source_indent = mem::replace(&mut self.source_indent, content.source_indent());
+        match content {
+            LineContent::Tree(tree) => self.tree(tree),
+            LineContent::Operator(expression) => self.items(expression),
+            LineContent::Case(case) => self.case(case),
+            LineContent::Argument(argument) => self.items(argument),
+        }
+        self.source_indent = source_indent;
+    }
+}
+
+
+
+// ===============
+// === Helpers ===
+// ===============
+
+/// Return the [`Token`]s and [`Tree`]s that are direct children of the given value.
+fn children<'s: 'a, 'a>(value: &'a impl ItemVisitable<'s, 'a>) -> Vec<item::Ref<'s, 'a>> {
+    #[derive(Debug, Default)]
+    struct ChildrenVisitor<'s, 'a> {
+        children: Vec<item::Ref<'s, 'a>>,
+    }
+    impl<'s, 'a> Visitor for ChildrenVisitor<'s, 'a> {}
+    impl<'s, 'a> ItemVisitor<'s, 'a> for ChildrenVisitor<'s, 'a> {
+        fn visit_item(&mut self, item: item::Ref<'s, 'a>) -> bool {
+            self.children.push(item);
+            false
+        }
+    }
+    let mut visitor = ChildrenVisitor::default();
+    value.visit_item(&mut visitor);
+    visitor.children
+}
+
+/// Whether the given line break token is a comment. Comments are lexed as line breaks.
+fn is_comment(newline: &str) -> bool {
+    newline.starts_with('#')
+}
+
+/// Whether the tree begins with a block, i.e. on a new line.
+fn starts_with_block(tree: &Tree) -> bool {
+    match &*tree.variant {
+        Variant::BodyBlock(_) => true,
+        Variant::ArgumentBlockApplication(app) => app.lhs.is_none(),
+        Variant::OperatorBlockApplication(app) => app.lhs.is_none(),
+        _ => false,
+    }
+}
+
+/// Whether the statement defines a method, function, or type.
+fn is_definition(tree: &Tree) -> bool {
+    match &*tree.variant {
+        Variant::Function(_) | Variant::ForeignFunction(_) | Variant::TypeDef(_) => true,
+        Variant::Documented(documented) =>
+            documented.expression.as_ref().map_or(false, is_definition),
+        Variant::Annotated(annotated) => annotated.expression.as_ref().map_or(false, is_definition),
+        Variant::AnnotatedBuiltin(annotated) =>
+            annotated.expression.as_ref().map_or(false, is_definition),
+        _ => false,
+    }
+}
+
+/// Change the indentation of every line but the first from `from` to `to` spaces. Lines indented
+/// by less than `from` spaces are left unchanged.
+fn reindent(code: &str, from: usize, to: usize) -> String {
+    if from == to {
+        return code.to_owned();
+    }
+    let mut lines = code.split('\n');
+    let mut result = lines.next().unwrap_or_default().to_owned();
+    for line in lines {
+        result.push('\n');
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        if indent >= from && indent < line.trim_end_matches('\r').len() {
+            result.extend(iter::repeat(' ').take(to));
+            result.push_str(&line[from..]);
+        } else {
+            result.push_str(line);
+        }
+    }
+    result
+}
@@ -0,0 +1,357 @@
+//! Incremental reparsing. After the source code has been edited, the top-level lines of the
+//! [`BodyBlock`] that were not affected by the edits are reused from the previous [`Tree`], and
+//! only the damaged region is parsed again. The result is equal to a full parse of the new code.
+//!
+//! [`BodyBlock`]: tree::BodyBlock
+
+use crate::prelude::*;
+
+use crate::source::Code;
+use crate::source::Offset;
+use crate::syntax::item;
+use crate::syntax::token;
+use crate::syntax::tree;
+use crate::syntax::tree::block;
+use crate::syntax::tree::ItemVisitableMut;
+use crate::syntax::Tree;
+use crate::Parser;
+
+
+
+// ============
+// === Edit ===
+// ============
+
+/// A single text edit: the given byte range of the source is replaced with the given text. When a
+/// sequence of edits is given, every edit is applied to the result of the previous ones, just like
+/// consecutive calls to `enso_text::Text::replace`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Edit<'a> {
+    /// Byte range of the text being replaced.
+    pub range: Range<usize>,
+    /// The text inserted in place of `range`.
+    pub text:  &'a str,
+}
+
+impl<'a> Edit<'a> {
+    /// Constructor.
+    pub fn new(range: Range<usize>, text: &'a str) -> Self {
+        Self { range, text }
+    }
+
+    /// Apply the edit to the given string.
+    pub fn apply(&self, target: &mut String) {
+        target.replace_range(self.range.clone(), self.text);
+    }
+}
+
+
+
+// ==============
+// === Damage ===
+// ==============
+
+/// The region of the source affected by a sequence of edits. The code before `start` is not
+/// changed; the code after `old_end` in the old source is the same as the code after `new_end` in
+/// the new source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Damage {
+    start:   usize,
+    old_end: usize,
+    new_end: usize,
+}
+
+impl Damage {
+    /// Compute the region affected by the given edits. Returns [`None`] if there are no edits.
+    fn new(edits: &[Edit]) -> Option<Self> {
+        let mut damaged: Option<Range<usize>> = None;
+        let mut delta = 0isize;
+        for edit in edits {
+            let Range { start, end } = edit.range.clone();
+            let inserted_end = start + edit.text.len();
+            let edit_delta = edit.text.len() as isize - (end - start) as isize;
+            let map = |pos: usize, inside: usize| match pos {
+                _ if pos <= start => pos,
+                _ if pos >= end => (pos as isize + edit_delta) as usize,
+                _ => inside,
+            };
+            damaged = Some(match damaged {
+                Some(range) => {
+                    let damaged_start = map(range.start, start).min(start);
+                    let damaged_end = map(range.end, inserted_end).max(inserted_end);
+                    damaged_start..damaged_end
+                }
+                None => start..inserted_end,
+            });
+            delta += edit_delta;
+        }
+        damaged.map(|Range { start, end }| {
+            let old_end = (end as isize - delta) as usize;
+            Self { start, old_end, new_end: end }
+        })
+    }
+
+    /// The difference between the new and the old source length.
+    fn delta(&self) -> isize {
+        self.new_end as isize - self.old_end as isize
+    }
+}
+
+
+
+// =================
+// === Reparsing ===
+// =================
+
+/// Byte offsets of a top-level line in the old source.
+#[derive(Clone, Copy, Debug)]
+struct LineRange {
+    /// Start of the line's `newline` token, including its left offset.
+    start:         usize,
+    /// End of the line's `newline` token; this is where the line's content begins.
+    content_start: usize,
+    /// End of the line's content.
+    end:           usize,
+}
+
+/// Parse `new_code`, reusing the unaffected top-level lines of `old_tree`. See
+/// [`Parser::run_incremental`].
+pub(crate) fn reparse<'o, 's>(
+    parser: &Parser,
+    old_tree: Tree<'o>,
+    old_code: &'o str,
+    edits: &[Edit],
+    new_code: &'s str,
+) -> Tree<'s> {
+    let Some(damage) = Damage::new(edits) else {
+        return Rebase::new(old_code, new_code, 0).tree(old_tree);
+    };
+    let (old_left_offset, old_lines) = match old_tree {
+        Tree { span, variant: box tree::Variant::BodyBlock(tree::BodyBlock { statements }) } =>
+            (span.left_offset, statements),
+        _ => return parser.run(new_code),
+    };
+    let Some(ranges) = line_ranges(&old_left_offset, &old_lines, old_code.len()) else {
+        return parser.run(new_code);
+    };
+    let is_damaged = |range: &LineRange| damage.start <= range.end && damage.old_end >= range.start;
+    let Some(first_damaged) = ranges.iter().position(is_damaged) else {
+        return parser.run(new_code);
+    };
+    let last_damaged = ranges.iter().rposition(is_damaged).unwrap_or(first_damaged);
+    // A line that becomes indented joins the block of the closest non-empty line before it, so
+    // that line has to be parsed again as well.
+    let first = (0..first_damaged)
+        .rev()
+        .find(|&index| old_lines[index].expression.is_some())
+        .unwrap_or_default();
+    let mut last = (last_damaged + 1).min(old_lines.len() - 1);
+    let region_start = if first == 0 { 0 } else { ranges[first].content_start };
+    let (mut region, region_left_offset) = loop {
+        let region_end = match ranges.get(last + 1) {
+            Some(next) => (next.start as isize + damage.delta()) as usize,
+            None => new_code.len(),
+        };
+        let Some(region_code) = new_code.get(region_start..region_end) else {
+            return parser.run(new_code);
+        };
+        let (left_offset, lines) = match parser.run(region_code) {
+            Tree {
+                span,
+                variant: box tree::Variant::BodyBlock(tree::BodyBlock { statements }),
+            } => (span.left_offset, statements),
+            _ => return parser.run(new_code),
+        };
+        // A trailing annotation or documentation comment would be attached to the next statement
+        // in a full parse; unless the region reaches the end of the source, include the next line
+        // and try again.
+        if last + 1 < old_lines.len() && ends_with_prefix(&lines) {
+            last += 1;
+            continue;
+        }
+        break (lines, left_offset);
+    };
+    if first > 0 && region_left_offset.exists() {
+        return parser.run(new_code);
+    }
+    let mut prefix = Rebase::new(old_code, new_code, 0);
+    let mut suffix = Rebase::new(old_code, new_code, damage.delta());
+    let mut old_lines = old_lines.into_iter();
+    let mut statements: Vec<_> = (&mut old_lines).take(first).map(|l| prefix.line(l)).collect();
+    let region_newline = old_lines.next().map(|line| line.newline);
+    if first > 0 && let Some(newline) = region_newline && let Some(line) = region.first_mut() {
+        // The region was parsed as if it was at the beginning of the source, so its first line
+        // lacks the token ending the previous line.
+        line.newline = prefix.newline(newline);
+    }
+    statements.extend(region);
+    statements.extend(old_lines.skip(last - first).map(|line| suffix.line(line)));
+    let mut result = Tree::body_block(statements);
+    result.span.left_offset = match first {
+        0 => region_left_offset,
+        _ => prefix.offset(old_left_offset),
+    };
+    result
+}
+
+/// Compute the location of every top-level line in the source. Returns [`None`] if the lines do
+/// not cover the whole source.
+fn line_ranges(
+    left_offset: &Offset,
+    lines: &[block::Line],
+    code_len: usize,
+) -> Option<Vec<LineRange>> {
+    let mut position = left_offset.code.repr.len();
+    let ranges = lines
+        .iter()
+        .map(|line| {
+            let start = position;
+            position += line.newline.left_offset.code.repr.len() + line.newline.code.repr.len();
+            let content_start = position;
+            if let Some(expression) = &line.expression {
+                position += expression.span.left_offset.code.repr.len();
+                position += expression.span.code_length.utf8_bytes();
+            }
+            LineRange { start, content_start, end: position }
+        })
+        .collect();
+    (position == code_len).then_some(ranges)
+}
+
+/// Return whether the last non-empty line is an annotation or a documentation comment that is not
+/// attached to any statement.
+fn ends_with_prefix(lines: &[block::Line]) -> bool {
+    let mut expression = lines.iter().rev().find_map(|line| line.expression.as_ref());
+    let mut is_prefix = false;
+    while let Some(tree) = expression {
+        expression = match &*tree.variant {
+            tree::Variant::Annotated(annotated) => annotated.expression.as_ref(),
+            tree::Variant::AnnotatedBuiltin(annotated) => annotated.expression.as_ref(),
+            tree::Variant::Documented(documented) => documented.expression.as_ref(),
+            _ => return false,
+        };
+        is_prefix = true;
+    }
+    is_prefix
+}
+
+
+
+// ==============
+// === Rebase ===
+// ==============
+
+/// Moves reused syntax from the old source to the new one: every code fragment borrowed from the
+/// old source is replaced by the same fragment of the new source, located `shift` bytes further.
+///
+/// Code that was not borrowed from the old source is made owned, so after rebasing nothing in the
+/// value is borrowed from anything other than the new source. This is what makes it sound to
+/// change the lifetime of the rebased values to the lifetime of the new source.
+#[derive(Debug)]
+struct Rebase<'s> {
+    old_code: Range<usize>,
+    new_code: &'s str,
+    shift:    isize,
+}
+
+impl<'s> Rebase<'s> {
+    fn new(old_code: &str, new_code: &'s str, shift: isize) -> Self {
+        let start = old_code.as_ptr() as usize;
+        let old_code = start..start + old_code.len();
+        Self { old_code, new_code, shift }
+    }
+
+    #[allow(unsafe_code)]
+    fn line<'o>(&mut self, mut line: block::Line<'o>) -> block::Line<'s> {
+        line.visit_item_mut(self);
+        // SAFETY: The value has been rebased. See the docs of [`Rebase`].
+        unsafe { mem::transmute::<block::Line<'o>, block::Line<'s>>(line) }
+    }
+
+    #[allow(unsafe_code)]
+    fn tree<'o>(&mut self, mut tree: Tree<'o>) -> Tree<'s> {
+        tree.visit_item_mut(self);
+        // SAFETY: The value has been rebased. See the docs of [`Rebase`].
+        unsafe { mem::transmute::<Tree<'o>, Tree<'s>>(tree) }
+    }
+
+    #[allow(unsafe_code)]
+    fn newline<'o>(&mut self, mut newline: token::Newline<'o>) -> token::Newline<'s> {
+        newline.visit_item_mut(self);
+        // SAFETY: The value has been rebased. See the docs of [`Rebase`].
+        unsafe { mem::transmute::<token::Newline<'o>, token::Newline<'s>>(newline) }
+    }
+
+    #[allow(unsafe_code)]
+    fn offset<'o>(&mut self, mut offset: Offset<'o>) -> Offset<'s> {
+        self.code(&mut offset.code);
+        // SAFETY: The value has been rebased. See the docs of [`Rebase`].
+        unsafe { mem::transmute::<Offset<'o>, Offset<'s>>(offset) }
+    }
+
+    /// Point the code to the new source. Code that was not borrowed from the old source is made
+    /// owned, so that afterwards nothing is borrowed from anything other than the new source.
+    #[allow(unsafe_code)]
+    fn code(&self, code: &mut Code) {
+        if let Cow::Borrowed(str) = code.repr {
+            let address = str.as_ptr() as usize;
+            let in_old_code =
+                address >= self.old_code.start && address + str.len() <= self.old_code.end;
+            let new_start = address as isize - self.old_code.start as isize + self.shift;
+            let new_range = new_start as usize..new_start as usize + str.len();
+            let new_str = if in_old_code { self.new_code.get(new_range) } else { None };
+            code.repr = match new_str {
+                // SAFETY: The lifetime is extended to match the value being rebased, which is then
+                //  transmuted back to the lifetime of the new source.
+                Some(new_str) => Cow::Borrowed(unsafe { &*(new_str as *const str) }),
+                None => Cow::Owned(str.to_owned()),
+            };
+        }
+    }
+}
+
+impl<'s> tree::Visitor for Rebase<'s> {}
+impl<'o, 's> tree::ItemVisitorMut<'o> for Rebase<'s> {
+    fn visit_item_mut(&mut self, item: item::RefMut<'o, '_>) -> bool {
+        match item {
+            item::RefMut::Tree(tree) => self.code(&mut tree.span.left_offset.code),
+            item::RefMut::Token { left_offset, code } => {
+                self.code(&mut left_offset.code);
+                self.code(code);
+            }
+        }
+        true
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_of_single_edits() {
+        let insert = [Edit::new(5..5, "abc")];
+        assert_eq!(Damage::new(&insert), Some(Damage { start: 5, old_end: 5, new_end: 8 }));
+        let delete = [Edit::new(5..10, "")];
+        assert_eq!(Damage::new(&delete), Some(Damage { start: 5, old_end: 10, new_end: 5 }));
+        let replace = [Edit::new(5..10, "ab")];
+        assert_eq!(Damage::new(&replace), Some(Damage { start: 5, old_end: 10, new_end: 7 }));
+        assert_eq!(Damage::new(&[]), None);
+    }
+
+    #[test]
+    fn damage_of_edit_sequences() {
+        let edits = [Edit::new(10..10, "abc"), Edit::new(2..4, "")];
+        assert_eq!(Damage::new(&edits), Some(Damage { start: 2, old_end: 10, new_end: 11 }));
+        let edits = [Edit::new(2..4, ""), Edit::new(10..10, "abc")];
+        assert_eq!(Damage::new(&edits), Some(Damage { start: 2, old_end: 12, new_end: 13 }));
+        let edits = [Edit::new(5..5, "abcdef"), Edit::new(7..9, "x")];
+        assert_eq!(Damage::new(&edits), Some(Damage { start: 5, old_end: 5, new_end: 10 }));
+    }
+}
@@ -918,6 +918,7 @@ impl<'s> Lexer<'s> {
                 let token = self.make_token(close_quote_start, close_quote_end,
                     token::Variant::text_end());
                 self.output.push(token);
+                self.spaces_after_lexeme();
             }
         } else {
             // One quote followed by non-quote character: Inline quote.
@@ -927,7 +928,6 @@ impl<'s> Lexer<'s> {
             self.output.push(token);
             self.inline_quote(quote_char, text_type);
         }
-        self.spaces_after_lexeme();
     }
 
     fn multiline_text(
@@ -1090,6 +1090,7 @@ impl<'s> Lexer<'s> {
                 );
                 self.output.push(token);
                 self.stack.push(state);
+                self.spaces_after_lexeme();
                 return TextEndedAt::Splice;
             }
             self.take_next();
@@ -1107,6 +1108,7 @@ impl<'s> Lexer<'s> {
             Token::from(token::text_end("", ""))
         };
         self.output.push(end_token);
+        self.spaces_after_lexeme();
         TextEndedAt::End
     }
 
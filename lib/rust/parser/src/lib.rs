@@ -111,9 +111,13 @@ use crate::prelude::*;
 // === Export ===
 // ==============
 
+pub mod diagnostic;
+pub mod format;
+pub mod incremental;
 pub mod lexer;
 pub mod macros;
 pub mod metadata;
+pub mod round_trip;
 pub mod serialization;
 pub mod source;
 pub mod syntax;
@@ -188,10 +192,27 @@ impl Parser {
         let result = tokens.map(|tokens| resolver.run(&self.macros, tokens));
         let value = result.value;
         if let Some(error) = result.internal_error {
-            return value.with_error(format!("Internal error: {error}"));
+            let error = syntax::tree::ErrorKind::Internal { message: error };
+            return value.with_error(error);
         }
         value
     }
+
+    /// Parse the code resulting from applying the given edits to the code of a previously parsed
+    /// tree. Top-level lines that are not affected by the edits are reused from `old_tree`; the
+    /// result is the same as the result of [`Self::run`] applied to `new_code`.
+    ///
+    /// The `new_code` must be the result of applying the `edits` to the `old_code`. The returned
+    /// tree borrows only from `new_code`, so the old code can be dropped afterwards.
+    pub fn run_incremental<'o, 's>(
+        &self,
+        old_tree: syntax::Tree<'o>,
+        old_code: &'o str,
+        edits: &[incremental::Edit],
+        new_code: &'s str,
+    ) -> syntax::Tree<'s> {
+        incremental::reparse(self, old_tree, old_code, edits, new_code)
+    }
 }
 
 impl Default for Parser {
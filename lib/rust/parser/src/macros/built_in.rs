@@ -4,6 +4,7 @@ use crate::macros::pattern::*;
 use crate::macros::*;
 
 use crate::syntax::operator;
+use crate::syntax::tree::ErrorKind;
 
 
 
@@ -122,7 +123,7 @@ fn import_body(segments: NonEmptyVec<MatchedSegment>) -> syntax::Tree {
     }
     let import = syntax::Tree::import(polyglot, from, import.unwrap(), all, as_, hiding);
     if incomplete_import {
-        return import.with_error("Expected name or `all` keyword following `import` keyword.");
+        return import.with_error(ErrorKind::ExpectedImportName);
     }
     import
 }
@@ -201,7 +202,7 @@ fn export_body(segments: NonEmptyVec<MatchedSegment>) -> syntax::Tree {
     }
     let export = syntax::Tree::export(from, export.unwrap(), all, as_, hiding);
     if incomplete_export {
-        return export.with_error("Expected name or `all` keyword following `export` keyword.");
+        return export.with_error(ErrorKind::ExpectedExportName);
     }
     export
 }
@@ -267,20 +268,25 @@ fn type_def_body(matched_segments: NonEmptyVec<MatchedSegment>) -> syntax::Tree
     let segment = matched_segments.pop().0;
     let header = into_ident(segment.header);
     let mut tokens = segment.result.tokens();
-    let mut block = vec![];
-    if let Some(syntax::Item::Block(lines)) = tokens.last_mut() {
-        block = mem::take(lines);
-        tokens.pop();
-    }
-    let mut tokens = tokens.into_iter();
-    let name = match tokens.next() {
+    let name = match tokens.first() {
         Some(syntax::Item::Token(syntax::Token {
             left_offset,
             code,
             variant: syntax::token::Variant::Ident(ident),
-        })) => syntax::Token(left_offset, code, ident),
-        _ => return Tree::ident(header).with_error("Expected identifier after `type` keyword."),
+        })) => syntax::Token(left_offset.clone(), code.clone(), *ident),
+        _ => {
+            // Keep all the matched tokens, so that no code is lost.
+            let items = iter::once(Tree::ident(header).into()).chain(tokens);
+            let tree = operator::Precedence::new().resolve(items).unwrap();
+            return tree.with_error(ErrorKind::ExpectedTypeName);
+        }
     };
+    let mut block = vec![];
+    if let Some(syntax::Item::Block(lines)) = tokens.last_mut() {
+        block = mem::take(lines);
+        tokens.pop();
+    }
+    let tokens = tokens.into_iter().skip(1);
     let mut precedence = operator::Precedence::new();
     let params = precedence
         .resolve_non_section(tokens)
@@ -421,7 +427,7 @@ fn case_body(segments: NonEmptyVec<MatchedSegment>) -> syntax::Tree {
     let (case_lines, any_invalid) = case_builder.finish();
     let tree = Tree::case_of(case_, expression, of_, case_lines);
     if any_invalid {
-        return tree.with_error("Invalid case expression.");
+        return tree.with_error(ErrorKind::InvalidCaseExpression);
     }
     tree
 }
@@ -488,7 +494,7 @@ impl<'s> CaseBuilder<'s> {
                         mut documentation,
                         expression: None,
                     }),
-            }) if self.documentation.is_none() => {
+            }) if self.documentation.is_none() && pattern.is_none() && arrow.is_none() => {
                 documentation.open.left_offset += span.left_offset;
                 if self.case_lines.is_empty() {
                     self.case_lines.push(default());
@@ -614,7 +620,7 @@ fn sequence_tree<'s>(
         tree = Tree::opr_app(tree, Ok(operator), body.map(&mut f)).into();
     }
     if invalid {
-        tree = tree.map(|tree| tree.with_error("Malformed comma-delimited sequence."));
+        tree = tree.map(|tree| tree.with_error(ErrorKind::MalformedSequence));
     }
     tree
 }
@@ -675,29 +681,27 @@ fn foreign_body(segments: NonEmptyVec<MatchedSegment>) -> syntax::Tree {
 fn try_foreign_body<'s>(
     keyword: syntax::token::Ident<'s>,
     tokens: impl IntoIterator<Item = syntax::Item<'s>>,
-) -> Result<syntax::Tree, &'static str> {
+) -> Result<syntax::Tree, ErrorKind> {
     let mut tokens = tokens.into_iter();
     let language = tokens
         .next()
         .and_then(try_into_token)
         .and_then(try_token_into_ident)
-        .ok_or("Expected an identifier specifying foreign method's language.")?;
-    let expected_name = "Expected an identifier specifying foreign function's name.";
-    let function =
-        operator::resolve_operator_precedence_if_non_empty(tokens).ok_or(expected_name)?;
-    let expected_function = "Expected a function definition after foreign declaration.";
+        .ok_or(ErrorKind::ExpectedForeignLanguage)?;
+    let function = operator::resolve_operator_precedence_if_non_empty(tokens)
+        .ok_or(ErrorKind::ExpectedForeignName)?;
     let box syntax::tree::Variant::OprApp(
             syntax::tree::OprApp { lhs: Some(lhs), opr: Ok(equals), rhs: Some(body) }) = function.variant else {
-        return Err(expected_function)
+        return Err(ErrorKind::ExpectedForeignDefinition)
     };
     if !equals.properties.is_assignment() {
-        return Err(expected_function);
+        return Err(ErrorKind::ExpectedForeignDefinition);
     };
     if !matches!(body.variant, box syntax::tree::Variant::TextLiteral(_)) {
-        return Err("Expected a text literal as body of `foreign` declaration.");
+        return Err(ErrorKind::ExpectedForeignBody);
     }
     let (name, args) = crate::collect_arguments(lhs);
-    let mut name = try_tree_into_ident(name).ok_or(expected_name)?;
+    let mut name = try_tree_into_ident(name).ok_or(ErrorKind::ExpectedForeignName)?;
     name.left_offset += function.span.left_offset;
     Ok(syntax::Tree::foreign_function(keyword, language, name, args, equals, body))
 }
@@ -750,7 +754,7 @@ fn expect_ident(tree: syntax::Tree) -> syntax::Tree {
     if matches!(&*tree.variant, syntax::tree::Variant::Ident(_)) {
         tree
     } else {
-        tree.with_error("Expected identifier.")
+        tree.with_error(ErrorKind::ExpectedIdentifier)
     }
 }
 
@@ -758,11 +762,11 @@ fn expect_qualified(tree: syntax::Tree) -> syntax::Tree {
     if crate::is_qualified_name(&tree) {
         tree
     } else {
-        tree.with_error("Expected qualified name.")
+        tree.with_error(ErrorKind::ExpectedQualifiedName)
     }
 }
 
 fn expected_nonempty<'s>() -> syntax::Tree<'s> {
     let empty = syntax::Tree::ident(syntax::token::ident("", "", false, 0, false, false, false));
-    empty.with_error("Expected tokens.")
+    empty.with_error(ErrorKind::ExpectedTokens)
 }
@@ -414,7 +414,18 @@ impl<'s> Resolver<'s> {
     /// Resolve the [`PartiallyMatchedMacro`]. Returns the AST and the non-used tokens. For example,
     /// the resolution of the `(a)` macro in the `(a) x (b)` expression will return the `(a)` AST
     /// and the `x` and `(b)` items (already resolved).
-    fn resolve(m: PartiallyMatchedMacro<'s>) -> (syntax::Tree<'s>, VecDeque<syntax::Item<'s>>) {
+    fn resolve(mut m: PartiallyMatchedMacro<'s>) -> (syntax::Tree<'s>, VecDeque<syntax::Item<'s>>) {
+        if m.matched_macro_def.is_none() && let Some(definition) = Self::unclosed_group(&m) {
+            // The line ended before the closing delimiter of a group was found. Complete the group
+            // with an empty closing delimiter, so that its content is parsed as it would be if the
+            // group were closed at the end of the line, and mark the whole group as invalid.
+            let close = Token("", "", token::Variant::close_symbol());
+            let open = mem::replace(&mut m.current_segment, MatchedSegment::new(close));
+            m.resolved_segments.push(open);
+            m.matched_macro_def = Some(definition);
+            let (group, rest) = Self::resolve(m);
+            return (group.with_error(syntax::tree::ErrorKind::UnmatchedDelimiter), rest);
+        }
         let segments = NonEmptyVec::<MatchedSegment, usize>::new_with_last(
             m.resolved_segments,
             m.current_segment,
@@ -478,7 +489,8 @@ impl<'s> Resolver<'s> {
                     if let Some(excess) =
                         syntax::operator::resolve_operator_precedence_if_non_empty(excess)
                     {
-                        let excess = excess.with_error("Unexpected tokens in macro invocation.");
+                        let excess =
+                            excess.with_error(syntax::tree::ErrorKind::UnexpectedMacroTokens);
                         tokens.push(excess.into());
                     }
                     let body = syntax::operator::resolve_operator_precedence_if_non_empty(tokens);
@@ -499,11 +511,30 @@ impl<'s> Resolver<'s> {
                 items.push_back(syntax::Item::Token(header));
                 items.append(&mut segment);
             }
-            let header0 = syntax::tree::to_ast(header0).with_error("Invalid macro invocation.");
+            let header0 = syntax::tree::to_ast(header0)
+                .with_error(syntax::tree::ErrorKind::InvalidMacroInvocation);
             (header0, items)
         }
     }
 
+    /// If the macro is a group opened by a delimiter, that would be complete if its closing
+    /// delimiter were found, return the definition of the group's macro.
+    fn unclosed_group(m: &PartiallyMatchedMacro<'s>) -> Option<Rc<macros::Definition<'s>>> {
+        let is_open_symbol =
+            matches!(m.current_segment.header.variant, token::Variant::OpenSymbol(_));
+        if !is_open_symbol || !m.resolved_segments.is_empty() {
+            return None;
+        }
+        let mut next_segments = m.possible_next_segments.values();
+        let (Some(entries), None) = (next_segments.next(), next_segments.next()) else {
+            return None;
+        };
+        let entry = entries.first();
+        let is_unique = entries.len() == 1;
+        (is_unique && entry.required_segments.head().is_none())
+            .then(|| entry.definition.clone_ref())
+    }
+
     /// Move the resolution to the next segment. Takes possible next segments and merges them in a
     /// new [`SegmentMap`]. If after moving to the next segment there is a macro definition that is
     /// fully matched, its definition will be recorded.
@@ -54,46 +54,26 @@ fn check_file(path: &str, mut code: &str) {
         code = code_;
     }
     let ast = enso_parser::Parser::new().run(code);
-    let errors = RefCell::new(vec![]);
-    ast.map(|tree| {
-        if let enso_parser::syntax::tree::Variant::Invalid(err) = &*tree.variant {
-            let error = format!("{}: {}", err.error.message, tree.code());
-            errors.borrow_mut().push((error, tree.span.clone()));
-        } else if let enso_parser::syntax::tree::Variant::TextLiteral(text) = &*tree.variant {
-            for element in &text.elements {
-                if let enso_parser::syntax::tree::TextElement::Escape { token } = element {
-                    if token.variant.value.is_none() {
-                        let escape = token.code.to_string();
-                        let error = format!("Invalid escape sequence: {escape}");
-                        errors.borrow_mut().push((error, tree.span.clone()));
-                    }
-                }
+    for diagnostic in enso_parser::diagnostic::collect(&ast) {
+        let start = diagnostic.span.start;
+        let mut line = 1;
+        let mut char = 0;
+        for (i, c) in code.char_indices() {
+            if i >= start {
+                break;
             }
-        }
-    });
-    for (error, span) in &*errors.borrow() {
-        let whitespace = &span.left_offset.code.repr;
-        if matches!(whitespace, Cow::Borrowed(_)) {
-            let start = whitespace.as_ptr() as usize + whitespace.len() - code.as_ptr() as usize;
-            let mut line = 1;
-            let mut char = 0;
-            for (i, c) in code.char_indices() {
-                if i >= start {
-                    break;
-                }
-                if c == '\n' {
-                    line += 1;
-                    char = 0;
-                } else {
-                    char += 1;
-                }
+            if c == '\n' {
+                line += 1;
+                char = 0;
+            } else {
+                char += 1;
             }
-            eprintln!("{path}:{line}:{char}: {}", &error);
-        } else {
-            eprintln!("{path}:?:?: {}", &error);
-        };
+        }
+        let code = &code[diagnostic.span.clone()];
+        let error = diagnostic.message();
+        eprintln!("{path}:{line}:{char}: {} {error}: {code}", diagnostic.code());
     }
-    for (parsed, original) in ast.code().lines().zip(code.lines()) {
-        assert_eq!(parsed, original, "Bug: dropped tokens, while parsing: {path}");
+    if let Err(mismatch) = enso_parser::round_trip::check_tree(&ast, code) {
+        panic!("Bug: {mismatch} While parsing: {path}");
     }
 }
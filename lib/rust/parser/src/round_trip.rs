@@ -0,0 +1,67 @@
+//! Checking that parsing is lossless: the tokens of the tree store the code they were parsed from,
+//! including comments and code that fails to parse, so that printing a tree with [`Tree::code`]
+//! should reproduce exactly the code it was parsed from. Edits made through the tree rely on this.
+
+use crate::prelude::*;
+
+use crate::syntax::Tree;
+use crate::Parser;
+
+
+
+// ================
+// === Mismatch ===
+// ================
+
+/// The number of characters of each side of a [`Mismatch`] that are reported.
+const CONTEXT_CHARS: usize = 24;
+
+/// A difference between some code, and the source printed from its tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The byte offset of the first difference.
+    pub offset:   usize,
+    /// The beginning of the code following the difference, in the parsed code.
+    pub expected: String,
+    /// The beginning of the code following the difference, in the printed source.
+    pub found:    String,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { offset, expected, found } = self;
+        write!(f, "Printed source differs from the parsed code at byte {offset}: ")?;
+        write!(f, "expected {expected:?}, found {found:?}.")
+    }
+}
+
+
+
+// =============
+// === Check ===
+// =============
+
+/// Parse the code, and check that printing the resulting tree reproduces the code exactly.
+pub fn check(parser: &Parser, code: &str) -> Result<(), Mismatch> {
+    compare(code, &parser.run(code).code())
+}
+
+/// Check that the tree prints as the given code.
+pub fn check_tree(tree: &Tree, code: &str) -> Result<(), Mismatch> {
+    compare(code, &tree.code())
+}
+
+/// Compare some code with the source printed from its tree, and locate the first difference.
+pub fn compare(code: &str, printed: &str) -> Result<(), Mismatch> {
+    if code == printed {
+        return Ok(());
+    }
+    let first_difference = code.char_indices().zip(printed.chars()).find(|((_, a), b)| a != b);
+    let offset = match first_difference {
+        Some(((offset, _), _)) => offset,
+        // One of the strings is a prefix of the other.
+        None => code.len().min(printed.len()),
+    };
+    let context = |text: &str| -> String { text[offset..].chars().take(CONTEXT_CHARS).collect() };
+    Err(Mismatch { offset, expected: context(code), found: context(printed) })
+}
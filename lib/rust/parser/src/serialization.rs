@@ -91,14 +91,31 @@ where D: serde::Deserializer<'de> {
 // === Error ===
 // =============
 
-/// Deserialization type for `crate::syntax::tree::Error`.
-#[derive(Deserialize, Debug, Clone)]
-pub(crate) struct Error(String);
+/// Serialized representation of `crate::syntax::tree::Error`. The code and the argument identify
+/// the kind of the error; the message describes it to the consumers that don't know the kinds.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone)]
+pub(crate) struct Error {
+    code:     String,
+    #[reflect(hide)]
+    argument: String,
+    message:  String,
+}
 
 impl From<Error> for crate::syntax::tree::Error {
     fn from(error: Error) -> Self {
-        let message = error.0.into();
-        crate::syntax::tree::Error { message }
+        let Error { code, argument, message } = error;
+        let kind = crate::syntax::tree::ErrorKind::from_code(&code, argument);
+        let kind = kind.unwrap_or(crate::syntax::tree::ErrorKind::Other { message });
+        crate::syntax::tree::Error::new(kind)
+    }
+}
+
+impl From<crate::syntax::tree::Error> for Error {
+    fn from(error: crate::syntax::tree::Error) -> Self {
+        let code = error.kind.code().to_owned();
+        let argument = error.kind.argument().unwrap_or_default().to_owned();
+        let message = error.message();
+        Self { code, argument, message }
     }
 }
 
@@ -106,6 +106,22 @@ pub enum Ref<'s, 'a> {
 
 
 
+// ==============
+// === RefMut ===
+// ==============
+
+/// A mutably borrowed version of [`Item`]. Used mostly by AST visitors.
+///
+/// Tokens are generic over their variant type, so only their location and code are exposed.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub enum RefMut<'s, 'a> {
+    Token { left_offset: &'a mut Offset<'s>, code: &'a mut Code<'s> },
+    Tree(&'a mut Tree<'s>),
+}
+
+
+
 // ======================
 // === Variant Checks ===
 // ======================
@@ -149,9 +149,13 @@ impl<'s> ExpressionBuilder<'s> {
     pub fn operand(&mut self, operand: Operand<syntax::Tree<'s>>) {
         if self.prev_type == Some(ItemType::Ast) {
             if let Some(Operand { value: syntax::Tree { variant: box
-                    syntax::tree::Variant::TextLiteral(ref mut lhs), .. }, .. }) = self.output.last_mut()
+                    syntax::tree::Variant::TextLiteral(ref mut lhs), span: ref mut lhs_span }, .. }) = self.output.last_mut()
                     && !lhs.closed
+                    && let box syntax::tree::Variant::TextLiteral(syntax::tree::TextLiteral {
+                        open: None, ..
+                    }) = &operand.value.variant
                     && let box syntax::tree::Variant::TextLiteral(mut rhs) = operand.value.variant {
+                lhs_span.concat_mut(&operand.value.span);
                 syntax::tree::join_text_literals(lhs, &mut rhs, operand.value.span);
                 if let syntax::tree::TextLiteral { open: Some(open), newline: None, elements, closed: true, close: None } = lhs
                     && open.code.starts_with('#') {
@@ -161,6 +165,13 @@ impl<'s> ExpressionBuilder<'s> {
                     open.left_offset += lhs_tree.span.left_offset;
                     let doc = syntax::tree::DocComment { open, elements, newlines: default() };
                     self.output.push(syntax::Tree::documented(doc, default()).into());
+                } else if let syntax::tree::TextLiteral { open: Some(open), newline: None, closed: true, close: None, .. } = lhs
+                    && matches!(&*open.code.repr, "'" | "\"") {
+                    // An inline text literal ended by the end of its line, rather than a closing
+                    // quote. Its content is kept, so that the rest of the line is still analyzed.
+                    let lhs = self.output.pop().unwrap();
+                    let error = syntax::tree::ErrorKind::UnterminatedText;
+                    self.output.push(lhs.map(|text| text.with_error(error)));
                 }
                 return;
             }
@@ -176,6 +187,7 @@ impl<'s> ExpressionBuilder<'s> {
         let arity = Arity::Binary {
             tokens:                  default(),
             lhs_section_termination: default(),
+            missing_lhs:             false,
         };
         self.push_operator(precedence, associativity, arity);
     }
@@ -215,7 +227,7 @@ impl<'s> ExpressionBuilder<'s> {
             self.application();
             if self.nospace {
                 if let Unary::Simple(token) = arity {
-                    let error = "Space required between term and unary-operator expression.".into();
+                    let error = syntax::tree::ErrorKind::UnaryOperatorRequiresSpace;
                     arity = Unary::Invalid { token, error };
                 }
             }
@@ -231,9 +243,33 @@ impl<'s> ExpressionBuilder<'s> {
         assoc: token::Associativity,
         opr: token::Operator<'s>,
     ) {
+        if self.prev_type == Some(ItemType::Opr)
+                && let Some(Operator { opr: Arity::Unary(_), .. }) = self.operator_stack.last() {
+            // Unary operators directly followed by a binary operator, like the arrow in
+            // `x a-> , y`, are missing their operands; apply them before the binary operator is
+            // pushed, so that it doesn't take an earlier operand as its LHS.
+            let mut operand = None;
+            while let Some(opr) =
+                    self.operator_stack.pop_if(|opr| matches!(opr.opr, Arity::Unary(_))) {
+                operand = Some(self.apply_operator(opr.opr, operand));
+            }
+            self.output.extend(operand);
+            self.prev_type = Some(ItemType::Ast);
+        }
         if self.prev_type == Some(ItemType::Opr)
                 && let Some(prev_opr) = self.operator_stack.last_mut()
                 && let Arity::Binary { tokens, .. } = &mut prev_opr.opr {
+            if tokens.len() == 1 && tokens[0].properties.is_assignment() {
+                // An operator following an assignment operator is the start of an expression
+                // missing its left operand, like `x = * 2`. Report the error in the assigned
+                // expression, rather than joining the two operators into a multiple-operator error
+                // that would lose the structure of the assignment.
+                let tokens = vec![opr];
+                let lhs_section_termination = None;
+                let arity = Arity::Binary { tokens, lhs_section_termination, missing_lhs: true };
+                self.push_operator(prec, assoc, arity);
+                return;
+            }
             if tokens.len() == 1 && tokens[0].properties.is_dot() {
                 let Token { left_offset, code, .. } = opr;
                 let is_operator = true;
@@ -279,42 +315,58 @@ impl<'s> ExpressionBuilder<'s> {
             opr.precedence > prec
                 || (opr.precedence == prec && opr.associativity == token::Associativity::Left)
         }) {
-            let rhs_ = rhs.take();
-            let ast = match opr.opr {
-                Arity::Unary(Unary::Simple(opr)) =>
-                    Operand::from(rhs_).map(|item| syntax::tree::apply_unary_operator(opr, item)),
-                Arity::Unary(Unary::Invalid { token, error }) => Operand::from(rhs_)
-                    .map(|item| syntax::tree::apply_unary_operator(token, item).with_error(error)),
-                Arity::Unary(Unary::Fragment { mut fragment }) => {
-                    if let Some(rhs_) = rhs_ {
-                        fragment.operand(rhs_);
-                    }
-                    fragment.finish().unwrap()
+            let ast = self.apply_operator(opr.opr, rhs.take());
+            *rhs = Some(ast);
+        }
+    }
+
+    /// Apply an operator from the `operator_stack` to its RHS operand, consuming its LHS operand
+    /// from the `output` stack if it has one.
+    fn apply_operator(
+        &mut self,
+        opr: Arity<'s>,
+        rhs: Option<Operand<syntax::Tree<'s>>>,
+    ) -> Operand<syntax::Tree<'s>> {
+        match opr {
+            Arity::Unary(Unary::Simple(opr)) =>
+                Operand::from(rhs).map(|item| syntax::tree::apply_unary_operator(opr, item)),
+            Arity::Unary(Unary::Invalid { token, error }) => Operand::from(rhs)
+                .map(|item| syntax::tree::apply_unary_operator(token, item).with_error(error)),
+            Arity::Unary(Unary::Fragment { mut fragment }) => {
+                if let Some(rhs) = rhs {
+                    fragment.operand(rhs);
                 }
-                Arity::Binary { tokens, lhs_section_termination } => {
-                    let lhs = self.output.pop();
-                    if let Some(lhs_termination) = lhs_section_termination {
-                        let lhs = match lhs_termination {
-                            SectionTermination::Reify => lhs.map(syntax::Tree::from),
-                            SectionTermination::Unwrap => lhs.map(|op| op.value),
-                        };
-                        let rhs = rhs_.map(syntax::Tree::from);
-                        let ast = syntax::tree::apply_operator(lhs, tokens, rhs);
-                        Operand::from(ast)
-                    } else {
-                        let rhs = rhs_.map(syntax::Tree::from);
-                        let mut elided = 0;
-                        if tokens.len() != 1 || tokens[0].properties.can_form_section() {
-                            elided += lhs.is_none() as u32 + rhs.is_none() as u32;
-                        }
-                        let mut operand = Operand::from(lhs)
-                            .map(|lhs| syntax::tree::apply_operator(lhs, tokens, rhs));
-                        operand.elided += elided;
-                        operand
+                fragment.finish().unwrap()
+            }
+            Arity::Binary { tokens, missing_lhs: true, .. } => {
+                let operator = tokens[0].code.to_string();
+                let rhs = rhs.map(syntax::Tree::from);
+                let ast = syntax::tree::apply_operator(None, tokens, rhs);
+                let error = syntax::tree::ErrorKind::MissingOperands { operator };
+                Operand::from(ast.with_error(error))
+            }
+            Arity::Binary { tokens, lhs_section_termination, .. } => {
+                let lhs = self.output.pop();
+                if let Some(lhs_termination) = lhs_section_termination {
+                    let lhs = match lhs_termination {
+                        SectionTermination::Reify => lhs.map(syntax::Tree::from),
+                        SectionTermination::Unwrap => lhs.map(|op| op.value),
+                    };
+                    let rhs = rhs.map(syntax::Tree::from);
+                    let ast = syntax::tree::apply_operator(lhs, tokens, rhs);
+                    Operand::from(ast)
+                } else {
+                    let rhs = rhs.map(syntax::Tree::from);
+                    let mut elided = 0;
+                    if tokens.len() != 1 || tokens[0].properties.can_form_section() {
+                        elided += lhs.is_none() as u32 + rhs.is_none() as u32;
                     }
+                    let mut operand = Operand::from(lhs)
+                        .map(|lhs| syntax::tree::apply_operator(lhs, tokens, rhs));
+                    operand.elided += elided;
+                    operand
                 }
-            };
-            *rhs = Some(ast);
+            }
         }
     }
 
@@ -335,15 +387,19 @@ impl<'s> ExpressionBuilder<'s> {
     }
 
     pub fn extend_from(&mut self, child: &mut Self) {
-        if child.output.is_empty() && let Some(op) = child.operator_stack.pop() {
-            match op.opr {
-                Arity::Unary(Unary::Simple(un)) => self.operator(un),
-                Arity::Unary(Unary::Invalid{ .. }) => unreachable!(),
-                Arity::Unary(Unary::Fragment{ .. }) => unreachable!(),
-                Arity::Binary { tokens, .. } => tokens.into_iter().for_each(|op| self.operator(op)),
-            };
+        if child.output.is_empty() && !child.operator_stack.is_empty() {
+            // A group of operators without operands, like `,@`: each operator is applied to the
+            // outer expression in order.
+            for op in mem::take(&mut child.operator_stack) {
+                match op.opr {
+                    Arity::Unary(Unary::Simple(un)) => self.operator(un),
+                    Arity::Unary(Unary::Invalid { .. }) => unreachable!(),
+                    Arity::Unary(Unary::Fragment { .. }) => unreachable!(),
+                    Arity::Binary { tokens, .. } =>
+                        tokens.into_iter().for_each(|op| self.operator(op)),
+                };
+            }
             child.prev_type = None;
-            debug_assert_eq!(&child.operator_stack, &[]);
             return;
         }
         if child.prev_type == Some(ItemType::Opr)
@@ -391,6 +447,9 @@ enum Arity<'s> {
     Binary {
         tokens:                  Vec<token::Operator<'s>>,
         lhs_section_termination: Option<SectionTermination>,
+        /// Whether the operator directly follows an assignment operator, so that it has no left
+        /// operand even though the output stack is not empty.
+        missing_lhs:             bool,
     },
 }
 
@@ -398,7 +457,7 @@ impl<'s> Arity<'s> {
     fn binary(tok: token::Operator<'s>) -> Self {
         let lhs_section_termination = tok.properties.lhs_section_termination();
         let tokens = vec![tok];
-        Self::Binary { tokens, lhs_section_termination }
+        Self::Binary { tokens, lhs_section_termination, missing_lhs: false }
     }
 
     fn unary(tok: token::Operator<'s>) -> Self {
@@ -409,7 +468,7 @@ impl<'s> Arity<'s> {
 #[derive(Debug, PartialEq, Eq)]
 enum Unary<'s> {
     Simple(token::Operator<'s>),
-    Invalid { token: token::Operator<'s>, error: Cow<'static, str> },
+    Invalid { token: token::Operator<'s>, error: syntax::tree::ErrorKind },
     Fragment { fragment: ExpressionBuilder<'s> },
 }
 
@@ -391,26 +391,238 @@ with_ast_definition!(generate_ast_definition());
 
 /// Error of parsing attached to an [`Tree`] node.
 #[derive(Clone, Debug, Eq, PartialEq, Visitor, Serialize, Reflect, Deserialize)]
-#[allow(missing_docs)]
 #[reflect(transparent)]
-#[serde(from = "crate::serialization::Error")]
+#[serde(from = "crate::serialization::Error", into = "crate::serialization::Error")]
 pub struct Error {
-    #[serde(skip_deserializing)]
-    pub message: Cow<'static, str>,
+    /// What is wrong with the code. It is serialized as its code, argument and message.
+    #[reflect(as = "crate::serialization::Error")]
+    pub kind: ErrorKind,
 }
 
 impl Error {
     /// Constructor.
-    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
-        let message = message.into();
-        Self { message }
+    pub fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+
+    /// A human-readable description of the error.
+    pub fn message(&self) -> String {
+        self.kind.to_string()
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.kind, f)
     }
 }
 
 impl<'s> Tree<'s> {
     /// Constructor.
-    pub fn with_error(self, message: impl Into<Cow<'static, str>>) -> Self {
-        Tree::invalid(Error::new(message), self)
+    pub fn with_error(self, kind: ErrorKind) -> Self {
+        Tree::invalid(Error::new(kind), self)
+    }
+}
+
+/// A kind of syntax error. Every kind is identified by a stable [`code`](Self::code), so that tools
+/// can recognize errors without matching on their messages.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// An opening or closing delimiter without its counterpart.
+    UnmatchedDelimiter,
+    /// An operator with a special meaning, used where that meaning does not apply.
+    InvalidSpecialOperator,
+    /// The `:` operator, not applied to two operands.
+    IncompleteTypeAnnotation,
+    /// A binary operator that cannot form a section, applied to no operands.
+    MissingOperands {
+        /// The code of the operator.
+        operator: String,
+    },
+    /// A unary operator that cannot form a section, applied to no operand.
+    MissingOperand {
+        /// The code of the operator.
+        operator: String,
+    },
+    /// A unary operator applied to an operand, directly following another term.
+    UnaryOperatorRequiresSpace,
+    /// Operators following each other, like `a + * b`.
+    MultipleOperators,
+    /// A token that can't be used in an expression.
+    UnexpectedToken {
+        /// The code of the token.
+        token: String,
+    },
+    /// A text escape sequence that does not denote a character.
+    InvalidEscapeSequence {
+        /// The code of the escape sequence.
+        escape: String,
+    },
+    /// An inline text literal not closed by a quote before the end of its line.
+    UnterminatedText,
+    /// An `import` keyword not followed by a module name or the `all` keyword.
+    ExpectedImportName,
+    /// An `export` keyword not followed by a module name or the `all` keyword.
+    ExpectedExportName,
+    /// A `type` keyword not followed by the name of the defined type.
+    ExpectedTypeName,
+    /// A `case` expression without the `of` keyword, or with malformed branches.
+    InvalidCaseExpression,
+    /// A comma-delimited sequence with a missing element, like `[1, , 2]`.
+    MalformedSequence,
+    /// A `foreign` keyword not followed by the name of the foreign language.
+    ExpectedForeignLanguage,
+    /// A `foreign` declaration without the name of the defined function.
+    ExpectedForeignName,
+    /// A `foreign` declaration not followed by the definition of the function.
+    ExpectedForeignDefinition,
+    /// A `foreign` function definition whose body is not a text literal.
+    ExpectedForeignBody,
+    /// An identifier missing where the syntax requires one.
+    ExpectedIdentifier,
+    /// A qualified name, like `Standard.Base`, missing where the syntax requires one.
+    ExpectedQualifiedName,
+    /// A macro invocation missing the tokens of one of its segments.
+    ExpectedTokens,
+    /// Tokens following a complete macro invocation, which the macro does not accept.
+    UnexpectedMacroTokens,
+    /// A macro invocation that does not match any of the macro's definitions.
+    InvalidMacroInvocation,
+    /// A bug in the parser.
+    Internal {
+        /// The description of the bug.
+        message: String,
+    },
+    /// An error known only by its message, e.g. one with an unknown code read from the serialized
+    /// representation.
+    Other {
+        /// The description of the error.
+        message: String,
+    },
+}
+
+impl ErrorKind {
+    /// The stable identifier of this kind of error. Codes are never reused for other kinds.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::UnmatchedDelimiter => "E001",
+            ErrorKind::InvalidSpecialOperator => "E002",
+            ErrorKind::IncompleteTypeAnnotation => "E003",
+            ErrorKind::MissingOperands { .. } => "E004",
+            ErrorKind::MissingOperand { .. } => "E005",
+            ErrorKind::UnaryOperatorRequiresSpace => "E006",
+            ErrorKind::MultipleOperators => "E007",
+            ErrorKind::UnexpectedToken { .. } => "E008",
+            ErrorKind::InvalidEscapeSequence { .. } => "E009",
+            ErrorKind::ExpectedImportName => "E010",
+            ErrorKind::ExpectedExportName => "E011",
+            ErrorKind::ExpectedTypeName => "E012",
+            ErrorKind::InvalidCaseExpression => "E013",
+            ErrorKind::MalformedSequence => "E014",
+            ErrorKind::ExpectedForeignLanguage => "E015",
+            ErrorKind::ExpectedForeignName => "E016",
+            ErrorKind::ExpectedForeignDefinition => "E017",
+            ErrorKind::ExpectedForeignBody => "E018",
+            ErrorKind::ExpectedIdentifier => "E019",
+            ErrorKind::ExpectedQualifiedName => "E020",
+            ErrorKind::ExpectedTokens => "E021",
+            ErrorKind::UnexpectedMacroTokens => "E022",
+            ErrorKind::InvalidMacroInvocation => "E023",
+            ErrorKind::UnterminatedText => "E024",
+            ErrorKind::Internal { .. } => "E998",
+            ErrorKind::Other { .. } => "E999",
+        }
+    }
+
+    /// The value this kind of error is parameterized with, if any.
+    pub fn argument(&self) -> Option<&str> {
+        match self {
+            ErrorKind::MissingOperands { operator } | ErrorKind::MissingOperand { operator } =>
+                Some(operator),
+            ErrorKind::UnexpectedToken { token } => Some(token),
+            ErrorKind::InvalidEscapeSequence { escape } => Some(escape),
+            ErrorKind::Internal { message } | ErrorKind::Other { message } => Some(message),
+            _ => None,
+        }
+    }
+
+    /// The kind of error identified by the [`code`](Self::code), parameterized with the
+    /// [`argument`](Self::argument), which is ignored by the kinds without one. Return [`None`] if
+    /// the code is unknown.
+    pub fn from_code(code: &str, argument: String) -> Option<Self> {
+        Some(match code {
+            "E001" => ErrorKind::UnmatchedDelimiter,
+            "E002" => ErrorKind::InvalidSpecialOperator,
+            "E003" => ErrorKind::IncompleteTypeAnnotation,
+            "E004" => ErrorKind::MissingOperands { operator: argument },
+            "E005" => ErrorKind::MissingOperand { operator: argument },
+            "E006" => ErrorKind::UnaryOperatorRequiresSpace,
+            "E007" => ErrorKind::MultipleOperators,
+            "E008" => ErrorKind::UnexpectedToken { token: argument },
+            "E009" => ErrorKind::InvalidEscapeSequence { escape: argument },
+            "E010" => ErrorKind::ExpectedImportName,
+            "E011" => ErrorKind::ExpectedExportName,
+            "E012" => ErrorKind::ExpectedTypeName,
+            "E013" => ErrorKind::InvalidCaseExpression,
+            "E014" => ErrorKind::MalformedSequence,
+            "E015" => ErrorKind::ExpectedForeignLanguage,
+            "E016" => ErrorKind::ExpectedForeignName,
+            "E017" => ErrorKind::ExpectedForeignDefinition,
+            "E018" => ErrorKind::ExpectedForeignBody,
+            "E019" => ErrorKind::ExpectedIdentifier,
+            "E020" => ErrorKind::ExpectedQualifiedName,
+            "E021" => ErrorKind::ExpectedTokens,
+            "E022" => ErrorKind::UnexpectedMacroTokens,
+            "E023" => ErrorKind::InvalidMacroInvocation,
+            "E024" => ErrorKind::UnterminatedText,
+            "E998" => ErrorKind::Internal { message: argument },
+            "E999" => ErrorKind::Other { message: argument },
+            _ => return None,
+        })
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnmatchedDelimiter => write!(f, "Unmatched delimiter"),
+            ErrorKind::InvalidSpecialOperator => write!(f, "Invalid use of special operator."),
+            ErrorKind::IncompleteTypeAnnotation =>
+                write!(f, "`:` operator must be applied to two operands."),
+            ErrorKind::MissingOperands { operator } =>
+                write!(f, "Operator `{operator}` must be applied to two operands."),
+            ErrorKind::MissingOperand { operator } =>
+                write!(f, "Operator `{operator}` must be applied to an operand."),
+            ErrorKind::UnaryOperatorRequiresSpace =>
+                write!(f, "Space required between term and unary-operator expression."),
+            ErrorKind::MultipleOperators => write!(f, "Multiple operators in a row."),
+            ErrorKind::UnexpectedToken { token } => write!(f, "Unexpected token: `{token}`."),
+            ErrorKind::InvalidEscapeSequence { escape } =>
+                write!(f, "Invalid escape sequence: {escape}"),
+            ErrorKind::UnterminatedText => write!(f, "Unterminated text literal."),
+            ErrorKind::ExpectedImportName =>
+                write!(f, "Expected name or `all` keyword following `import` keyword."),
+            ErrorKind::ExpectedExportName =>
+                write!(f, "Expected name or `all` keyword following `export` keyword."),
+            ErrorKind::ExpectedTypeName => write!(f, "Expected identifier after `type` keyword."),
+            ErrorKind::InvalidCaseExpression => write!(f, "Invalid case expression."),
+            ErrorKind::MalformedSequence => write!(f, "Malformed comma-delimited sequence."),
+            ErrorKind::ExpectedForeignLanguage =>
+                write!(f, "Expected an identifier specifying foreign method's language."),
+            ErrorKind::ExpectedForeignName =>
+                write!(f, "Expected an identifier specifying foreign function's name."),
+            ErrorKind::ExpectedForeignDefinition =>
+                write!(f, "Expected a function definition after foreign declaration."),
+            ErrorKind::ExpectedForeignBody =>
+                write!(f, "Expected a text literal as body of `foreign` declaration."),
+            ErrorKind::ExpectedIdentifier => write!(f, "Expected identifier."),
+            ErrorKind::ExpectedQualifiedName => write!(f, "Expected qualified name."),
+            ErrorKind::ExpectedTokens => write!(f, "Expected tokens."),
+            ErrorKind::UnexpectedMacroTokens => write!(f, "Unexpected tokens in macro invocation."),
+            ErrorKind::InvalidMacroInvocation => write!(f, "Invalid macro invocation."),
+            ErrorKind::Internal { message } => write!(f, "Internal error: {message}"),
+            ErrorKind::Other { message } => write!(f, "{message}"),
+        }
     }
 }
 
@@ -773,7 +985,9 @@ pub fn apply<'s>(mut func: Tree<'s>, mut arg: Tree<'s>) -> Tree<'s> {
         (Variant::OprApp(OprApp { lhs: Some(_), opr: Ok(_), rhs }),
                 Variant::ArgumentBlockApplication(ArgumentBlockApplication { lhs: None, arguments }))
         if rhs.is_none() => {
-            *rhs = block::body_from_lines(mem::take(arguments)).into();
+            let mut block = block::body_from_lines(mem::take(arguments));
+            block.span.left_offset += mem::take(&mut arg.span.left_offset);
+            *rhs = block.into();
             func
         }
         (_, Variant::ArgumentBlockApplication(block)) if block.lhs.is_none() => {
@@ -808,7 +1022,9 @@ pub fn apply<'s>(mut func: Tree<'s>, mut arg: Tree<'s>) -> Tree<'s> {
             open.left_offset += arg.span.left_offset;
             let open = Some(open);
             let close = Some(close.clone());
-            Tree::named_app(func, open, lhs.token.clone(), opr.clone(), rhs.clone(), close)
+            let mut name = lhs.token.clone();
+            name.left_offset += body.span.left_offset.clone();
+            Tree::named_app(func, open, name, opr.clone(), rhs.clone(), close)
         }
         (_, Variant::Ident(Ident { token })) if token.is_default => {
             let mut token = token.clone();
@@ -881,7 +1097,7 @@ pub fn apply_operator<'s>(
     }
     if let Ok(opr_) = &opr && opr_.properties.is_special() {
         let tree = Tree::opr_app(lhs, opr, rhs);
-        return tree.with_error("Invalid use of special operator.");
+        return tree.with_error(ErrorKind::InvalidSpecialOperator);
     }
     if let Ok(opr_) = &opr && opr_.properties.is_type_annotation() {
         return match (lhs, rhs) {
@@ -891,12 +1107,13 @@ pub fn apply_operator<'s>(
             },
             (lhs, rhs) => {
                 let invalid = Tree::opr_app(lhs, opr, rhs);
-                invalid.with_error("`:` operator must be applied to two operands.")
+                invalid.with_error(ErrorKind::IncompleteTypeAnnotation)
             }
         };
     }
     if let Ok(opr_) = &opr && !opr_.properties.can_form_section() && lhs.is_none() && rhs.is_none() {
-        let error = format!("Operator `{opr:?}` must be applied to two operands.");
+        let operator = opr_.code.to_string();
+        let error = ErrorKind::MissingOperands { operator };
         let invalid = Tree::opr_app(lhs, opr, rhs);
         return invalid.with_error(error);
     }
@@ -940,7 +1157,7 @@ pub fn apply_unary_operator<'s>(opr: token::Operator<'s>, rhs: Option<Tree<'s>>)
         };
     }
     if !opr.properties.can_form_section() && rhs.is_none() {
-        let error = format!("Operator `{opr:?}` must be applied to an operand.");
+        let error = ErrorKind::MissingOperand { operator: opr.code.to_string() };
         let invalid = Tree::unary_opr_app(opr, rhs);
         return invalid.with_error(error);
     }
@@ -980,9 +1197,9 @@ pub fn to_ast(token: Token) -> Tree {
         token::Variant::Wildcard(wildcard) => Tree::wildcard(token.with_variant(wildcard), default()),
         token::Variant::AutoScope(t) => Tree::auto_scope(token.with_variant(t)),
         token::Variant::OpenSymbol(s) =>
-            Tree::group(Some(token.with_variant(s)), default(), default()).with_error("Unmatched delimiter"),
+            Tree::group(Some(token.with_variant(s)), default(), default()).with_error(ErrorKind::UnmatchedDelimiter),
         token::Variant::CloseSymbol(s) =>
-            Tree::group(default(), default(), Some(token.with_variant(s))).with_error("Unmatched delimiter"),
+            Tree::group(default(), default(), Some(token.with_variant(s))).with_error(ErrorKind::UnmatchedDelimiter),
         // These should be unreachable: They are handled when assembling items into blocks,
         // before parsing proper.
         token::Variant::Newline(_)
@@ -993,10 +1210,10 @@ pub fn to_ast(token: Token) -> Tree {
         | token::Variant::Operator(_)
         // Map an error case in the lexer to an error in the AST.
         | token::Variant::Invalid(_) => {
-            let message = format!("Unexpected token: {token:?}");
+            let error = ErrorKind::UnexpectedToken { token: token.code.to_string() };
             let ident = token::variant::Ident(false, 0, false, false, false);
             let value = Tree::ident(token.with_variant(ident));
-            Tree::with_error(value, message)
+            Tree::with_error(value, error)
         }
     }
 }
@@ -1079,6 +1296,12 @@ pub trait ItemVisitor<'s, 'a>: Visitor {
     fn visit_item(&mut self, ast: item::Ref<'s, 'a>) -> bool;
 }
 
+/// The visitor trait allowing for [`Item`] mutable traversal.
+#[allow(missing_docs)]
+pub trait ItemVisitorMut<'s>: Visitor {
+    fn visit_item_mut(&mut self, ast: item::RefMut<'s, '_>) -> bool;
+}
+
 macro_rules! define_visitor {
     ($name:ident, $visit:ident) => {
         define_visitor_no_mut! {$name, $visit}
@@ -1186,7 +1409,7 @@ macro_rules! define_visitor_for_tokens {
 
 define_visitor!(Tree, visit);
 define_visitor!(Span, visit_span);
-define_visitor_no_mut!(Item, visit_item);
+define_visitor!(Item, visit_item);
 
 crate::with_token_definition!(define_visitor_for_tokens());
 
@@ -1200,6 +1423,7 @@ macro_rules! spanless_leaf_impls {
         impl<'a, 's> SpanVisitable<'s, 'a> for $ty {}
         impl<'a, 's> SpanVisitableMut<'s, 'a> for $ty {}
         impl<'a, 's> ItemVisitable<'s, 'a> for $ty {}
+        impl<'a, 's> ItemVisitableMut<'s, 'a> for $ty {}
         impl<'s> span::Builder<'s> for $ty {
             fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
                 span
@@ -1211,6 +1435,7 @@ macro_rules! spanless_leaf_impls {
 spanless_leaf_impls!(u32);
 spanless_leaf_impls!(bool);
 spanless_leaf_impls!(VisibleOffset);
+spanless_leaf_impls!(ErrorKind);
 
 
 // === TreeVisitable special cases ===
@@ -1292,6 +1517,21 @@ where &'a Token<'s, T>: Into<token::Ref<'s, 'a>>
     }
 }
 
+impl<'s, 'a> ItemVisitableMut<'s, 'a> for Tree<'s> {
+    fn visit_item_mut<V: ItemVisitorMut<'s>>(&'a mut self, visitor: &mut V) {
+        if visitor.visit_item_mut(item::RefMut::Tree(&mut *self)) {
+            self.variant.visit_item_mut(visitor)
+        }
+    }
+}
+
+impl<'s, 'a, T> ItemVisitableMut<'s, 'a> for Token<'s, T> {
+    fn visit_item_mut<V: ItemVisitorMut<'s>>(&'a mut self, visitor: &mut V) {
+        let Token { left_offset, code, .. } = self;
+        visitor.visit_item_mut(item::RefMut::Token { left_offset, code });
+    }
+}
+
 
 // === String ===
 
@@ -1300,6 +1540,7 @@ impl<'s, 'a> TreeVisitableMut<'s, 'a> for String {}
 impl<'a, 's> SpanVisitable<'s, 'a> for String {}
 impl<'a, 's> SpanVisitableMut<'s, 'a> for String {}
 impl<'a, 's> ItemVisitable<'s, 'a> for String {}
+impl<'a, 's> ItemVisitableMut<'s, 'a> for String {}
 impl<'s> span::Builder<'s> for String {
     fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
         span
@@ -1311,6 +1552,7 @@ impl<'s, 'a> TreeVisitableMut<'s, 'a> for Cow<'static, str> {}
 impl<'a, 's> SpanVisitable<'s, 'a> for Cow<'static, str> {}
 impl<'a, 's> SpanVisitableMut<'s, 'a> for Cow<'static, str> {}
 impl<'a, 's> ItemVisitable<'s, 'a> for Cow<'static, str> {}
+impl<'a, 's> ItemVisitableMut<'s, 'a> for Cow<'static, str> {}
 impl<'s> span::Builder<'s> for Cow<'static, str> {
     fn add_to_span(&mut self, span: Span<'s>) -> Span<'s> {
         span
@@ -1326,7 +1568,7 @@ impl<'s> span::Builder<'s> for Cow<'static, str> {
 /// A visitor collecting code representation of AST nodes.
 #[derive(Debug, Default)]
 #[allow(missing_docs)]
-struct CodePrinterVisitor {
+pub(crate) struct CodePrinterVisitor {
     pub code: String,
 }
 
@@ -41,7 +41,8 @@ use syn::Variant;
 /// ======================
 use quote::ToTokens;
 
-/// Implements [`TreeVisitable`], [`TreeVisitableMut`], [`SpanVisitable`], and [`SpanVisitableMut`].
+/// Implements [`TreeVisitable`], [`TreeVisitableMut`], [`SpanVisitable`], [`SpanVisitableMut`],
+/// [`ItemVisitable`], and [`ItemVisitableMut`].
 /// These traits are defined in the [`crate::ast`] module. Macros in this module hardcode the names
 /// of the traits and are not implemented in a generic way because the current Rust implementation
 /// does not understand generic definition. See the [`crate::ast`] module to learn more about the
@@ -56,6 +57,7 @@ pub fn derive_visitor(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     let body_span = gen_body(quote!(SpanVisitable::visit_span), &decl.data, false);
     let body_span_mut = gen_body(quote!(SpanVisitableMut::visit_span_mut), &decl.data, true);
     let body_item = gen_body(quote!(ItemVisitable::visit_item), &decl.data, false);
+    let body_item_mut = gen_body(quote!(ItemVisitableMut::visit_item_mut), &decl.data, true);
 
     let impl_generics_vec: Vec<_> = impl_generics.to_token_stream().into_iter().collect();
     let impl_generics_len = impl_generics_vec.len();
@@ -111,6 +113,14 @@ pub fn derive_visitor(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                 visitor.after_visiting_children();
             }
         }
+
+        impl #impl_generics ItemVisitableMut #impl_generics for #ident #ty_generics {
+            fn visit_item_mut<T: ItemVisitorMut<'s>>(&'a mut self, visitor:&mut T) {
+                visitor.before_visiting_children();
+                #body_item_mut
+                visitor.after_visiting_children();
+            }
+        }
     };
 
     // #[allow(missing_docs)]
@@ -749,7 +749,6 @@ impl Rope {
     ) -> Location<Utf16CodeUnit> {
         let line_start = self.line_offset_snapped(location.line);
         let position = self.location_offset_snapped(location);
-        println!("{line_start} {position}");
         let line_fragment_before = self.rope.slice(line_start.value..position.value);
         let line = location.line;
         let offset = Utf16CodeUnit::from(line_fragment_before.measure::<Utf16CodeUnitsMetric>());